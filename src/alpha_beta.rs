@@ -0,0 +1,369 @@
+use super::*;
+
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+/// The score of a win at the current node.
+/// A win `n` plies away scores `WIN_SCORE - n`,
+/// and a loss `n` plies away scores `-(WIN_SCORE - n)`.
+pub const WIN_SCORE: i32 = 1_000_000;
+
+/// Scores with an absolute value above this are forced wins or losses.
+/// Evaluations are clamped below it.
+pub const MIN_WIN_SCORE: i32 = WIN_SCORE - 1_000;
+
+/// The depth `AlphaBetaEngine` searches to,
+/// unless `AlphaBetaEngine::max_depth` says otherwise.
+pub const DEFAULT_MAX_DEPTH: u8 = 16;
+
+/// How often (in nodes) the engine checks the clock.
+const TIME_CHECK_INTERVAL: u64 = 1024;
+
+/// The result of an `AlphaBetaEngine` search,
+/// from the perspective of the root's active player.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AlphaBetaResult {
+    /// The child the engine recommends,
+    /// or `None` if the root is terminal.
+    pub best_move: Option<State>,
+
+    pub score: i32,
+
+    /// The depth of the deepest completed iteration.
+    /// This is zero if the limits were reached during the first iteration,
+    /// in which case `best_move` comes from that partial iteration
+    /// and `score` is zero.
+    pub depth: u8,
+
+    pub node_count: u64,
+}
+
+impl AlphaBetaResult {
+    /// Returns the outcome that `score` proves,
+    /// or `None` if `score` is a heuristic evaluation.
+    ///
+    /// Also returns `None` if the win or loss is
+    /// more than `OUTCOME_DELAY_COUNT - 1` plies away,
+    /// since `Outcome` cannot encode it.
+    /// Only searches deeper than that can find such wins.
+    pub fn proven_outcome(self) -> Option<Outcome> {
+        if self.score.abs() <= MIN_WIN_SCORE {
+            return None;
+        }
+
+        let delay = WIN_SCORE - self.score.abs();
+        if delay as usize >= OUTCOME_DELAY_COUNT {
+            return None;
+        }
+
+        Some(if self.score > 0 {
+            Outcome::win_in(delay as u8)
+        } else {
+            Outcome::loss_in(delay as u8)
+        })
+    }
+}
+
+/// A negamax search with alpha-beta pruning, iterative deepening,
+/// and a fixed-size transposition table.
+///
+/// Unlike the solver, this only needs `State::visit_children`,
+/// so it works where the full solution is unavailable.
+/// Repetitions of a state on the current line score as draws.
+#[derive(Clone, Debug)]
+pub struct AlphaBetaEngine {
+    table: Vec<TableEntry>,
+    max_depth: u8,
+    node_limit: u64,
+    time_limit: Option<Duration>,
+    evaluator: fn(State) -> i32,
+
+    node_count: u64,
+    deadline: Option<Instant>,
+    is_aborted: bool,
+
+    /// The states on the current line.
+    path: HashSet<State>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Bound {
+    Exact,
+    Lower,
+    Upper,
+}
+
+/// A transposition table entry.
+/// Entries are replaced whenever another state maps to the same slot.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct TableEntry {
+    /// `State(0)` means the entry is empty (since states are never zero).
+    state: State,
+
+    depth: u8,
+    bound: Bound,
+
+    /// Win and loss scores are relative to this node
+    /// (rather than the root),
+    /// so they stay valid at any ply.
+    score: i32,
+
+    best_child: State,
+}
+
+impl TableEntry {
+    const EMPTY: Self = Self {
+        state: State(0),
+        depth: 0,
+        bound: Bound::Exact,
+        score: 0,
+        best_child: State(0),
+    };
+}
+
+impl AlphaBetaEngine {
+    /// `table_capacity` is the number of transposition table entries.
+    /// It must be positive.
+    pub fn new(table_capacity: usize) -> Self {
+        assert!(table_capacity > 0, "Table capacity must be positive.");
+
+        Self {
+            table: vec![TableEntry::EMPTY; table_capacity],
+            max_depth: DEFAULT_MAX_DEPTH,
+            node_limit: u64::MAX,
+            time_limit: None,
+            evaluator: evaluate,
+
+            node_count: 0,
+            deadline: None,
+            is_aborted: false,
+            path: HashSet::new(),
+        }
+    }
+
+    /// The depth (in plies) of the last iteration.
+    /// It must be positive.
+    pub fn max_depth(mut self, max_depth: u8) -> Self {
+        assert!(max_depth > 0, "Max depth must be positive.");
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Each search stops after visiting this many nodes.
+    pub fn node_limit(mut self, node_limit: u64) -> Self {
+        self.node_limit = node_limit;
+        self
+    }
+
+    /// Each search stops after roughly this long.
+    /// Without a time limit, the engine never reads the clock.
+    pub fn time_limit(mut self, time_limit: Duration) -> Self {
+        self.time_limit = Some(time_limit);
+        self
+    }
+
+    /// Scores nonterminal leaves from the perspective of their active player.
+    /// The default is `evaluate`.
+    pub fn evaluator(mut self, evaluator: fn(State) -> i32) -> Self {
+        self.evaluator = evaluator;
+        self
+    }
+
+    /// Clears the transposition table,
+    /// so the next search does not depend on earlier ones.
+    pub fn clear_table(&mut self) {
+        self.table.fill(TableEntry::EMPTY);
+    }
+
+    pub fn search(&mut self, root: State) -> AlphaBetaResult {
+        self.node_count = 0;
+        self.deadline = self.time_limit.map(|limit| Instant::now() + limit);
+        self.is_aborted = false;
+        self.path.clear();
+
+        let mut result = AlphaBetaResult {
+            best_move: None,
+            score: self.leaf_score(root, 0).unwrap_or(0),
+            depth: 0,
+            node_count: 0,
+        };
+
+        if !root.is_terminal() {
+            for depth in 1..=self.max_depth {
+                let score = self.negamax(root, depth, -WIN_SCORE, WIN_SCORE, 0);
+                let best_move = self.probe(root).map(|entry| entry.best_child);
+
+                if self.is_aborted {
+                    // A partial first iteration is better than nothing,
+                    // but its score may come from an unsearched child.
+                    if result.best_move.is_none() {
+                        result.best_move = best_move.or_else(|| root.children().first().copied());
+                    }
+                    break;
+                }
+
+                result.best_move = best_move;
+                result.score = score;
+                result.depth = depth;
+
+                // Deeper iterations cannot change a forced result.
+                if score.abs() > MIN_WIN_SCORE {
+                    break;
+                }
+            }
+        }
+
+        result.node_count = self.node_count;
+        result
+    }
+
+    fn negamax(&mut self, state: State, depth: u8, mut alpha: i32, beta: i32, ply: u8) -> i32 {
+        self.node_count += 1;
+        if self.should_abort() {
+            self.is_aborted = true;
+            return 0;
+        }
+
+        if let Some(score) = self.leaf_score(state, ply) {
+            return score;
+        }
+
+        if depth == 0 {
+            return (self.evaluator)(state).clamp(-MIN_WIN_SCORE, MIN_WIN_SCORE);
+        }
+
+        let entry = self.probe(state);
+        if let Some(entry) = entry.filter(|entry| entry.depth >= depth) {
+            let score = score_from_table(entry.score, ply);
+            let is_cutoff = match entry.bound {
+                Bound::Exact => true,
+                Bound::Lower => score >= beta,
+                Bound::Upper => score <= alpha,
+            };
+            if is_cutoff {
+                return score;
+            }
+        }
+
+        let children = ordered_children(state, entry.map(|entry| entry.best_child));
+        let original_alpha = alpha;
+        let mut best_score = -WIN_SCORE;
+        let mut best_child = children[0];
+
+        self.path.insert(state);
+        for child in children {
+            let score = -self.negamax(child, depth - 1, -beta, -alpha, ply + 1);
+            if self.is_aborted {
+                break;
+            }
+
+            if score > best_score {
+                best_score = score;
+                best_child = child;
+            }
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+        self.path.remove(&state);
+
+        if self.is_aborted {
+            return best_score;
+        }
+
+        let bound = if best_score <= original_alpha {
+            Bound::Upper
+        } else if best_score >= beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        self.store(TableEntry {
+            state,
+            depth,
+            bound,
+            score: score_to_table(best_score, ply),
+            best_child,
+        });
+
+        best_score
+    }
+
+    /// Returns the score of a terminal or repeated state,
+    /// or `None` if the state needs to be searched.
+    fn leaf_score(&self, state: State, ply: u8) -> Option<i32> {
+        match state.terminality() {
+            Terminality::Win => Some(WIN_SCORE - ply as i32),
+            Terminality::Loss => Some(-(WIN_SCORE - ply as i32)),
+            Terminality::Nonterminal => self.path.contains(&state).then_some(0),
+        }
+    }
+
+    fn should_abort(&self) -> bool {
+        if self.node_count > self.node_limit {
+            return true;
+        }
+
+        match self.deadline {
+            Some(deadline) if self.node_count.is_multiple_of(TIME_CHECK_INTERVAL) => {
+                Instant::now() >= deadline
+            }
+            _ => false,
+        }
+    }
+
+    fn probe(&self, state: State) -> Option<TableEntry> {
+        let entry = self.table[self.slot(state)];
+        (entry.state == state).then_some(entry)
+    }
+
+    fn store(&mut self, entry: TableEntry) {
+        let slot = self.slot(entry.state);
+        self.table[slot] = entry;
+    }
+
+    fn slot(&self, state: State) -> usize {
+        // Fibonacci hashing spreads nearby states across the table.
+        (state.0.wrapping_mul(0x9E37_79B9_7F4A_7C15) >> 16) as usize % self.table.len()
+    }
+}
+
+/// Returns the children of `state`,
+/// starting with `table_move` (if it is a child),
+/// followed by captures, followed by everything else.
+fn ordered_children(state: State, table_move: Option<State>) -> Vec<State> {
+    let mut children = state.children();
+    children.sort_by_key(|&child| {
+        if Some(child) == table_move {
+            0
+        } else if state.is_capture(child) {
+            1
+        } else {
+            2
+        }
+    });
+    children
+}
+
+/// Converts a win or loss score from root-relative to node-relative.
+fn score_to_table(score: i32, ply: u8) -> i32 {
+    if score > MIN_WIN_SCORE {
+        score + ply as i32
+    } else if score < -MIN_WIN_SCORE {
+        score - ply as i32
+    } else {
+        score
+    }
+}
+
+/// Converts a win or loss score from node-relative to root-relative.
+fn score_from_table(score: i32, ply: u8) -> i32 {
+    if score > MIN_WIN_SCORE {
+        score - ply as i32
+    } else if score < -MIN_WIN_SCORE {
+        score + ply as i32
+    } else {
+        score
+    }
+}
@@ -2,19 +2,123 @@ use super::*;
 
 use std::collections::VecDeque;
 
+/// The progress of an unfinished `initial_stat_map` call.
+#[derive(Clone, Debug)]
+pub struct InitialStatsSnapshot {
+    map: StateMap<StateStats>,
+
+    /// The index of the first top-level bucket
+    /// of the reachable set that has not been visited.
+    next_bucket: usize,
+
+    processed: u64,
+}
+
+/// The progress of an unfinished `compute_stats` call.
+///
+/// The partially computed stats live in the map itself,
+/// so the snapshot only holds the queue of states
+/// whose outcomes are known but not yet reported to their parents.
+#[derive(Clone, Debug)]
+pub struct RetrogradeSnapshot {
+    known_queue: VecDeque<(State, Outcome)>,
+    processed: u64,
+}
+
+impl InitialStatsSnapshot {
+    /// Returns a snapshot of a call that has not started yet.
+    pub fn new() -> Self {
+        Self {
+            map: StateMap::empty(),
+            next_bucket: 0,
+            processed: 0,
+        }
+    }
+}
+
+impl Default for InitialStatsSnapshot {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RetrogradeSnapshot {
+    /// Returns a snapshot of a call that has not started yet.
+    ///
+    /// The map must be initialized as described in `compute_stats`.
+    pub fn new(map: &StateMap<StateStats>) -> Self {
+        let mut known_queue = VecDeque::new();
+        add_terminal_states(map, &mut known_queue);
+
+        Self {
+            known_queue,
+            processed: 0,
+        }
+    }
+
+    /// Returns the number of states whose outcomes
+    /// have not been reported to their parents yet.
+    pub fn queue_len(&self) -> usize {
+        self.known_queue.len()
+    }
+}
+
 pub fn initial_stat_map(
     reachable: &StateSet,
-    mut on_state_processed: impl FnMut(State),
+    observer: &mut (impl ProgressObserver + ?Sized),
 ) -> StateMap<StateStats> {
-    let mut map = StateMap::empty();
+    resume_initial_stat_map(
+        reachable,
+        InitialStatsSnapshot::new(),
+        observer,
+        &CancellationToken::new(),
+    )
+    .expect("A new token is never cancelled.")
+}
+
+/// Continues `initial_stat_map` from `snapshot`,
+/// checking `token` before each top-level bucket of `reachable`.
+///
+/// `reachable` must be the set the snapshot was taken from.
+pub fn resume_initial_stat_map(
+    reachable: &StateSet,
+    snapshot: InitialStatsSnapshot,
+    observer: &mut (impl ProgressObserver + ?Sized),
+    token: &CancellationToken,
+) -> Result<StateMap<StateStats>, Cancelled<InitialStatsSnapshot>> {
+    let InitialStatsSnapshot {
+        mut map,
+        next_bucket,
+        processed,
+    } = snapshot;
+    let mut tracker = PhaseTracker::resume(
+        observer,
+        SolverPhase::InitialStats,
+        Some(reachable.len()),
+        processed,
+    );
+
+    for bucket in next_bucket..TOP_LEVEL_BUCKET_COUNT {
+        if token.should_stop() {
+            return Err(Cancelled {
+                snapshot: InitialStatsSnapshot {
+                    map,
+                    next_bucket: bucket,
+                    processed: tracker.processed(),
+                },
+            });
+        }
 
-    reachable.visit_in_order(|state| {
-        map.add(state, state.guess_stats());
+        reachable.visit_shard_in_order(bucket, TOP_LEVEL_BUCKET_COUNT, |state| {
+            map.add(state, state.guess_stats());
 
-        on_state_processed(state);
-    });
+            tracker.tick(None);
+        });
+    }
+
+    tracker.finish(None);
 
-    map
+    Ok(map)
 }
 
 /// This function will solve the game when provided
@@ -24,69 +128,84 @@ pub fn initial_stat_map(
 /// is mapped to `s.guess_stats()`.
 pub fn compute_stats(
     map: &mut StateMap<StateStats>,
-    progress: &mut Progress,
-    mut on_state_processed: impl FnMut(&Progress) -> bool,
+    observer: &mut (impl ProgressObserver + ?Sized),
 ) {
-    let mut known_queue: VecDeque<(State, Outcome)> = VecDeque::new();
-    add_terminal_states(map, &mut known_queue);
+    let snapshot = RetrogradeSnapshot::new(map);
+    resume_compute_stats(map, snapshot, observer, &CancellationToken::new())
+        .expect("A new token is never cancelled.");
+}
 
-    while let Some((child, child_outcome)) = known_queue.pop_front() {
-        if child_outcome.0 < 0 {
-            visit_parents(
-                child,
-                map,
-                progress,
-                |original_parent, parent_stats_mut, progress| {
-                    *parent_stats_mut = parent_stats_mut
-                        .record_child_outcome(child_outcome)
-                        .set_required_child_report_count_to_zero();
+/// Continues `compute_stats` from `snapshot`,
+/// checking `token` before reporting each known outcome.
+///
+/// `map` must be the map the snapshot was taken from,
+/// unchanged since the snapshot was taken.
+pub fn resume_compute_stats(
+    map: &mut StateMap<StateStats>,
+    snapshot: RetrogradeSnapshot,
+    observer: &mut (impl ProgressObserver + ?Sized),
+    token: &CancellationToken,
+) -> Result<(), Cancelled<RetrogradeSnapshot>> {
+    let RetrogradeSnapshot {
+        mut known_queue,
+        processed,
+    } = snapshot;
+    let mut tracker = PhaseTracker::resume(
+        observer,
+        SolverPhase::Retrograde,
+        Some(map.len()),
+        processed,
+    );
+
+    while let Some(&(child, child_outcome)) = known_queue.front() {
+        if token.should_stop() {
+            return Err(Cancelled {
+                snapshot: RetrogradeSnapshot {
+                    known_queue,
+                    processed: tracker.processed(),
+                },
+            });
+        }
 
-                    known_queue.push_back((original_parent, parent_stats_mut.best_known_outcome()));
+        known_queue.pop_front();
 
-                    progress.queue_pushes += 1;
-                    progress.winning_parent_conclusions += 1;
-                },
-            );
+        if child_outcome.0 < 0 {
+            visit_parents(child, map, |original_parent, parent_stats_mut| {
+                *parent_stats_mut = parent_stats_mut
+                    .record_child_outcome(child_outcome)
+                    .set_required_child_report_count_to_zero();
+
+                known_queue.push_back((original_parent, parent_stats_mut.best_known_outcome()));
+            });
         } else {
-            visit_parents(
-                child,
-                map,
-                progress,
-                |original_parent, parent_stats_mut, progress| {
-                    *parent_stats_mut = parent_stats_mut
-                        .record_child_outcome(child_outcome)
-                        .decrement_required_child_report_count();
-
-                    if parent_stats_mut.required_child_report_count() == 0 {
-                        known_queue
-                            .push_back((original_parent, parent_stats_mut.best_known_outcome()));
-
-                        progress.queue_pushes += 1;
-                        progress.losing_parent_conclusions += 1;
-                    } else {
-                        progress.uncertain_parent_conclusions += 1;
-                    }
-                },
-            );
-        }
+            visit_parents(child, map, |original_parent, parent_stats_mut| {
+                *parent_stats_mut = parent_stats_mut
+                    .record_child_outcome(child_outcome)
+                    .decrement_required_child_report_count();
 
-        if on_state_processed(&progress) {
-            *progress = Progress::default();
+                if parent_stats_mut.required_child_report_count() == 0 {
+                    known_queue.push_back((original_parent, parent_stats_mut.best_known_outcome()));
+                }
+            });
         }
+
+        tracker.tick(Some(known_queue.len() as u64));
     }
+
+    tracker.finish(Some(0));
+
+    Ok(())
 }
 
 #[inline(always)]
 fn visit_parents(
     child: State,
     map: &mut StateMap<StateStats>,
-    progress: &mut Progress,
-    mut visitor: impl FnMut(State, &mut StateStats, &mut Progress),
+    mut visitor: impl FnMut(State, &mut StateStats),
 ) {
     child.visit_parents(|parent| {
         let Some(parent_stats_mut) = map.get_mut(parent) else {
             // It's possible that a theoretical parent is actually unreachable.
-            progress.unreachable_parent_visits += 1;
             return;
         };
         let original_parent_stats = *parent_stats_mut;
@@ -108,13 +227,10 @@ fn visit_parents(
             // It's possible that the parent has already determined
             // its best outcome before seeing all of its children's best outcomes.
             // This happens when a child reports a loss.
-            progress.already_solved_parent_visits += 1;
             return;
         }
 
-        progress.unsolved_parent_visits += 1;
-
-        visitor(parent, parent_stats_mut, progress);
+        visitor(parent, parent_stats_mut);
     });
 }
 
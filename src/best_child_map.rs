@@ -2,25 +2,297 @@ use super::*;
 
 use crate::pretty::*;
 
+use std::sync::mpsc;
+
+/// A rule for choosing between children with the same outcome.
+///
+/// By default, `best_child_map` picks whichever tied child
+/// `State::visit_children` visits first.
+/// Tie-breaks are applied in order,
+/// each narrowing the children the previous ones left tied,
+/// and any remaining tie is broken by visiting order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TieBreak {
+    /// Prefer moves that capture a piece.
+    PreferCaptures,
+
+    /// Prefer moves that leave the opponent
+    /// the fewest replies that do not lose.
+    FewestNonLosingReplies,
+
+    /// If the best outcome is a draw, prefer moves that leave the opponent
+    /// the most replies that lose.
+    /// This has no effect on wins and losses.
+    MostLosingRepliesInDraws,
+}
+
+/// The progress of an unfinished `best_child_map`
+/// or `best_child_map_parallel` call.
+/// Either function can resume a snapshot taken by the other.
+#[derive(Clone, Debug)]
+pub struct BestChildrenSnapshot {
+    out: StateMap<StateAndStats>,
+
+    /// The tie-breaks are part of the snapshot
+    /// so that a resumed call cannot mix policies.
+    tie_breaks: Vec<TieBreak>,
+
+    /// Whether each top-level bucket of the stats map has been visited.
+    completed_buckets: Vec<bool>,
+
+    processed: u64,
+}
+
+impl BestChildrenSnapshot {
+    /// Returns a snapshot of a call that has not started yet.
+    pub fn new() -> Self {
+        Self::with_tie_breaks(vec![])
+    }
+
+    /// Returns a snapshot of a call that has not started yet,
+    /// which will break ties between best children with `tie_breaks`.
+    pub fn with_tie_breaks(tie_breaks: Vec<TieBreak>) -> Self {
+        Self {
+            out: StateMap::empty(),
+            tie_breaks,
+            completed_buckets: vec![false; TOP_LEVEL_BUCKET_COUNT],
+            processed: 0,
+        }
+    }
+
+    pub fn tie_breaks(&self) -> &[TieBreak] {
+        &self.tie_breaks
+    }
+}
+
+impl Default for BestChildrenSnapshot {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 pub fn best_child_map(
     map: &StateMap<StateStats>,
-    mut on_state_processed: impl FnMut(State),
+    observer: &mut (impl ProgressObserver + ?Sized),
+) -> StateMap<StateAndStats> {
+    best_child_map_with_tie_breaks(map, vec![], observer)
+}
+
+/// This is equivalent to `best_child_map`,
+/// but breaks ties between best children with `tie_breaks`
+/// (see `TieBreak`).
+pub fn best_child_map_with_tie_breaks(
+    map: &StateMap<StateStats>,
+    tie_breaks: Vec<TieBreak>,
+    observer: &mut (impl ProgressObserver + ?Sized),
 ) -> StateMap<StateAndStats> {
-    let mut out = StateMap::empty();
+    resume_best_child_map(
+        map,
+        BestChildrenSnapshot::with_tie_breaks(tie_breaks),
+        observer,
+        &CancellationToken::new(),
+    )
+    .expect("A new token is never cancelled.")
+}
+
+/// Continues `best_child_map` from `snapshot`,
+/// checking `token` before each top-level bucket of `map`.
+///
+/// `map` must be the map the snapshot was taken from.
+pub fn resume_best_child_map(
+    map: &StateMap<StateStats>,
+    snapshot: BestChildrenSnapshot,
+    observer: &mut (impl ProgressObserver + ?Sized),
+    token: &CancellationToken,
+) -> Result<StateMap<StateAndStats>, Cancelled<BestChildrenSnapshot>> {
+    let BestChildrenSnapshot {
+        mut out,
+        tie_breaks,
+        mut completed_buckets,
+        processed,
+    } = snapshot;
+    let mut tracker = PhaseTracker::resume(
+        observer,
+        SolverPhase::BestChildren,
+        Some(map.len()),
+        processed,
+    );
+
+    for bucket in 0..TOP_LEVEL_BUCKET_COUNT {
+        if completed_buckets[bucket] {
+            continue;
+        }
 
-    map.visit_in_key_order(|parent, _| {
-        if let Some(best_child) = parent.best_child(map) {
-            out.add(parent, best_child);
+        if token.should_stop() {
+            return Err(Cancelled {
+                snapshot: BestChildrenSnapshot {
+                    out,
+                    tie_breaks,
+                    completed_buckets,
+                    processed: tracker.processed(),
+                },
+            });
         }
 
-        on_state_processed(parent);
+        map.visit_shard_in_key_order(bucket, TOP_LEVEL_BUCKET_COUNT, |parent, _| {
+            if let Some(best_child) = parent.best_child(map, &tie_breaks) {
+                out.add(parent, best_child);
+            }
+
+            tracker.tick(None);
+        });
+        completed_buckets[bucket] = true;
+    }
+
+    tracker.finish(None);
+
+    Ok(out)
+}
+
+/// This is equivalent to `best_child_map`,
+/// but splits the work across `thread_count` threads.
+///
+/// The observer is only called on the calling thread,
+/// as each shard of the map finishes.
+pub fn best_child_map_parallel(
+    map: &StateMap<StateStats>,
+    thread_count: usize,
+    observer: &mut (impl ProgressObserver + ?Sized),
+) -> StateMap<StateAndStats> {
+    resume_best_child_map_parallel(
+        map,
+        BestChildrenSnapshot::new(),
+        thread_count,
+        observer,
+        &CancellationToken::new(),
+    )
+    .expect("A new token is never cancelled.")
+}
+
+/// Continues `best_child_map_parallel` from `snapshot`.
+/// Each thread checks `token` before each top-level bucket of `map`.
+///
+/// `map` must be the map the snapshot was taken from.
+pub fn resume_best_child_map_parallel(
+    map: &StateMap<StateStats>,
+    snapshot: BestChildrenSnapshot,
+    thread_count: usize,
+    observer: &mut (impl ProgressObserver + ?Sized),
+    token: &CancellationToken,
+) -> Result<StateMap<StateAndStats>, Cancelled<BestChildrenSnapshot>> {
+    assert!(thread_count > 0, "Thread count must be positive.");
+
+    let BestChildrenSnapshot {
+        mut out,
+        tie_breaks,
+        mut completed_buckets,
+        processed,
+    } = snapshot;
+    let mut tracker = PhaseTracker::resume(
+        observer,
+        SolverPhase::BestChildren,
+        Some(map.len()),
+        processed,
+    );
+
+    // We use many more shards than threads,
+    // so that progress is reported regularly.
+    // Each shard is a contiguous range of top-level buckets.
+    let shard_count = thread_count * 64;
+    let previously_completed_buckets = completed_buckets.clone();
+
+    std::thread::scope(|scope| {
+        let (sender, receiver) = mpsc::channel();
+
+        for thread_index in 0..thread_count {
+            let sender = sender.clone();
+            let previously_completed_buckets = &previously_completed_buckets;
+            let tie_breaks = &tie_breaks;
+            scope.spawn(move || {
+                for shard_index in (thread_index..shard_count).step_by(thread_count) {
+                    let start = TOP_LEVEL_BUCKET_COUNT * shard_index / shard_count;
+                    let end = TOP_LEVEL_BUCKET_COUNT * (shard_index + 1) / shard_count;
+
+                    let mut shard_out = StateMap::empty();
+                    let mut shard_completed_buckets = vec![];
+                    let mut state_count = 0;
+                    let mut was_cancelled = false;
+
+                    for (bucket, &is_completed) in previously_completed_buckets
+                        .iter()
+                        .enumerate()
+                        .take(end)
+                        .skip(start)
+                    {
+                        if is_completed {
+                            continue;
+                        }
+
+                        if token.should_stop() {
+                            was_cancelled = true;
+                            break;
+                        }
+
+                        map.visit_shard_in_key_order(
+                            bucket,
+                            TOP_LEVEL_BUCKET_COUNT,
+                            |parent, _| {
+                                if let Some(best_child) = parent.best_child(map, tie_breaks) {
+                                    shard_out.add(parent, best_child);
+                                }
+
+                                state_count += 1;
+                            },
+                        );
+                        shard_completed_buckets.push(bucket);
+                    }
+
+                    let message = (shard_out, shard_completed_buckets, state_count);
+                    if sender.send(message).is_err() || was_cancelled {
+                        return;
+                    }
+                }
+            });
+        }
+
+        // Otherwise, the receiver would wait forever.
+        std::mem::drop(sender);
+
+        for (shard_out, shard_completed_buckets, state_count) in receiver {
+            out.union_in_place(shard_out);
+            for bucket in shard_completed_buckets {
+                completed_buckets[bucket] = true;
+            }
+            tracker.add(state_count, None);
+        }
     });
 
-    out
+    if completed_buckets.contains(&false) {
+        return Err(Cancelled {
+            snapshot: BestChildrenSnapshot {
+                out,
+                tie_breaks,
+                completed_buckets,
+                processed: tracker.processed(),
+            },
+        });
+    }
+
+    tracker.finish(None);
+
+    Ok(out)
 }
 
 impl State {
-    fn best_child(self, map: &StateMap<StateStats>) -> Option<StateAndStats> {
+    fn best_child(
+        self,
+        map: &StateMap<StateStats>,
+        tie_breaks: &[TieBreak],
+    ) -> Option<StateAndStats> {
+        if !tie_breaks.is_empty() {
+            return self.best_child_with_tie_breaks(map, tie_breaks);
+        }
+
         let mut best_child = None;
         let mut best_outcome = Outcome(i16::MAX);
         self.visit_children(|child| {
@@ -37,6 +309,77 @@ impl State {
         Some(best_child.with_stats(best_child.stats(map)))
     }
 
+    fn best_child_with_tie_breaks(
+        self,
+        map: &StateMap<StateStats>,
+        tie_breaks: &[TieBreak],
+    ) -> Option<StateAndStats> {
+        let mut best_children = vec![];
+        let mut best_outcome = Outcome(i16::MAX);
+        self.visit_children(|child| {
+            let outcome = child.outcome(map).unwrap_or(Outcome(0));
+            if outcome < best_outcome {
+                best_children.clear();
+                best_outcome = outcome;
+            }
+
+            if outcome == best_outcome {
+                best_children.push(child);
+            }
+        });
+
+        for &tie_break in tie_breaks {
+            if best_children.len() <= 1 {
+                break;
+            }
+
+            // Higher scores are better.
+            let score = |child: State| -> i32 {
+                match tie_break {
+                    TieBreak::PreferCaptures => self.is_capture(child) as i32,
+
+                    TieBreak::FewestNonLosingReplies => {
+                        let (losing, total) = child.losing_reply_count(map);
+                        -((total - losing) as i32)
+                    }
+
+                    TieBreak::MostLosingRepliesInDraws => {
+                        if best_outcome == Outcome::DRAW {
+                            child.losing_reply_count(map).0 as i32
+                        } else {
+                            0
+                        }
+                    }
+                }
+            };
+
+            let scores: Vec<i32> = best_children.iter().map(|&child| score(child)).collect();
+            let best_score = *scores.iter().max().unwrap();
+            let mut scores = scores.into_iter();
+            best_children.retain(|_| scores.next() == Some(best_score));
+        }
+
+        let best_child = *best_children.first()?;
+        Some(best_child.with_stats(best_child.stats(map)))
+    }
+
+    /// Returns the number of the active player's moves
+    /// that lose (i.e., that lead to a win for the opponent),
+    /// followed by the total number of moves.
+    /// Unsolved children are treated as draws.
+    fn losing_reply_count(self, map: &StateMap<StateStats>) -> (u32, u32) {
+        let mut losing = 0;
+        let mut total = 0;
+        self.visit_children(|reply| {
+            if reply.outcome(map).is_some_and(|outcome| outcome.0 > 0) {
+                losing += 1;
+            }
+
+            total += 1;
+        });
+        (losing, total)
+    }
+
     fn outcome(self, map: &StateMap<StateStats>) -> Option<Outcome> {
         self.stats(map).best_outcome()
     }
@@ -51,3 +394,23 @@ impl State {
         stats
     }
 }
+
+impl StateMap<StateAndStats> {
+    /// Returns the best outcome of `state`.
+    /// Terminal states are wins or losses in zero plies.
+    /// Returns `None` if `state` is nonterminal and has no entry.
+    pub fn outcome(&self, state: State) -> Option<Outcome> {
+        match state.terminality() {
+            Terminality::Loss => Some(Outcome::loss_in(0)),
+            Terminality::Win => Some(Outcome::win_in(0)),
+            Terminality::Nonterminal => {
+                let best_child = self.get(state);
+                if best_child.is_null() {
+                    return None;
+                }
+
+                Some(best_child.parent_outcome())
+            }
+        }
+    }
+}
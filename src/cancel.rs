@@ -0,0 +1,129 @@
+use std::fmt::{self, Debug, Display, Formatter};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+
+const RUNNING: u8 = 0;
+const PAUSED: u8 = 1;
+const CANCELLED: u8 = 2;
+
+/// Lets another thread cancel or pause the solver phases
+/// that were given this token.
+///
+/// Clones share the same state,
+/// so a clone can be kept by whoever decides when to stop.
+///
+/// Phases check the token periodically
+/// (between states, or between top-level buckets of the state map),
+/// so they stop shortly after, not immediately.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    shared: Arc<Shared>,
+}
+
+#[derive(Debug, Default)]
+struct Shared {
+    status: AtomicU8,
+    lock: Mutex<()>,
+    status_changed: Condvar,
+}
+
+/// Returned by a phase that was cancelled.
+///
+/// Pass the snapshot to the phase's `resume_*` function
+/// to continue where the phase stopped.
+pub struct Cancelled<S> {
+    pub snapshot: S,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Makes every phase using this token return `Err(Cancelled)`
+    /// at its next check, even if the token is paused.
+    /// Cancellation cannot be undone.
+    pub fn cancel(&self) {
+        self.set_status(CANCELLED);
+    }
+
+    /// Makes every phase using this token block at its next check
+    /// until `resume` or `cancel` is called.
+    /// Does nothing if the token is cancelled.
+    pub fn pause(&self) {
+        let _guard = self.shared.lock.lock().unwrap();
+        let _ = self.shared.status.compare_exchange(
+            RUNNING,
+            PAUSED,
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        );
+    }
+
+    /// Does nothing if the token is cancelled.
+    pub fn resume(&self) {
+        let _guard = self.shared.lock.lock().unwrap();
+        if self
+            .shared
+            .status
+            .compare_exchange(PAUSED, RUNNING, Ordering::AcqRel, Ordering::Acquire)
+            .is_ok()
+        {
+            self.shared.status_changed.notify_all();
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.shared.status.load(Ordering::Acquire) == CANCELLED
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.shared.status.load(Ordering::Acquire) == PAUSED
+    }
+
+    /// Blocks while the token is paused,
+    /// and then returns whether it is cancelled.
+    #[inline(always)]
+    pub(crate) fn should_stop(&self) -> bool {
+        // This is called once per state in some phases,
+        // so the common case must stay a single atomic load.
+        match self.shared.status.load(Ordering::Acquire) {
+            RUNNING => false,
+            _ => self.wait_while_paused(),
+        }
+    }
+
+    #[cold]
+    fn wait_while_paused(&self) -> bool {
+        let mut guard = self.shared.lock.lock().unwrap();
+        while self.shared.status.load(Ordering::Acquire) == PAUSED {
+            guard = self.shared.status_changed.wait(guard).unwrap();
+        }
+
+        self.is_cancelled()
+    }
+
+    fn set_status(&self, status: u8) {
+        // We update the status while holding the lock,
+        // so that a waiting phase cannot miss the notification.
+        let _guard = self.shared.lock.lock().unwrap();
+        self.shared.status.store(status, Ordering::Release);
+        self.shared.status_changed.notify_all();
+    }
+}
+
+// We don't derive `Debug`, since printing a snapshot
+// would print every state in it.
+impl<S> Debug for Cancelled<S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cancelled").finish_non_exhaustive()
+    }
+}
+
+impl<S> Display for Cancelled<S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "The phase was cancelled.")
+    }
+}
+
+impl<S> std::error::Error for Cancelled<S> {}
@@ -0,0 +1,400 @@
+use super::*;
+
+use solution_file::{read_up_to, Checksum};
+
+use std::fmt::{self, Display, Formatter};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+
+/// The first 8 bytes of every checkpoint file.
+pub const CHECKPOINT_MAGIC: [u8; 8] = *b"DBTCKPT\0";
+
+/// The version of the checkpoint file format.
+/// Bump this whenever the layout of the header or the records changes.
+pub const CHECKPOINT_FORMAT_VERSION: u32 = 1;
+
+const BUFFER_CAPACITY: usize = 1 << 20;
+
+/// A checkpoint file stores the result of a completed solver phase,
+/// so that a later solve can skip that phase.
+///
+/// Every checkpoint file starts with a 44-byte header:
+/// the 8-byte `CHECKPOINT_MAGIC`,
+/// followed by the version (`u32`), the ruleset tag (`u32`),
+/// the kind (`u32`), the root state (`u64`),
+/// the entry count (`u64`), and the body checksum (`u64`),
+/// all in little-endian order.
+/// The checksum is the 64-bit FNV-1a hash of the body.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CheckpointKind {
+    /// Each record is a 5-byte state.
+    ReachableStates = 1,
+
+    /// Each record is a 5-byte state followed by its 2-byte `StateStats`.
+    StateStats = 2,
+}
+
+impl CheckpointKind {
+    pub const fn file_name(self) -> &'static str {
+        match self {
+            CheckpointKind::ReachableStates => "reachable_states.ckpt",
+            CheckpointKind::StateStats => "state_stats.ckpt",
+        }
+    }
+
+    const fn record_len(self) -> usize {
+        match self {
+            CheckpointKind::ReachableStates => 5,
+            CheckpointKind::StateStats => 5 + 2,
+        }
+    }
+}
+
+const HEADER_LEN: usize = CHECKPOINT_MAGIC.len() + 3 * 4 + 3 * 8;
+
+#[derive(Debug)]
+pub enum CheckpointError {
+    Io(io::Error),
+
+    /// The file does not start with `CHECKPOINT_MAGIC`.
+    MissingMagic,
+
+    UnsupportedVersion {
+        version: u32,
+    },
+
+    KindMismatch {
+        expected: CheckpointKind,
+        found: u32,
+    },
+
+    /// The checkpoint was written for a solve with a different root.
+    RootMismatch {
+        expected: State,
+        found: State,
+    },
+
+    /// The checkpoint was written for a solve with a different ruleset.
+    RulesetMismatch {
+        expected: Ruleset,
+        found: u32,
+    },
+
+    /// The file ended partway through the header or a record.
+    Truncated {
+        byte_len: u64,
+    },
+
+    EntryCountMismatch {
+        expected: u64,
+        found: u64,
+    },
+
+    ChecksumMismatch {
+        expected: u64,
+        found: u64,
+    },
+
+    InvalidState {
+        record_index: u64,
+    },
+}
+
+impl Display for CheckpointError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            CheckpointError::Io(error) => write!(f, "I/O error: {error}"),
+
+            CheckpointError::MissingMagic => write!(f, "The file is not a checkpoint file."),
+
+            CheckpointError::UnsupportedVersion { version } => write!(
+                f,
+                "The file has format version {version}, but only version {CHECKPOINT_FORMAT_VERSION} is supported."
+            ),
+
+            CheckpointError::KindMismatch { expected, found } => write!(
+                f,
+                "The file has kind {found}, but {expected:?} was expected."
+            ),
+
+            CheckpointError::RootMismatch { expected, found } => write!(
+                f,
+                "The file was written for root {:#x}, but {:#x} was expected.",
+                found.0, expected.0
+            ),
+
+            CheckpointError::RulesetMismatch { expected, found } => write!(
+                f,
+                "The file has ruleset tag {found}, but {expected:?} was expected."
+            ),
+
+            CheckpointError::Truncated { byte_len } => {
+                write!(f, "The file is truncated ({byte_len} bytes).")
+            }
+
+            CheckpointError::EntryCountMismatch { expected, found } => write!(
+                f,
+                "The header declares {expected} records, but the file contains {found} records."
+            ),
+
+            CheckpointError::ChecksumMismatch { expected, found } => write!(
+                f,
+                "The body checksum is {found:#018x}, but the header declares {expected:#018x}."
+            ),
+
+            CheckpointError::InvalidState { record_index } => {
+                write!(f, "Record {record_index} contains an invalid state.")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CheckpointError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CheckpointError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for CheckpointError {
+    fn from(error: io::Error) -> Self {
+        CheckpointError::Io(error)
+    }
+}
+
+pub fn write_reachable_states_checkpoint(
+    reachable: &StateSet,
+    root: State,
+    ruleset: Ruleset,
+    writer: impl Write,
+) -> io::Result<()> {
+    write_checkpoint(
+        CheckpointKind::ReachableStates,
+        root,
+        ruleset,
+        writer,
+        |on_record| {
+            reachable.visit_in_order(|state| on_record(&state.0.to_le_bytes()[0..5]));
+        },
+    )
+}
+
+pub fn read_reachable_states_checkpoint(
+    reader: impl Read,
+    root: State,
+    ruleset: Ruleset,
+) -> Result<StateSet, CheckpointError> {
+    let mut out = StateSet::empty();
+
+    read_checkpoint(
+        CheckpointKind::ReachableStates,
+        root,
+        ruleset,
+        reader,
+        |record, record_index| {
+            let state = decode_state(record, record_index)?;
+            out.add(state);
+            Ok(())
+        },
+    )?;
+
+    Ok(out)
+}
+
+pub fn write_state_stats_checkpoint(
+    map: &StateMap<StateStats>,
+    root: State,
+    ruleset: Ruleset,
+    writer: impl Write,
+) -> io::Result<()> {
+    write_checkpoint(
+        CheckpointKind::StateStats,
+        root,
+        ruleset,
+        writer,
+        |on_record| {
+            map.visit_in_key_order(|state, stats| {
+                let mut record = [0; CheckpointKind::StateStats.record_len()];
+                record[0..5].copy_from_slice(&state.0.to_le_bytes()[0..5]);
+                record[5..7].copy_from_slice(&stats.0.to_le_bytes());
+                on_record(&record);
+            });
+        },
+    )
+}
+
+pub fn read_state_stats_checkpoint(
+    reader: impl Read,
+    root: State,
+    ruleset: Ruleset,
+) -> Result<StateMap<StateStats>, CheckpointError> {
+    let mut out = StateMap::empty();
+
+    read_checkpoint(
+        CheckpointKind::StateStats,
+        root,
+        ruleset,
+        reader,
+        |record, record_index| {
+            let state = decode_state(record, record_index)?;
+            let stats = StateStats(u16::from_le_bytes([record[5], record[6]]));
+            out.add(state, stats);
+            Ok(())
+        },
+    )?;
+
+    Ok(out)
+}
+
+fn decode_state(record: &[u8], record_index: u64) -> Result<State, CheckpointError> {
+    let mut state_bytes = [0; 8];
+    state_bytes[0..5].copy_from_slice(&record[0..5]);
+    let state = State(u64::from_le_bytes(state_bytes));
+
+    if !state.is_constructible() {
+        return Err(CheckpointError::InvalidState { record_index });
+    }
+
+    Ok(state)
+}
+
+/// `visit_records` must pass every record to its argument,
+/// and must do so identically each time it is called.
+fn write_checkpoint(
+    kind: CheckpointKind,
+    root: State,
+    ruleset: Ruleset,
+    writer: impl Write,
+    visit_records: impl Fn(&mut dyn FnMut(&[u8])),
+) -> io::Result<()> {
+    // The header precedes the body,
+    // so we make one pass to count and checksum the records
+    // before making another pass to write them.
+    let mut entry_count: u64 = 0;
+    let mut checksum = Checksum::new();
+    visit_records(&mut |record| {
+        entry_count += 1;
+        checksum.update(record);
+    });
+
+    let mut header = [0; HEADER_LEN];
+    header[0..8].copy_from_slice(&CHECKPOINT_MAGIC);
+    header[8..12].copy_from_slice(&CHECKPOINT_FORMAT_VERSION.to_le_bytes());
+    header[12..16].copy_from_slice(&ruleset.tag().to_le_bytes());
+    header[16..20].copy_from_slice(&(kind as u32).to_le_bytes());
+    header[20..28].copy_from_slice(&root.0.to_le_bytes());
+    header[28..36].copy_from_slice(&entry_count.to_le_bytes());
+    header[36..44].copy_from_slice(&checksum.0.to_le_bytes());
+
+    let mut writer = BufWriter::with_capacity(BUFFER_CAPACITY, writer);
+    writer.write_all(&header)?;
+
+    let mut result = Ok(());
+    visit_records(&mut |record| {
+        if result.is_ok() {
+            result = writer.write_all(record);
+        }
+    });
+    result?;
+
+    writer.flush()
+}
+
+fn read_checkpoint(
+    kind: CheckpointKind,
+    root: State,
+    ruleset: Ruleset,
+    reader: impl Read,
+    mut on_record: impl FnMut(&[u8], u64) -> Result<(), CheckpointError>,
+) -> Result<(), CheckpointError> {
+    let mut reader = BufReader::with_capacity(BUFFER_CAPACITY, reader);
+
+    let mut header = [0; HEADER_LEN];
+    let header_len = read_up_to(&mut reader, &mut header)?;
+
+    if header_len < CHECKPOINT_MAGIC.len() || header[0..8] != CHECKPOINT_MAGIC {
+        return Err(CheckpointError::MissingMagic);
+    }
+
+    if header_len < HEADER_LEN {
+        return Err(CheckpointError::Truncated {
+            byte_len: header_len as u64,
+        });
+    }
+
+    let read_u32 = |start: usize| u32::from_le_bytes(header[start..start + 4].try_into().unwrap());
+    let read_u64 = |start: usize| u64::from_le_bytes(header[start..start + 8].try_into().unwrap());
+
+    let version = read_u32(8);
+    if version != CHECKPOINT_FORMAT_VERSION {
+        return Err(CheckpointError::UnsupportedVersion { version });
+    }
+
+    let ruleset_tag = read_u32(12);
+    if ruleset_tag != ruleset.tag() {
+        return Err(CheckpointError::RulesetMismatch {
+            expected: ruleset,
+            found: ruleset_tag,
+        });
+    }
+
+    let kind_tag = read_u32(16);
+    if kind_tag != kind as u32 {
+        return Err(CheckpointError::KindMismatch {
+            expected: kind,
+            found: kind_tag,
+        });
+    }
+
+    let found_root = State(read_u64(20));
+    if found_root != root {
+        return Err(CheckpointError::RootMismatch {
+            expected: root,
+            found: found_root,
+        });
+    }
+
+    let entry_count = read_u64(28);
+    let expected_checksum = read_u64(36);
+
+    let mut record = [0; CheckpointKind::StateStats.record_len()];
+    let record = &mut record[..kind.record_len()];
+    let mut record_index = 0;
+    let mut checksum = Checksum::new();
+
+    loop {
+        let record_len = read_up_to(&mut reader, record)?;
+
+        if record_len == 0 {
+            break;
+        }
+
+        if record_len < record.len() {
+            return Err(CheckpointError::Truncated {
+                byte_len: (HEADER_LEN + record_len) as u64 + record_index * record.len() as u64,
+            });
+        }
+
+        checksum.update(record);
+        on_record(record, record_index)?;
+        record_index += 1;
+    }
+
+    if record_index != entry_count {
+        return Err(CheckpointError::EntryCountMismatch {
+            expected: entry_count,
+            found: record_index,
+        });
+    }
+
+    if checksum.0 != expected_checksum {
+        return Err(CheckpointError::ChecksumMismatch {
+            expected: expected_checksum,
+            found: checksum.0,
+        });
+    }
+
+    Ok(())
+}
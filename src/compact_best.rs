@@ -0,0 +1,187 @@
+use super::*;
+
+use std::io::{self, Read, Write};
+
+/// This struct stores a state's best outcome
+/// and the index of its best child.
+///
+/// The least significant 9 bits store the state's best outcome
+/// as an `i9` (i.e., a signed two's complement 9-bit integer).
+/// Note that this is the outcome of the state itself
+/// (not the outcome of its best child).
+/// Unsolved states are stored as draws.
+///
+/// The most significant 7 bits store the index of the best child
+/// (as an unsigned 7-bit integer),
+/// where the children are ordered by `State::visit_children`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CompactBest(pub u16);
+
+impl CompactBest {
+    /// The number of bytes used to store each entry of a compact solution file:
+    /// a 5-byte parent key followed by the 2-byte entry.
+    /// The key is needed because the file only lists reachable states
+    /// (see `docs/spec.md` for why it is not indexed by rank instead).
+    pub const RECORD_LEN: usize = 5 + 2;
+
+    pub const fn new(outcome: Outcome, best_child_index: u8) -> Self {
+        Self((outcome.into_i9() as u16) | ((best_child_index as u16) << 9))
+    }
+
+    pub const fn outcome(self) -> Outcome {
+        Outcome::from_i9((self.0 & 0b1_1111_1111) as u64)
+    }
+
+    pub const fn best_child_index(self) -> u8 {
+        (self.0 >> 9) as u8
+    }
+
+    /// Returns `None` if `parent` has fewer children
+    /// than the best child index implies.
+    pub fn best_child(self, parent: State) -> Option<State> {
+        let best_child_index = self.best_child_index() as usize;
+        let mut i = 0;
+        let mut out = None;
+        parent.visit_children(|child| {
+            if i == best_child_index {
+                out = Some(child);
+            }
+
+            i += 1;
+        });
+        out
+    }
+
+    /// This converts the entry back into the
+    /// representation used by `best_child_map`.
+    /// Since unsolved states are stored as draws,
+    /// the best child's required child report count is always zero.
+    pub fn best_child_with_stats(self, parent: State) -> Option<StateAndStats> {
+        let best_child = self.best_child(parent)?;
+        let best_child_outcome = self.outcome().undelay_by_one().invert();
+        Some(best_child.with_stats(StateStats::new(best_child_outcome, 0)))
+    }
+}
+
+impl Null for CompactBest {
+    fn null() -> Self {
+        // `!0` can never represent a valid `CompactBest` value,
+        // because it would imply that the best child has index 127,
+        // which is impossible (see `<StateStats as Null>::null`).
+        Self(!0)
+    }
+}
+
+impl Outcome {
+    /// This is the inverse of `Outcome::delay_by_one`.
+    pub const fn undelay_by_one(self) -> Self {
+        Self(self.0 + self.0.signum())
+    }
+}
+
+impl StateAndStats {
+    /// Interprets `self` as the best child of some parent,
+    /// and returns the parent's best outcome.
+    /// If the best child is unsolved, the parent is a draw.
+    pub const fn parent_outcome(self) -> Outcome {
+        match self.stats().best_outcome() {
+            Some(outcome) => outcome.invert().delay_by_one(),
+            None => Outcome::DRAW,
+        }
+    }
+}
+
+pub fn compact_best_child_map(
+    best_child_map: &StateMap<StateAndStats>,
+    observer: &mut (impl ProgressObserver + ?Sized),
+) -> StateMap<CompactBest> {
+    let mut out = StateMap::empty();
+    let mut tracker = PhaseTracker::resume(
+        observer,
+        SolverPhase::CompactSolution,
+        Some(best_child_map.len()),
+        0,
+    );
+
+    best_child_map.visit_in_key_order(|parent, best_child| {
+        let best_child_state = best_child.state();
+        let mut i = 0;
+        let mut best_child_index = None;
+        parent.visit_children(|child| {
+            if child == best_child_state {
+                best_child_index = Some(i);
+            }
+
+            i += 1;
+        });
+
+        let best_child_index = best_child_index.unwrap_or_else(|| {
+            use crate::pretty::*;
+            panic!(
+                "Best child is not a child of its parent.\n\nPARENT:\n\n{}\n\nBEST CHILD:\n\n{}",
+                parent.pretty(),
+                best_child.pretty()
+            )
+        });
+
+        out.add(
+            parent,
+            CompactBest::new(best_child.parent_outcome(), best_child_index),
+        );
+
+        tracker.tick(None);
+    });
+
+    tracker.finish(None);
+
+    out
+}
+
+/// Each entry is stored as a 5-byte parent state
+/// followed by a 2-byte `CompactBest`, both in little-endian order.
+/// The entries are sorted by their parents.
+pub fn write_compact_solution(
+    map: &StateMap<CompactBest>,
+    mut writer: impl Write,
+) -> io::Result<()> {
+    let mut result = Ok(());
+
+    map.visit_in_key_order(|parent, best| {
+        if result.is_err() {
+            return;
+        }
+
+        let mut record = [0; CompactBest::RECORD_LEN];
+        record[0..5].copy_from_slice(&parent.0.to_le_bytes()[0..5]);
+        record[5..7].copy_from_slice(&best.0.to_le_bytes());
+        result = writer.write_all(&record);
+    });
+
+    result
+}
+
+pub fn read_compact_solution(mut reader: impl Read) -> io::Result<StateMap<CompactBest>> {
+    let mut out = StateMap::empty();
+    let mut record = [0; CompactBest::RECORD_LEN];
+
+    loop {
+        let record_len = solution_file::read_up_to(&mut reader, &mut record)?;
+
+        if record_len == 0 {
+            return Ok(out);
+        }
+
+        if record_len < record.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "The compact solution ends with an incomplete record.",
+            ));
+        }
+
+        let mut parent_bytes = [0; 8];
+        parent_bytes[0..5].copy_from_slice(&record[0..5]);
+        let parent = State(u64::from_le_bytes(parent_bytes));
+        let best = CompactBest(u16::from_le_bytes([record[5], record[6]]));
+        out.add(parent, best);
+    }
+}
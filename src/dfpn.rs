@@ -0,0 +1,366 @@
+use super::*;
+
+use std::collections::HashSet;
+
+/// The number of nodes `DfpnSearch` expands before giving up,
+/// unless `DfpnSearch::node_limit` says otherwise.
+pub const DEFAULT_DFPN_NODE_LIMIT: u64 = 10_000_000;
+
+/// The longest path `DfpnSearch` follows from the root,
+/// unless `DfpnSearch::max_depth` says otherwise.
+pub const DEFAULT_DFPN_MAX_DEPTH: usize = 1_000;
+
+/// Proof and disproof numbers at or above this are infinite.
+const INFINITY: u32 = u32::MAX / 2;
+
+/// The result of a `DfpnSearch`,
+/// from the perspective of the root's active player.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DfpnResult {
+    /// The active player can force a win.
+    /// `winning_move` is a child that keeps the win,
+    /// or `None` if the root is terminal.
+    Win { winning_move: Option<State> },
+
+    /// The passive player can force a win.
+    Loss,
+
+    /// Neither player could be proven to win.
+    /// Either the root is a draw,
+    /// or the search ran out of nodes.
+    Unproven,
+}
+
+/// A depth-first proof-number (df-pn) search
+/// that proves single positions without a database.
+///
+/// The search only uses move generation
+/// and a fixed-size transposition table,
+/// so its memory use does not depend on the position.
+///
+/// Each search asks whether one player (the _prover_) can force a win,
+/// with draws counting as failures.
+/// `search` runs one search for each player.
+/// Repetitions and nodes beyond `max_depth` count as draws,
+/// so every win it reports is sound.
+/// However, a disproof can depend on the path that reached a position
+/// (the graph history interaction problem),
+/// so `DfpnResult::Unproven` does not prove a draw.
+#[derive(Clone, Debug)]
+pub struct DfpnSearch {
+    table: Vec<DfpnEntry>,
+    node_limit: u64,
+    node_count: u64,
+    max_depth: usize,
+
+    /// The keys of the nodes on the current path.
+    path: HashSet<u64>,
+}
+
+/// A transposition table entry.
+/// Entries are replaced whenever another key maps to the same slot.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct DfpnEntry {
+    /// The state in the least significant 40 bits,
+    /// and whether the prover is to move in bit 40.
+    /// Zero means the entry is empty (since states are never zero).
+    key: u64,
+
+    proof_number: u32,
+    disproof_number: u32,
+
+    /// If the prover is to move and the node is proven,
+    /// this is the child that proves it.
+    /// Otherwise, it is `State(0)`.
+    proving_child: State,
+}
+
+impl DfpnEntry {
+    const EMPTY: Self = Self {
+        key: 0,
+        proof_number: 1,
+        disproof_number: 1,
+        proving_child: State(0),
+    };
+}
+
+impl DfpnSearch {
+    /// `table_capacity` is the number of transposition table entries
+    /// (each of which is 24 bytes).
+    /// It must be positive.
+    pub fn new(table_capacity: usize) -> Self {
+        assert!(table_capacity > 0, "Table capacity must be positive.");
+
+        Self {
+            table: vec![DfpnEntry::EMPTY; table_capacity],
+            node_limit: DEFAULT_DFPN_NODE_LIMIT,
+            node_count: 0,
+            max_depth: DEFAULT_DFPN_MAX_DEPTH,
+            path: HashSet::new(),
+        }
+    }
+
+    /// Each call to `search` gives up after expanding this many nodes
+    /// (in total, across both of its searches).
+    pub fn node_limit(mut self, node_limit: u64) -> Self {
+        self.node_limit = node_limit;
+        self
+    }
+
+    /// The search recurses once per ply,
+    /// so this bounds its stack use.
+    /// Nodes deeper than this count as unproven,
+    /// just like repetitions.
+    /// It must be positive.
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        assert!(max_depth > 0, "Max depth must be positive.");
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Returns the number of nodes the last `search` expanded.
+    pub fn node_count(&self) -> u64 {
+        self.node_count
+    }
+
+    pub fn search(&mut self, root: State) -> DfpnResult {
+        self.node_count = 0;
+
+        match root.terminality() {
+            Terminality::Win => return DfpnResult::Win { winning_move: None },
+            Terminality::Loss => return DfpnResult::Loss,
+            Terminality::Nonterminal => {}
+        }
+
+        // The table is cleared between the searches,
+        // since an entry's meaning depends on who the prover is.
+        self.clear_table();
+        if self.prove(root, true) {
+            let winning_move = self.lookup(root, true).proving_child;
+            return DfpnResult::Win {
+                winning_move: Some(winning_move),
+            };
+        }
+
+        self.clear_table();
+        if self.prove(root, false) {
+            return DfpnResult::Loss;
+        }
+
+        DfpnResult::Unproven
+    }
+
+    fn clear_table(&mut self) {
+        self.table.fill(DfpnEntry::EMPTY);
+    }
+
+    /// Returns whether the prover can force a win from `root`.
+    fn prove(&mut self, root: State, is_prover_to_move: bool) -> bool {
+        self.path.clear();
+        self.mid(root, is_prover_to_move, INFINITY, INFINITY);
+        self.lookup(root, is_prover_to_move).proof_number == 0
+    }
+
+    /// Searches `state` until its proof number reaches `proof_threshold`
+    /// or its disproof number reaches `disproof_threshold`,
+    /// and stores the result in the table.
+    fn mid(
+        &mut self,
+        state: State,
+        is_prover_to_move: bool,
+        proof_threshold: u32,
+        disproof_threshold: u32,
+    ) {
+        let key = key(state, is_prover_to_move);
+
+        if let Some(numbers) = terminal_numbers(state, is_prover_to_move) {
+            self.store(key, numbers, State(0));
+            return;
+        }
+
+        self.path.insert(key);
+        let children = state.children();
+        let mut child_numbers = vec![(0, 0); children.len()];
+
+        loop {
+            for (numbers, &child) in child_numbers.iter_mut().zip(&children) {
+                *numbers = self.child_numbers(child, !is_prover_to_move);
+            }
+
+            let (proof_number, disproof_number) = combine(&child_numbers, is_prover_to_move);
+            let best_index = select(&child_numbers, is_prover_to_move);
+
+            let is_done = proof_number >= proof_threshold
+                || disproof_number >= disproof_threshold
+                || self.node_count >= self.node_limit;
+            if is_done {
+                let proving_child = if is_prover_to_move && proof_number == 0 {
+                    children[best_index]
+                } else {
+                    State(0)
+                };
+                self.store(key, (proof_number, disproof_number), proving_child);
+                break;
+            }
+
+            // The child's thresholds are the smallest numbers
+            // that would either finish this node
+            // or make a sibling the most promising child.
+            let (child_proof, child_disproof) = child_numbers[best_index];
+            let second_best = second_best(&child_numbers, best_index, is_prover_to_move);
+            let (child_proof_threshold, child_disproof_threshold) = if is_prover_to_move {
+                (
+                    proof_threshold.min(second_best.saturating_add(1)),
+                    (disproof_threshold - disproof_number).saturating_add(child_disproof),
+                )
+            } else {
+                (
+                    (proof_threshold - proof_number).saturating_add(child_proof),
+                    disproof_threshold.min(second_best.saturating_add(1)),
+                )
+            };
+
+            self.node_count += 1;
+            self.mid(
+                children[best_index],
+                !is_prover_to_move,
+                child_proof_threshold.min(INFINITY),
+                child_disproof_threshold.min(INFINITY),
+            );
+        }
+
+        self.path.remove(&key);
+    }
+
+    fn child_numbers(&self, child: State, is_prover_to_move: bool) -> (u32, u32) {
+        if let Some(numbers) = terminal_numbers(child, is_prover_to_move) {
+            return numbers;
+        }
+
+        // A repetition is a draw, which is not a win for the prover.
+        if self.path.contains(&key(child, is_prover_to_move)) {
+            return (INFINITY, 0);
+        }
+
+        let entry = self.lookup(child, is_prover_to_move);
+
+        // The child would be deeper than `max_depth`,
+        // so it cannot be searched.
+        // A proof from an earlier visit is still sound.
+        if self.path.len() >= self.max_depth && entry.proof_number != 0 {
+            return (INFINITY, 0);
+        }
+
+        (entry.proof_number, entry.disproof_number)
+    }
+
+    /// Returns the entry for the node,
+    /// or an unsearched entry if the table does not have one.
+    fn lookup(&self, state: State, is_prover_to_move: bool) -> DfpnEntry {
+        let key = key(state, is_prover_to_move);
+        let entry = self.table[self.slot(key)];
+        if entry.key == key {
+            entry
+        } else {
+            DfpnEntry::EMPTY
+        }
+    }
+
+    fn store(
+        &mut self,
+        key: u64,
+        (proof_number, disproof_number): (u32, u32),
+        proving_child: State,
+    ) {
+        let slot = self.slot(key);
+        self.table[slot] = DfpnEntry {
+            key,
+            proof_number,
+            disproof_number,
+            proving_child,
+        };
+    }
+
+    fn slot(&self, key: u64) -> usize {
+        // Fibonacci hashing spreads nearby states across the table.
+        (key.wrapping_mul(0x9E37_79B9_7F4A_7C15) >> 16) as usize % self.table.len()
+    }
+}
+
+fn key(state: State, is_prover_to_move: bool) -> u64 {
+    state.0 | ((is_prover_to_move as u64) << 40)
+}
+
+/// Returns the proof and disproof numbers of a terminal node,
+/// or `None` if the node is nonterminal.
+fn terminal_numbers(state: State, is_prover_to_move: bool) -> Option<(u32, u32)> {
+    let does_prover_win = match state.terminality() {
+        Terminality::Win => is_prover_to_move,
+        Terminality::Loss => !is_prover_to_move,
+        Terminality::Nonterminal => return None,
+    };
+
+    Some(if does_prover_win {
+        (0, INFINITY)
+    } else {
+        (INFINITY, 0)
+    })
+}
+
+/// At a prover node, the prover needs to prove one child,
+/// but the defender needs to disprove all of them.
+/// At a defender node, it is the other way around.
+fn combine(child_numbers: &[(u32, u32)], is_prover_to_move: bool) -> (u32, u32) {
+    let min_proof = child_numbers.iter().map(|n| n.0).min().unwrap_or(INFINITY);
+    let min_disproof = child_numbers.iter().map(|n| n.1).min().unwrap_or(INFINITY);
+    let sum_proof = child_numbers
+        .iter()
+        .fold(0u32, |sum, n| sum.saturating_add(n.0))
+        .min(INFINITY);
+    let sum_disproof = child_numbers
+        .iter()
+        .fold(0u32, |sum, n| sum.saturating_add(n.1))
+        .min(INFINITY);
+
+    if is_prover_to_move {
+        (min_proof, sum_disproof)
+    } else {
+        (sum_proof, min_disproof)
+    }
+}
+
+/// Returns the index of the most promising child
+/// (i.e., the one with the smallest proof number at a prover node,
+/// or the smallest disproof number at a defender node).
+/// Ties go to the first child.
+fn select(child_numbers: &[(u32, u32)], is_prover_to_move: bool) -> usize {
+    let mut best_index = 0;
+    for (i, numbers) in child_numbers.iter().enumerate() {
+        if relevant(*numbers, is_prover_to_move)
+            < relevant(child_numbers[best_index], is_prover_to_move)
+        {
+            best_index = i;
+        }
+    }
+    best_index
+}
+
+/// Returns the relevant number of the second most promising child,
+/// or `INFINITY` if there is only one child.
+fn second_best(child_numbers: &[(u32, u32)], best_index: usize, is_prover_to_move: bool) -> u32 {
+    child_numbers
+        .iter()
+        .enumerate()
+        .filter(|&(i, _)| i != best_index)
+        .map(|(_, numbers)| relevant(*numbers, is_prover_to_move))
+        .min()
+        .unwrap_or(INFINITY)
+}
+
+fn relevant((proof_number, disproof_number): (u32, u32), is_prover_to_move: bool) -> u32 {
+    if is_prover_to_move {
+        proof_number
+    } else {
+        disproof_number
+    }
+}
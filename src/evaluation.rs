@@ -0,0 +1,343 @@
+use super::*;
+
+use std::fmt::{self, Display, Formatter};
+
+/// The number of fields in `EvaluationFeatures` and `EvaluationWeights`.
+pub const FEATURE_COUNT: usize = 8;
+
+/// The heuristic features of a state.
+/// Each feature is the active player's count
+/// minus the passive player's count,
+/// so positive features favor the active player.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EvaluationFeatures {
+    /// Unpromoted chicks (on the board or in hand).
+    pub chick: i32,
+
+    /// Hens (i.e., promoted chicks).
+    pub hen: i32,
+
+    /// Elephants (on the board or in hand).
+    pub elephant: i32,
+
+    /// Giraffes (on the board or in hand).
+    pub giraffe: i32,
+
+    /// Nonlion pieces in hand.
+    /// These are also counted by the material features,
+    /// so this is the value of being able to drop them.
+    pub hand_piece: i32,
+
+    /// The number of rows each lion has advanced from its home row.
+    pub lion_advancement: i32,
+
+    /// The number of opposing pieces next to the passive lion,
+    /// minus the number next to the active lion.
+    pub lion_safety: i32,
+
+    /// The number of rows each chick on the board
+    /// has advanced from its player's home row.
+    pub chick_advancement: i32,
+}
+
+/// The weight of each feature in `EvaluationFeatures`.
+/// The evaluation is the sum of each feature times its weight.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EvaluationWeights {
+    pub chick: i32,
+    pub hen: i32,
+    pub elephant: i32,
+    pub giraffe: i32,
+    pub hand_piece: i32,
+    pub lion_advancement: i32,
+    pub lion_safety: i32,
+    pub chick_advancement: i32,
+}
+
+impl EvaluationWeights {
+    /// Hand-picked weights, where a chick is worth 100.
+    pub const DEFAULT: Self = Self {
+        chick: 100,
+        hen: 450,
+        elephant: 300,
+        giraffe: 400,
+        hand_piece: 50,
+        lion_advancement: 40,
+        lion_safety: 60,
+        chick_advancement: 20,
+    };
+
+    /// Returns the evaluation of `state`
+    /// from the perspective of its active player.
+    ///
+    /// The evaluation is only meaningful for nonterminal states.
+    pub fn evaluate(&self, state: State) -> i32 {
+        let features = EvaluationFeatures::of(state);
+        self.chick * features.chick
+            + self.hen * features.hen
+            + self.elephant * features.elephant
+            + self.giraffe * features.giraffe
+            + self.hand_piece * features.hand_piece
+            + self.lion_advancement * features.lion_advancement
+            + self.lion_safety * features.lion_safety
+            + self.chick_advancement * features.chick_advancement
+    }
+
+    /// Returns the weights in the order of `EvaluationFeatures::to_array`.
+    pub const fn to_array(self) -> [i32; FEATURE_COUNT] {
+        [
+            self.chick,
+            self.hen,
+            self.elephant,
+            self.giraffe,
+            self.hand_piece,
+            self.lion_advancement,
+            self.lion_safety,
+            self.chick_advancement,
+        ]
+    }
+
+    pub const fn from_array(array: [i32; FEATURE_COUNT]) -> Self {
+        let [chick, hen, elephant, giraffe, hand_piece, lion_advancement, lion_safety, chick_advancement] =
+            array;
+        Self {
+            chick,
+            hen,
+            elephant,
+            giraffe,
+            hand_piece,
+            lion_advancement,
+            lion_safety,
+            chick_advancement,
+        }
+    }
+}
+
+impl Default for EvaluationWeights {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Returns the evaluation of `state` with `EvaluationWeights::DEFAULT`.
+pub fn evaluate(state: State) -> i32 {
+    EvaluationWeights::DEFAULT.evaluate(state)
+}
+
+impl EvaluationFeatures {
+    /// `state` must be nonterminal
+    /// (otherwise, one of the lions is in hand).
+    pub fn of(state: State) -> Self {
+        const CHICKS: [Nonlion; 2] = [Nonlion::CHICK0, Nonlion::CHICK1];
+        const ELEPHANTS: [Nonlion; 2] = [Nonlion::ELEPHANT0, Nonlion::ELEPHANT1];
+        const GIRAFFES: [Nonlion; 2] = [Nonlion::GIRAFFE0, Nonlion::GIRAFFE1];
+
+        let state = state.into_builder();
+        let active_lion = state.active_lion_coords();
+        let passive_lion = state.passive_lion_coords();
+
+        let mut features = Self {
+            lion_advancement: row(active_lion) - (3 - row(passive_lion)),
+            ..Self::default()
+        };
+
+        for piece in CHICKS.into_iter().chain(ELEPHANTS).chain(GIRAFFES) {
+            let is_active = piece.is_active(state);
+            let sign = if is_active { 1 } else { -1 };
+
+            if piece.is_in_hand(state) {
+                features.hand_piece += sign;
+            } else {
+                let coords = state.nonlion_coords(piece);
+                if is_active && is_adjacent(coords, passive_lion) {
+                    features.lion_safety += 1;
+                } else if !is_active && is_adjacent(coords, active_lion) {
+                    features.lion_safety -= 1;
+                }
+            }
+
+            if CHICKS.contains(&piece) {
+                if Actor(piece.0).is_promoted(state) {
+                    features.hen += sign;
+                } else {
+                    features.chick += sign;
+
+                    if !piece.is_in_hand(state) {
+                        let row = row(state.nonlion_coords(piece));
+                        features.chick_advancement += if is_active { row } else { -(3 - row) };
+                    }
+                }
+            } else if ELEPHANTS.contains(&piece) {
+                features.elephant += sign;
+            } else {
+                features.giraffe += sign;
+            }
+        }
+
+        features
+    }
+
+    /// Returns the features in declaration order.
+    pub const fn to_array(self) -> [i32; FEATURE_COUNT] {
+        [
+            self.chick,
+            self.hen,
+            self.elephant,
+            self.giraffe,
+            self.hand_piece,
+            self.lion_advancement,
+            self.lion_safety,
+            self.chick_advancement,
+        ]
+    }
+}
+
+fn row(coords: Coords) -> i32 {
+    (coords.0 >> 2) as i32
+}
+
+fn column(coords: Coords) -> i32 {
+    (coords.0 & 0b11) as i32
+}
+
+fn is_adjacent(a: Coords, b: Coords) -> bool {
+    a != b && (row(a) - row(b)).abs() <= 1 && (column(a) - column(b)).abs() <= 1
+}
+
+/// How well an evaluation function agrees with a solution.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EvaluationAccuracy {
+    pub sampled_state_count: u64,
+
+    /// The number of sampled states that are wins or losses.
+    pub decisive_state_count: u64,
+
+    /// The number of decisive sampled states
+    /// whose evaluation has the sign of their outcome
+    /// (positive for wins, negative for losses).
+    pub sign_agreement_count: u64,
+
+    /// The number of pairs of moves from a sampled state
+    /// that lead to different results (win, draw, or loss).
+    pub move_pair_count: u64,
+
+    /// The number of those pairs where the evaluation
+    /// strictly prefers the move with the better result.
+    pub ordered_move_pair_count: u64,
+}
+
+impl EvaluationAccuracy {
+    /// Returns the fraction of decisive states with the correct sign,
+    /// or zero if there are none.
+    pub fn sign_accuracy(&self) -> f64 {
+        fraction(self.sign_agreement_count, self.decisive_state_count)
+    }
+
+    /// Returns the fraction of move pairs in the correct order,
+    /// or zero if there are none.
+    pub fn ordering_accuracy(&self) -> f64 {
+        fraction(self.ordered_move_pair_count, self.move_pair_count)
+    }
+}
+
+fn fraction(numerator: u64, denominator: u64) -> f64 {
+    if denominator == 0 {
+        0.0
+    } else {
+        numerator as f64 / denominator as f64
+    }
+}
+
+impl Display for EvaluationAccuracy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Sampled {} states ({} decisive).\nSign agreement: {}/{} ({:.2}%).\nMove ordering agreement: {}/{} ({:.2}%).",
+            self.sampled_state_count,
+            self.decisive_state_count,
+            self.sign_agreement_count,
+            self.decisive_state_count,
+            100.0 * self.sign_accuracy(),
+            self.ordered_move_pair_count,
+            self.move_pair_count,
+            100.0 * self.ordering_accuracy()
+        )
+    }
+}
+
+/// Grades `evaluator` against `solution`,
+/// using every `sample_interval`-th state in key order.
+///
+/// For each sampled state, this checks whether the sign of the evaluation
+/// matches the state's outcome,
+/// and whether the evaluation orders the state's moves
+/// the same way as their results.
+/// A move's evaluation is the negated evaluation of its child,
+/// and terminal children are skipped
+/// (since their outcomes are obvious to any search).
+pub fn measure_evaluation_accuracy(
+    solution: &StateMap<StateAndStats>,
+    evaluator: impl Fn(State) -> i32,
+    sample_interval: u64,
+    observer: &mut (impl ProgressObserver + ?Sized),
+) -> EvaluationAccuracy {
+    assert!(sample_interval > 0, "Sample interval must be positive.");
+
+    let mut accuracy = EvaluationAccuracy::default();
+    let mut moves = Vec::with_capacity(8 * 12);
+    let mut tracker = PhaseTracker::resume(
+        observer,
+        SolverPhase::EvaluationAccuracy,
+        Some(solution.len()),
+        0,
+    );
+
+    solution.visit_in_key_order(|state, best_child| {
+        let is_sampled = tracker.processed().is_multiple_of(sample_interval);
+        tracker.tick(None);
+
+        if !is_sampled {
+            return;
+        }
+
+        accuracy.sampled_state_count += 1;
+
+        let outcome = best_child.parent_outcome();
+        if outcome != Outcome::DRAW {
+            accuracy.decisive_state_count += 1;
+            let evaluation = evaluator(state);
+            if (outcome.0 > 0 && evaluation > 0) || (outcome.0 < 0 && evaluation < 0) {
+                accuracy.sign_agreement_count += 1;
+            }
+        }
+
+        moves.clear();
+        state.visit_children(|child| {
+            let child_entry = solution.get(child);
+            if child.is_terminal() || child_entry.is_null() {
+                return;
+            }
+
+            let move_outcome = child_entry.parent_outcome().invert().delay_by_one();
+            moves.push((move_outcome.wdl() as u8, -evaluator(child)));
+        });
+
+        for (i, &(wdl_a, evaluation_a)) in moves.iter().enumerate() {
+            for &(wdl_b, evaluation_b) in &moves[i + 1..] {
+                if wdl_a == wdl_b {
+                    continue;
+                }
+
+                accuracy.move_pair_count += 1;
+                if (wdl_a > wdl_b) == (evaluation_a > evaluation_b) && evaluation_a != evaluation_b
+                {
+                    accuracy.ordered_move_pair_count += 1;
+                }
+            }
+        }
+    });
+
+    tracker.finish(None);
+
+    accuracy
+}
@@ -0,0 +1,117 @@
+use super::*;
+
+use crate::pretty::*;
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fmt::{self, Display, Formatter};
+
+/// A win or loss with one of the longest delays in a solution.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExtremePosition {
+    pub state: State,
+    pub outcome: Outcome,
+
+    /// `state` followed by its principal variation
+    /// (see `principal_variation`), which ends with the end of the game.
+    /// Each state's stats hold its outcome
+    /// (with a required child report count of zero).
+    pub line: Vec<StateAndStats>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExtremePositionCatalogue {
+    /// The wins with the longest delays, longest first.
+    pub longest_wins: Vec<ExtremePosition>,
+
+    /// The losses with the longest delays, longest first.
+    pub longest_losses: Vec<ExtremePosition>,
+}
+
+/// Returns up to `count` wins and up to `count` losses
+/// with the longest delays in `solution`.
+/// Ties are broken by state, in ascending order.
+pub fn extreme_positions(
+    solution: &StateMap<StateAndStats>,
+    count: usize,
+    observer: &mut (impl ProgressObserver + ?Sized),
+) -> ExtremePositionCatalogue {
+    // Each heap holds the best `count` candidates so far,
+    // with the worst candidate on top so it can be evicted.
+    // A candidate is better if its delay is longer,
+    // or if its delay is the same and its state is smaller.
+    let mut wins: BinaryHeap<(Reverse<u8>, State)> = BinaryHeap::new();
+    let mut losses: BinaryHeap<(Reverse<u8>, State)> = BinaryHeap::new();
+    let mut tracker = PhaseTracker::resume(
+        observer,
+        SolverPhase::ExtremePositions,
+        Some(solution.len()),
+        0,
+    );
+
+    solution.visit_in_key_order(|state, best_child| {
+        let outcome = best_child.parent_outcome();
+        let heap = match outcome.0 {
+            0 => None,
+            1.. => Some(&mut wins),
+            _ => Some(&mut losses),
+        };
+
+        if let Some(heap) = heap {
+            heap.push((Reverse(delay(outcome)), state));
+            if heap.len() > count {
+                heap.pop();
+            }
+        }
+
+        tracker.tick(None);
+    });
+
+    tracker.finish(None);
+
+    let into_positions = |heap: BinaryHeap<(Reverse<u8>, State)>| {
+        heap.into_sorted_vec()
+            .into_iter()
+            .map(|(_, state)| ExtremePosition {
+                state,
+                outcome: solution.get(state).parent_outcome(),
+                line: line(solution, state),
+            })
+            .collect()
+    };
+
+    ExtremePositionCatalogue {
+        longest_wins: into_positions(wins),
+        longest_losses: into_positions(losses),
+    }
+}
+
+fn line(solution: &StateMap<StateAndStats>, state: State) -> Vec<StateAndStats> {
+    let outcome = solution.get(state).parent_outcome();
+    let mut line = vec![state.with_stats(StateStats::new(outcome, 0))];
+    for (_, state, outcome) in principal_variation(state, solution) {
+        line.push(state.with_stats(StateStats::new(outcome, 0)));
+    }
+    line
+}
+
+impl Display for ExtremePosition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Outcome: {}\n\nSTATE:\n\n{}\n\nOPTIMAL LINE:\n\n{}",
+            self.outcome.pretty(),
+            self.state.pretty(),
+            self.line.clone().pretty()
+        )
+    }
+}
+
+/// Returns the number of plies until the game ends.
+fn delay(outcome: Outcome) -> u8 {
+    if outcome.0 > 0 {
+        (Outcome::win_in(0).0 - outcome.0) as u8
+    } else {
+        (outcome.0 - Outcome::loss_in(0).0) as u8
+    }
+}
@@ -2,26 +2,90 @@ use super::*;
 
 use std::collections::VecDeque;
 
+/// The progress of an unfinished `reachable_states` search.
+#[derive(Clone, Debug)]
+pub struct ReachableStatesSnapshot {
+    reachable: StateSet,
+    queue: VecDeque<State>,
+    processed: u64,
+}
+
+impl ReachableStatesSnapshot {
+    /// Returns a snapshot of a search that has not started yet.
+    pub fn new(initial_state: State) -> Self {
+        let mut reachable = StateSet::empty();
+        reachable.add(initial_state);
+
+        Self {
+            reachable,
+            queue: std::iter::once(initial_state).collect(),
+            processed: 0,
+        }
+    }
+
+    /// Returns the states found so far.
+    pub fn reachable(&self) -> &StateSet {
+        &self.reachable
+    }
+
+    /// Returns the number of found states whose children
+    /// have not been visited yet.
+    pub fn queue_len(&self) -> usize {
+        self.queue.len()
+    }
+}
+
 /// Returns a set of states reachable from
 /// the provided initial state.
 pub fn reachable_states(
     initial_state: State,
-    mut on_state_processed: impl FnMut(State),
+    observer: &mut (impl ProgressObserver + ?Sized),
 ) -> StateSet {
-    let mut reachable = StateSet::empty();
-    reachable.add(initial_state);
+    resume_reachable_states(
+        ReachableStatesSnapshot::new(initial_state),
+        observer,
+        &CancellationToken::new(),
+    )
+    .expect("A new token is never cancelled.")
+}
 
-    let mut queue = std::iter::once(initial_state).collect::<VecDeque<_>>();
+/// Continues the search from `snapshot`,
+/// checking `token` before visiting each state's children.
+pub fn resume_reachable_states(
+    snapshot: ReachableStatesSnapshot,
+    observer: &mut (impl ProgressObserver + ?Sized),
+    token: &CancellationToken,
+) -> Result<StateSet, Cancelled<ReachableStatesSnapshot>> {
+    let ReachableStatesSnapshot {
+        mut reachable,
+        mut queue,
+        processed,
+    } = snapshot;
+    let mut tracker = PhaseTracker::resume(observer, SolverPhase::ReachableStates, None, processed);
+
+    while let Some(&state) = queue.front() {
+        if token.should_stop() {
+            return Err(Cancelled {
+                snapshot: ReachableStatesSnapshot {
+                    reachable,
+                    queue,
+                    processed: tracker.processed(),
+                },
+            });
+        }
+
+        queue.pop_front();
 
-    while let Some(state) = queue.pop_front() {
         state.visit_children(|new_child| {
             if !reachable.add(new_child).did_addend_already_exist {
                 queue.push_back(new_child);
             }
         });
 
-        on_state_processed(state);
+        tracker.tick(Some(queue.len() as u64));
     }
 
-    reachable
+    tracker.finish(Some(0));
+
+    Ok(reachable)
 }
@@ -5,18 +5,99 @@
 #[cfg(test)]
 mod tests;
 
+pub mod alpha_beta;
 pub mod backward_pass;
 pub mod best_child_map;
+pub mod cancel;
+pub mod checkpoint;
+pub mod compact_best;
+pub mod dfpn;
+pub mod evaluation;
+pub mod extremes;
 pub mod forward_pass;
+pub mod mcts;
+pub mod move_outcomes;
+pub mod move_quality;
+pub mod optimal_children;
 pub mod pretty;
+pub mod principal_variation;
+mod prng;
+pub mod progress;
+pub mod proof_check;
+pub mod proof_tree;
+pub mod rank;
+pub mod solution_file;
+pub mod solution_index;
+pub mod solver;
 pub mod state_map;
 pub mod state_set;
-
-pub use backward_pass::{compute_stats, initial_stat_map};
-pub use best_child_map::best_child_map;
-pub use forward_pass::reachable_states;
+pub mod summary;
+pub mod training_data;
+pub mod verify;
+pub mod wdl;
+pub mod weight_fitting;
+
+pub use alpha_beta::{
+    AlphaBetaEngine, AlphaBetaResult, DEFAULT_MAX_DEPTH, MIN_WIN_SCORE, WIN_SCORE,
+};
+pub use backward_pass::{
+    compute_stats, initial_stat_map, resume_compute_stats, resume_initial_stat_map,
+    InitialStatsSnapshot, RetrogradeSnapshot,
+};
+pub use best_child_map::{
+    best_child_map, best_child_map_parallel, best_child_map_with_tie_breaks, resume_best_child_map,
+    resume_best_child_map_parallel, BestChildrenSnapshot, TieBreak,
+};
+pub use cancel::{CancellationToken, Cancelled};
+pub use compact_best::{
+    compact_best_child_map, read_compact_solution, write_compact_solution, CompactBest,
+};
+pub use dfpn::{DfpnResult, DfpnSearch, DEFAULT_DFPN_MAX_DEPTH, DEFAULT_DFPN_NODE_LIMIT};
+pub use evaluation::{
+    evaluate, measure_evaluation_accuracy, EvaluationAccuracy, EvaluationFeatures,
+    EvaluationWeights, FEATURE_COUNT,
+};
+pub use extremes::{extreme_positions, ExtremePosition, ExtremePositionCatalogue};
+pub use forward_pass::{reachable_states, resume_reachable_states, ReachableStatesSnapshot};
+pub use mcts::{
+    MctsEngine, MctsMoveStats, MctsResult, Playout, DEFAULT_EXPLORATION, DEFAULT_MAX_PLAYOUT_PLIES,
+    DEFAULT_MCTS_ITERATIONS,
+};
+pub use move_outcomes::MoveOutcomeTable;
+pub use move_quality::{classify_move_outcomes, ClassifiedMove, MoveQuality};
+pub use optimal_children::{
+    optimal_children_map, read_optimal_children, write_optimal_children, OptimalChildren,
+    CHILD_MASK_BITS,
+};
+pub use principal_variation::principal_variation;
+pub use progress::{
+    JsonLinesObserver, PhaseTracker, PlainTextObserver, ProgressObserver, ProgressReport,
+    SilentObserver, SolverPhase,
+};
+pub use proof_check::{check_proof_tree, ProofTreeError, ProofTreeReport};
+pub use proof_tree::{proof_tree, ProofTree, PROOF_TREE_MAGIC};
+pub use solution_file::{
+    read_solution_file, write_solution_file, Ruleset, SolutionFileError, SolutionHeader,
+    SOLUTION_FORMAT_VERSION, SOLUTION_MAGIC, SOLUTION_RECORD_LEN,
+};
+pub use solution_index::SolutionIndex;
+pub use solver::{
+    CompactSolutionSink, MoveOutcomeTableSink, OptimalChildrenSink, ProofTreeSink, Solution,
+    SolutionFileSink, SolutionSink, Solver, SolverError, SolverSnapshot, SummarySink, WdlTableSink,
+};
 pub use state_map::*;
 pub use state_set::StateSet;
+pub use summary::{SolutionSummary, OUTCOME_DELAY_COUNT};
+pub use training_data::{
+    TrainingDataset, TrainingSample, HAND_COUNT_COUNT, PLANE_COUNT, TRAINING_DATA_MAGIC,
+    TRAINING_RECORD_LEN,
+};
+pub use verify::{verify_solution, SolutionViolation, VerificationReport, MAX_STORED_VIOLATIONS};
+pub use wdl::{wdl_table, wdl_table_from_best_child_map, Wdl, WdlTable};
+pub use weight_fitting::{
+    fit_evaluation_weights, fit_evaluation_weights_from_best_child_map, EvaluationFit,
+    EvaluationFitter, FITTED_WEIGHT_SCALE,
+};
 
 // A note about fields with the comment "Must be non-zero":
 //
@@ -68,6 +149,13 @@ pub struct StateAndStats(
     pub u64,
 );
 
+/// The **least** significant 7 bits are used.
+/// The 3 most significant of those bits store the actor,
+/// and the 4 least significant store the destination's row and column
+/// (see the spec's "Action representation" section).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Action(pub u8);
+
 /// This is like a `State`,
 /// but with the `chick0 <= chick1` invariant
 /// (and all similar invariants) removed.
@@ -143,20 +231,6 @@ pub enum Player {
     Gote,
 }
 
-// TODO: Delete after we are done debugging.
-#[derive(Debug, Clone, Copy, Default)]
-pub struct Progress {
-    pub already_solved_parent_visits: usize,
-    pub unsolved_parent_visits: usize,
-    pub unreachable_parent_visits: usize,
-
-    pub winning_parent_conclusions: usize,
-    pub uncertain_parent_conclusions: usize,
-    pub losing_parent_conclusions: usize,
-
-    pub queue_pushes: usize,
-}
-
 impl Terminality {
     const fn is_terminal(self) -> bool {
         (self as i8) != (Terminality::Nonterminal as i8)
@@ -350,6 +424,30 @@ impl State {
         }
     }
 
+    /// Returns whether moving from `self` to `child` captures a piece.
+    /// `child` must be a child of `self`.
+    pub fn is_capture(self, child: State) -> bool {
+        // A capture moves a piece into a hand, a drop moves one out,
+        // and any other move leaves the hands alone.
+        child.hand_piece_count() > self.hand_piece_count()
+    }
+
+    fn hand_piece_count(self) -> u32 {
+        [
+            Offset::CHICK0_COLUMN,
+            Offset::CHICK1_COLUMN,
+            Offset::ELEPHANT0_COLUMN,
+            Offset::ELEPHANT1_COLUMN,
+            Offset::GIRAFFE0_COLUMN,
+            Offset::GIRAFFE1_COLUMN,
+            Offset::ACTIVE_LION_COLUMN,
+            Offset::PASSIVE_LION_COLUMN,
+        ]
+        .into_iter()
+        .filter(|offset| (self.0 >> offset.0) & 0b1111 == 0b1111)
+        .count() as u32
+    }
+
     fn child_count(self) -> u8 {
         let mut count = 0;
         self.visit_children(|_| count += 1);
@@ -657,8 +755,56 @@ impl Iterator for CoordVec {
 }
 
 impl State {
-    pub fn visit_children(self, visitor: impl FnMut(State)) {
-        ChildCalculator::new(self).visit_children(visitor);
+    pub fn visit_children(self, mut visitor: impl FnMut(State)) {
+        ChildCalculator::new(self).visit_actions(|_, child| visitor(child));
+    }
+
+    /// Visits each child along with the action that leads to it,
+    /// in the same order as `visit_children`.
+    pub fn visit_actions(self, visitor: impl FnMut(Action, State)) {
+        ChildCalculator::new(self).visit_actions(visitor);
+    }
+
+    /// Returns the action that leads from `self` to `child`,
+    /// or `None` if `child` is not a child of `self`.
+    pub fn action_to(self, child: State) -> Option<Action> {
+        let mut out = None;
+        self.visit_actions(|action, candidate| {
+            if candidate == child {
+                out = Some(action);
+            }
+        });
+        out
+    }
+}
+
+impl Action {
+    const fn new(actor: Actor, dest: Coords) -> Self {
+        Self((actor.0 .0 << 4) | dest.0)
+    }
+
+    /// Returns the name of the piece being moved or dropped
+    /// (e.g., `"chick0"`).
+    pub const fn actor_name(self) -> &'static str {
+        match self.0 >> 4 {
+            0b001 => "lion",
+            0b010 => "chick0",
+            0b011 => "chick1",
+            0b100 => "elephant0",
+            0b101 => "elephant1",
+            0b110 => "giraffe0",
+            0b111 => "giraffe1",
+            _ => "invalid",
+        }
+    }
+
+    /// Row zero is the moving player's home row.
+    pub const fn dest_row(self) -> u8 {
+        (self.0 >> 2) & 0b11
+    }
+
+    pub const fn dest_column(self) -> u8 {
+        self.0 & 0b11
     }
 }
 
@@ -674,7 +820,7 @@ impl ChildCalculator {
     }
 
     #[inline(always)]
-    fn visit_children(self, mut visitor: impl FnMut(State)) {
+    fn visit_actions(self, mut visitor: impl FnMut(Action, State)) {
         if self.state.is_terminal() {
             return;
         }
@@ -689,7 +835,7 @@ impl ChildCalculator {
     }
 
     #[inline(always)]
-    fn visit_children_with_actor(self, actor: Actor, visitor: impl FnMut(State)) {
+    fn visit_children_with_actor(self, actor: Actor, visitor: impl FnMut(Action, State)) {
         let state = self.state.into_builder();
 
         if actor.is_passive(state) {
@@ -706,7 +852,7 @@ impl ChildCalculator {
     }
 
     #[inline(always)]
-    fn visit_dropping_children(self, actor: Actor, mut visitor: impl FnMut(State)) {
+    fn visit_dropping_children(self, actor: Actor, mut visitor: impl FnMut(Action, State)) {
         let state = self.state.into_builder();
 
         // If two of the same species are in the active hand,
@@ -717,12 +863,20 @@ impl ChildCalculator {
 
         for dest in self.empty_squares {
             let state = actor.set_coords(state, dest);
-            visitor(state.invert_active_player().build());
+            visitor(
+                Action::new(actor, dest),
+                state.invert_active_player().build(),
+            );
         }
     }
 
     #[inline(always)]
-    fn visit_moving_children(self, actor: Actor, start: Coords, mut visitor: impl FnMut(State)) {
+    fn visit_moving_children(
+        self,
+        actor: Actor,
+        start: Coords,
+        mut visitor: impl FnMut(Action, State),
+    ) {
         let state = self.state.into_builder();
         let is_promoted = actor.is_promoted(state);
         let dest_candidates = actor.legal_dest_squares(is_promoted, start);
@@ -734,7 +888,10 @@ impl ChildCalculator {
             }
             let state = optional_state.unchecked_unwrap();
             let state = actor.set_coords_and_promote_if_in_last_row(state, dest);
-            visitor(state.invert_active_player().build());
+            visitor(
+                Action::new(actor, dest),
+                state.invert_active_player().build(),
+            );
         }
     }
 }
@@ -1025,7 +1182,11 @@ impl StateBuilder {
         let is_occupant_chick = occupant & !1 == 0b010;
         // If the occupant is a chick, we need to set the promotion bit to 0.
         // The promotion bit is 1 bit right of the column offset.
-        let demotion_mask = !((is_occupant_chick as u64) << (occupant_coords_offset.0 - 1));
+        let demotion_mask = if is_occupant_chick {
+            !(1 << (occupant_coords_offset.0 - 1))
+        } else {
+            !0
+        };
 
         Self((self.0 | (0b1111 << occupant_coords_offset.0)) & allegiance_mask & demotion_mask)
             .into_optional()
@@ -1264,6 +1425,7 @@ impl PassiveLion {
         StateBuilder((state.0 & !(0b1111 << coords_offset)) | ((coords.0 as u64) << coords_offset))
     }
 
+    #[allow(clippy::wrong_self_convention)]
     #[inline(always)]
     const fn is_in_hand(self, state: StateBuilder) -> bool {
         state.0 & Self::COORDS_MASK == Self::COORDS_MASK
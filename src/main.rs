@@ -1,5 +1,5 @@
 use std::fs::{self, File};
-use std::io::{Read, Write};
+use std::io::{BufWriter, Write};
 use std::path::Path;
 use std::time::Instant;
 
@@ -10,6 +10,7 @@ use pretty::IntoPretty;
 enum Command {
     Help,
     Parent,
+    PrincipalVariation,
     Child(usize),
 }
 
@@ -28,12 +29,70 @@ fn main() {
         .parent()
         .unwrap()
         .join("db");
+    let wdl_table_path = Path::new(file!())
+        .parent()
+        .unwrap()
+        .parent()
+        .unwrap()
+        .join("wdl.dat");
+    let compact_solution_path = Path::new(file!())
+        .parent()
+        .unwrap()
+        .parent()
+        .unwrap()
+        .join("compact_solution.dat");
+    let optimal_children_path = Path::new(file!())
+        .parent()
+        .unwrap()
+        .parent()
+        .unwrap()
+        .join("optimal_children.dat");
+    let move_outcomes_path = Path::new(file!())
+        .parent()
+        .unwrap()
+        .parent()
+        .unwrap()
+        .join("move_outcomes.dat");
+    let proof_tree_path = Path::new(file!())
+        .parent()
+        .unwrap()
+        .parent()
+        .unwrap()
+        .join("proof_tree.dat");
+    let training_data_path = Path::new(file!())
+        .parent()
+        .unwrap()
+        .parent()
+        .unwrap()
+        .join("training_data.dat");
+    let summary_path = Path::new(file!())
+        .parent()
+        .unwrap()
+        .parent()
+        .unwrap()
+        .join("summary.json");
+    let checkpoint_dir = Path::new(file!())
+        .parent()
+        .unwrap()
+        .parent()
+        .unwrap()
+        .join("checkpoints");
 
-    let solution = load_or_compute_solution_and_log(&solution_path);
+    // Loading the solution takes minutes, so we only do it
+    // for commands that need the whole thing.
+    let solution = if solution_path.exists() {
+        None
+    } else {
+        Some(compute_solution_and_log(
+            &solution_path,
+            &summary_path,
+            &checkpoint_dir,
+        ))
+    };
 
     let mut input_buffer = String::with_capacity(256);
 
-    println!("Tree inspector ready. Type \"launch\" to launch or \"simpledb\" to create a simple best-child database.");
+    println!("Tree inspector ready. Type \"launch\" to launch, \"simpledb\" to create a simple best-child database, \"wdl\" to create a win/draw/loss table, \"compact\" to create a compact solution, \"allbest\" to record every optimal move, \"moves\" to record the outcome of every move, \"proof\" to export a proof of the initial position's result, \"checkproof\" to check that proof, \"dataset\" to export a training dataset, \"summary\" to create a summary report, \"extremes\" to list the longest wins and losses, \"evalaccuracy\" to grade the heuristic evaluation, \"fitweights\" to fit its weights to the solution, or \"verify\" to verify the solution.");
     println!("Launching will clear the console, so be sure to save any important information.");
     loop {
         input_buffer.clear();
@@ -42,20 +101,94 @@ fn main() {
         let trimmed_input = input_buffer.trim();
 
         if trimmed_input == "launch" {
-            launch_tree_inspector(&solution);
+            let mut solution = SolutionIndex::open(&solution_path, Ruleset::SimplifiedTry)
+                .unwrap_or_else(|error| panic!("Failed to open {:?}: {error}", solution_path));
+            launch_tree_inspector(&mut solution);
+            break;
+        }
+
+        if trimmed_input == "checkproof" {
+            check_proof_tree_and_log(&proof_tree_path);
             break;
         }
 
         if trimmed_input == "simpledb" {
+            let solution = solution.unwrap_or_else(|| load_solution_and_log(&solution_path));
             create_simple_db(&solution, &simple_db_path);
             break;
         }
 
-        println!("Invalid command. Type \"launch\" to launch or \"simpledb\" to create a simple best-child database.");
+        if trimmed_input == "wdl" {
+            let solution = solution.unwrap_or_else(|| load_solution_and_log(&solution_path));
+            create_wdl_table(&solution, &wdl_table_path);
+            break;
+        }
+
+        if trimmed_input == "compact" {
+            let solution = solution.unwrap_or_else(|| load_solution_and_log(&solution_path));
+            create_compact_solution(&solution, &compact_solution_path);
+            break;
+        }
+
+        if trimmed_input == "allbest" {
+            let solution = solution.unwrap_or_else(|| load_solution_and_log(&solution_path));
+            create_optimal_children(&solution, &optimal_children_path);
+            break;
+        }
+
+        if trimmed_input == "moves" {
+            let solution = solution.unwrap_or_else(|| load_solution_and_log(&solution_path));
+            create_move_outcome_table(&solution, &move_outcomes_path);
+            break;
+        }
+
+        if trimmed_input == "proof" {
+            let solution = solution.unwrap_or_else(|| load_solution_and_log(&solution_path));
+            create_proof_tree(&solution, &proof_tree_path);
+            break;
+        }
+
+        if trimmed_input == "dataset" {
+            let solution = solution.unwrap_or_else(|| load_solution_and_log(&solution_path));
+            create_training_dataset(&solution, &training_data_path);
+            break;
+        }
+
+        if trimmed_input == "summary" {
+            let solution = solution.unwrap_or_else(|| load_solution_and_log(&solution_path));
+            create_summary(&solution, &summary_path);
+            break;
+        }
+
+        if trimmed_input == "extremes" {
+            let solution = solution.unwrap_or_else(|| load_solution_and_log(&solution_path));
+            print_extreme_positions(&solution);
+            break;
+        }
+
+        if trimmed_input == "evalaccuracy" {
+            let solution = solution.unwrap_or_else(|| load_solution_and_log(&solution_path));
+            measure_evaluation_accuracy_and_log(&solution);
+            break;
+        }
+
+        if trimmed_input == "fitweights" {
+            let solution = solution.unwrap_or_else(|| load_solution_and_log(&solution_path));
+            fit_evaluation_weights_and_log(&solution);
+            break;
+        }
+
+        if trimmed_input == "verify" {
+            let solution = solution.unwrap_or_else(|| load_solution_and_log(&solution_path));
+            verify_solution_and_log(&solution);
+            break;
+        }
+
+        println!("Invalid command. Type \"launch\" to launch, \"simpledb\" to create a simple best-child database, \"wdl\" to create a win/draw/loss table, \"compact\" to create a compact solution, \"allbest\" to record every optimal move, \"moves\" to record the outcome of every move, \"proof\" to export a proof of the initial position's result, \"checkproof\" to check that proof, \"dataset\" to export a training dataset, \"summary\" to create a summary report, \"extremes\" to list the longest wins and losses, \"evalaccuracy\" to grade the heuristic evaluation, \"fitweights\" to fit its weights to the solution, or \"verify\" to verify the solution.");
     }
 }
 
-fn launch_tree_inspector(solution: &BestChildMap) {
+fn launch_tree_inspector(solution: &mut SolutionIndex<File>) {
     let mut input_buffer = String::with_capacity(256);
 
     let mut history = vec![(State::initial(), State::initial().best_outcome(solution))];
@@ -71,7 +204,7 @@ fn launch_tree_inspector(solution: &BestChildMap) {
                 .with_stats(StateStats::new(top_outcome, 127))
                 .pretty()
         );
-        match top_state.best_child_index(&solution) {
+        match top_state.best_child_index(solution) {
             Some(i) => println!("Best child index: {i}.",),
             None => println!("Best child index: None (state is terminal)."),
         }
@@ -84,6 +217,18 @@ fn launch_tree_inspector(solution: &BestChildMap) {
                 .collect::<Vec<StateAndStats>>()
                 .pretty()
         );
+        if let Some(moves) = solution.classify_moves(top_state).unwrap() {
+            println!("Moves:");
+            for classified in moves {
+                println!(
+                    "    {}: {} ({}, {} worse than the best move)",
+                    classified.index,
+                    classified.outcome.pretty(),
+                    classified.quality,
+                    classified.delta
+                );
+            }
+        }
         println!("Enter a command: ");
         input_buffer.clear();
         std::io::stdin().read_line(&mut input_buffer).unwrap();
@@ -99,6 +244,7 @@ fn launch_tree_inspector(solution: &BestChildMap) {
                 println!("    help: Print this help message.");
                 println!("    parent: Go to the parent state.");
                 println!("    child <index>: Go to the child at the given index.");
+                println!("    pv: Print the optimal line from the current state.");
             }
 
             Command::Parent => {
@@ -109,6 +255,24 @@ fn launch_tree_inspector(solution: &BestChildMap) {
                 }
             }
 
+            Command::PrincipalVariation => {
+                let top_state = history.last().unwrap().0;
+                let line = solution.principal_variation(top_state).unwrap();
+                println!("Principal variation ({} plies):", line.len());
+                for (ply, (action, state, outcome)) in line.into_iter().enumerate() {
+                    println!(
+                        "{}. {} ({})\n{}",
+                        ply + 1,
+                        action.pretty(),
+                        outcome.pretty(),
+                        state.pretty()
+                    );
+                }
+                println!("Press Enter to continue.");
+                input_buffer.clear();
+                std::io::stdin().read_line(&mut input_buffer).unwrap();
+            }
+
             Command::Child(index) => {
                 let children = history.last().unwrap().0.children();
                 if index >= children.len() {
@@ -123,18 +287,13 @@ fn launch_tree_inspector(solution: &BestChildMap) {
 }
 
 trait StateHelperMethods {
-    fn best_child_index(self, solution: &BestChildMap) -> Option<usize>;
-    fn best_outcome(self, solution: &BestChildMap) -> Outcome;
+    fn best_child_index(self, solution: &mut SolutionIndex<File>) -> Option<usize>;
+    fn best_outcome(self, solution: &mut SolutionIndex<File>) -> Outcome;
 }
 
 impl StateHelperMethods for State {
-    fn best_child_index(self, solution: &BestChildMap) -> Option<usize> {
-        let best_child = solution.get(self);
-        if best_child.is_null() {
-            return None;
-        }
-
-        let best_child = best_child.state();
+    fn best_child_index(self, solution: &mut SolutionIndex<File>) -> Option<usize> {
+        let best_child = solution.best_child(self).unwrap()?.state();
 
         let mut i = 0;
         let mut best_child_index = None;
@@ -149,30 +308,13 @@ impl StateHelperMethods for State {
         best_child_index
     }
 
-    fn best_outcome(self, solution: &BestChildMap) -> Outcome {
-        match self.terminality() {
-            Terminality::Loss => return Outcome::loss_in(0),
-            Terminality::Win => return Outcome::win_in(0),
-            Terminality::Nonterminal => {}
-        }
-
-        let child = solution.get(self);
-        assert!(
-            !child.is_null(),
-            "Cannot find best child of non-terminal state.\n\nSTATE:\n\n{}",
-            self.pretty()
-        );
-
-        if child.stats().required_child_report_count() > 0 {
-            return Outcome::DRAW;
-        }
-
-        child
-            .stats()
-            .best_outcome()
-            .unwrap()
-            .invert()
-            .delay_by_one()
+    fn best_outcome(self, solution: &mut SolutionIndex<File>) -> Outcome {
+        solution.outcome(self).unwrap().unwrap_or_else(|| {
+            panic!(
+                "Cannot find best child of non-terminal state.\n\nSTATE:\n\n{}",
+                self.pretty()
+            )
+        })
     }
 }
 
@@ -192,21 +334,28 @@ fn create_simple_db(solution: &BestChildMap, simple_db_path: &Path) {
     );
 
     let start_time = Instant::now();
-    let mut prev_time = start_time;
-    let mut countup = 0;
-    let mut checkpoints = 0;
-    const CHECKPOINT_SIZE: u64 = 1_000_000;
+    let mut observer = PlainTextObserver::stdout();
+    let mut tracker = PhaseTracker::resume(
+        &mut observer,
+        SolverPhase::SimpleDatabase,
+        Some(solution.len()),
+        0,
+    );
 
     const U64_BYTES: usize = std::mem::size_of::<u64>();
     const STATES_PER_PACKET: usize = 1000;
     const PACKETS_PER_DIRECTORY: usize = 1000;
     let mut packet_buffer: Vec<u8> = Vec::with_capacity(U64_BYTES * STATES_PER_PACKET);
+    let mut optimal_packet_buffer: Vec<u8> = vec![];
+    let optimal_db_path = simple_db_path.join("optimal");
     let mut parent_of_most_recent_packet_addition: Option<State> = None;
     let mut byte_quintuplets_representing_packet_parent_shifted_state_maximums: Vec<u8> = vec![];
 
-    fs::create_dir(&simple_db_path).unwrap();
+    fs::create_dir(simple_db_path).unwrap();
     solution.visit_in_key_order(|parent, child| {
         packet_buffer.extend_from_slice(&child.0.to_le_bytes());
+        OptimalChildren::of(solution, parent)
+            .append_packet_entry(parent, &mut optimal_packet_buffer);
         parent_of_most_recent_packet_addition = Some(parent);
 
         if packet_buffer.len() == U64_BYTES * STATES_PER_PACKET {
@@ -218,25 +367,25 @@ fn create_simple_db(solution: &BestChildMap, simple_db_path: &Path) {
             let file_path = prefix.join(format!("{}.dat", packet_index % PACKETS_PER_DIRECTORY));
             fs::write(&file_path, &packet_buffer).unwrap();
 
+            let optimal_prefix =
+                optimal_db_path.join(format!("{}", packet_index / PACKETS_PER_DIRECTORY));
+            fs::create_dir_all(&optimal_prefix).unwrap();
+            let optimal_file_path =
+                optimal_prefix.join(format!("{}.dat", packet_index % PACKETS_PER_DIRECTORY));
+            fs::write(&optimal_file_path, &optimal_packet_buffer).unwrap();
+
             let parent_state_bytes = parent.0.to_le_bytes();
             byte_quintuplets_representing_packet_parent_shifted_state_maximums
                 .extend_from_slice(&parent_state_bytes[0..5]);
             packet_buffer.clear();
+            optimal_packet_buffer.clear();
         }
 
-        countup += 1;
-
-        if countup >= CHECKPOINT_SIZE {
-            countup %= CHECKPOINT_SIZE;
-            checkpoints += 1;
-            println!(
-                "Reached {checkpoints} database checkpoints. Duration: {:?}",
-                prev_time.elapsed()
-            );
-            prev_time = Instant::now();
-        }
+        tracker.tick(None);
     });
 
+    tracker.finish(None);
+
     if let Some(parent) = parent_of_most_recent_packet_addition {
         if !packet_buffer.is_empty() {
             let packet_index =
@@ -247,10 +396,18 @@ fn create_simple_db(solution: &BestChildMap, simple_db_path: &Path) {
             let file_path = prefix.join(format!("{}.dat", packet_index % PACKETS_PER_DIRECTORY));
             fs::write(&file_path, &packet_buffer).unwrap();
 
+            let optimal_prefix =
+                optimal_db_path.join(format!("{}", packet_index / PACKETS_PER_DIRECTORY));
+            fs::create_dir_all(&optimal_prefix).unwrap();
+            let optimal_file_path =
+                optimal_prefix.join(format!("{}.dat", packet_index % PACKETS_PER_DIRECTORY));
+            fs::write(&optimal_file_path, &optimal_packet_buffer).unwrap();
+
             let parent_state_bytes = parent.0.to_le_bytes();
             byte_quintuplets_representing_packet_parent_shifted_state_maximums
                 .extend_from_slice(&parent_state_bytes[0..5]);
             packet_buffer.clear();
+            optimal_packet_buffer.clear();
         }
     }
 
@@ -268,212 +425,365 @@ fn create_simple_db(solution: &BestChildMap, simple_db_path: &Path) {
     );
 }
 
-fn load_or_compute_solution_and_log(solution_path: &Path) -> StateMap<StateAndStats> {
-    if solution_path.exists() {
-        println!("Loading best child map from {:?}.", solution_path);
-        let mut file = File::open(&solution_path).unwrap();
-        let mut out = StateMap::empty();
-        const CHECKPOINT_SIZE: usize = 10_000_000;
-        const U64_BYTES: usize = std::mem::size_of::<u64>();
-        let mut buffer: Box<[u8; CHECKPOINT_SIZE * 2 * U64_BYTES]> =
-            Box::new([0; CHECKPOINT_SIZE * 2 * U64_BYTES]);
-        let mut buffer_len = 0;
-        let mut checkpoints = 0;
-        let start_time = Instant::now();
-        loop {
-            let bytes_read = file.read(&mut buffer[buffer_len..]).unwrap();
-            if bytes_read == 0 && !buffer[buffer_len..].is_empty() {
-                break;
-            }
+fn create_wdl_table(solution: &BestChildMap, wdl_table_path: &Path) {
+    if wdl_table_path.exists() {
+        println!("WDL table already exists at {:?}.", wdl_table_path);
+        println!("Aborting.");
+        return;
+    }
 
-            buffer_len += bytes_read;
+    println!("Creating WDL table at {:?}.", wdl_table_path);
 
-            if buffer_len == CHECKPOINT_SIZE * 2 * U64_BYTES {
-                for i in (0..buffer_len).step_by(2 * U64_BYTES) {
-                    let mut parent_bytes = [0; U64_BYTES];
-                    parent_bytes.copy_from_slice(&buffer[i..i + U64_BYTES]);
-                    let parent = State(u64::from_le_bytes(parent_bytes));
+    let start_time = Instant::now();
+    let table = wdl_table_from_best_child_map(solution, &mut PlainTextObserver::stdout());
 
-                    let mut child_bytes = [0; U64_BYTES];
-                    child_bytes.copy_from_slice(&buffer[i + U64_BYTES..i + 2 * U64_BYTES]);
-                    let child = StateAndStats(u64::from_le_bytes(child_bytes));
+    table
+        .write_to(File::create(wdl_table_path).unwrap())
+        .unwrap();
 
-                    out.add(parent, child);
-                }
+    println!(
+        "Created WDL table ({} bytes) at {:?}. It took {:?}.",
+        WdlTable::BYTE_LEN,
+        wdl_table_path,
+        start_time.elapsed()
+    );
+}
 
-                buffer_len = 0;
+fn create_compact_solution(solution: &BestChildMap, compact_solution_path: &Path) {
+    if compact_solution_path.exists() {
+        println!(
+            "Compact solution already exists at {:?}.",
+            compact_solution_path
+        );
+        println!("Aborting.");
+        return;
+    }
 
-                checkpoints += 1;
-                println!("Loaded {checkpoints} best child checkpoints.");
-            }
-        }
+    println!("Creating compact solution at {:?}.", compact_solution_path);
 
-        for i in (0..buffer_len).step_by(2 * U64_BYTES) {
-            let mut parent_bytes = [0; U64_BYTES];
-            parent_bytes.copy_from_slice(&buffer[i..i + U64_BYTES]);
-            let parent = State(u64::from_le_bytes(parent_bytes));
+    let start_time = Instant::now();
+    let compact = compact_best_child_map(solution, &mut PlainTextObserver::stdout());
 
-            let mut child_bytes = [0; U64_BYTES];
-            child_bytes.copy_from_slice(&buffer[i + U64_BYTES..i + 2 * U64_BYTES]);
-            let child = StateAndStats(u64::from_le_bytes(child_bytes));
+    let mut writer = BufWriter::new(File::create(compact_solution_path).unwrap());
+    write_compact_solution(&compact, &mut writer).unwrap();
+    writer.flush().unwrap();
 
-            out.add(parent, child);
-        }
+    println!(
+        "Created compact solution ({} states) at {:?}. It took {:?}.",
+        compact.len(),
+        compact_solution_path,
+        start_time.elapsed()
+    );
+}
 
+fn create_optimal_children(solution: &BestChildMap, optimal_children_path: &Path) {
+    if optimal_children_path.exists() {
         println!(
-            "Loaded best child map ({} states) from {:?}. It took {:?}.",
-            CHECKPOINT_SIZE * checkpoints + buffer_len / (2 * U64_BYTES),
-            solution_path,
-            start_time.elapsed()
+            "Optimal children file already exists at {:?}.",
+            optimal_children_path
         );
+        println!("Aborting.");
+        return;
+    }
 
-        out
-    } else {
-        let reachable_states = compute_reachable_states_and_log();
-        let mut stats_map = compute_initial_stats_map_and_log(&reachable_states);
-        std::mem::drop(reachable_states);
-        compute_state_stats_and_log(&mut stats_map);
-        let stats_map = stats_map;
-
-        println!("Computing best child map. This will probably take a while.");
-
-        let start_time = Instant::now();
-        let mut prev_time = start_time;
-        let mut countup = 0;
-        let mut checkpoints = 0;
-        const CHECKPOINT_SIZE: u64 = 1_000_000;
-
-        let solution = best_child_map(&stats_map, |_| {
-            countup += 1;
-
-            if countup >= CHECKPOINT_SIZE {
-                countup %= CHECKPOINT_SIZE;
-                checkpoints += 1;
-                println!(
-                    "Found best children for {checkpoints} checkpoints. Duration: {:?}",
-                    prev_time.elapsed()
-                );
-                prev_time = Instant::now();
-            }
-        });
+    println!(
+        "Creating optimal children file at {:?}.",
+        optimal_children_path
+    );
+
+    let start_time = Instant::now();
+    let optimal = optimal_children_map(solution, &mut PlainTextObserver::stdout());
+
+    let mut writer = BufWriter::new(File::create(optimal_children_path).unwrap());
+    write_optimal_children(&optimal, &mut writer).unwrap();
+    writer.flush().unwrap();
+
+    println!(
+        "Created optimal children file ({} states) at {:?}. It took {:?}.",
+        optimal.len(),
+        optimal_children_path,
+        start_time.elapsed()
+    );
+}
+
+fn create_move_outcome_table(solution: &BestChildMap, move_outcomes_path: &Path) {
+    if move_outcomes_path.exists() {
         println!(
-            "Computed best child map for {} states. It took {:?}.",
-            checkpoints * CHECKPOINT_SIZE + countup,
-            start_time.elapsed()
+            "Move outcome table already exists at {:?}.",
+            move_outcomes_path
         );
+        println!("Aborting.");
+        return;
+    }
 
-        {
-            let mut file = File::create(&solution_path).unwrap();
-            let mut out_buffer =
-                Vec::with_capacity(2 * std::mem::size_of::<u64>() * (CHECKPOINT_SIZE as usize));
-            solution.visit_in_key_order(|parent, child| {
-                out_buffer.extend_from_slice(&parent.0.to_le_bytes());
-                out_buffer.extend_from_slice(&child.0.to_le_bytes());
-
-                if out_buffer.len() >= 2 * std::mem::size_of::<u64>() * (CHECKPOINT_SIZE as usize) {
-                    file.write_all(&out_buffer).unwrap();
-                    out_buffer.clear();
-                }
-            });
-            file.write_all(&out_buffer).unwrap();
-            out_buffer.clear();
-        }
+    println!("Creating move outcome table at {:?}.", move_outcomes_path);
+
+    let start_time = Instant::now();
+    let table = MoveOutcomeTable::from_best_child_map(solution, &mut PlainTextObserver::stdout());
+
+    let mut writer = BufWriter::new(File::create(move_outcomes_path).unwrap());
+    table.write_to(&mut writer).unwrap();
+    writer.flush().unwrap();
 
-        println!("Wrote best child map to {:?}.", solution_path);
-        solution
+    println!(
+        "Created move outcome table ({} states) at {:?}. It took {:?}.",
+        table.len(),
+        move_outcomes_path,
+        start_time.elapsed()
+    );
+}
+
+fn create_proof_tree(solution: &BestChildMap, proof_tree_path: &Path) {
+    if proof_tree_path.exists() {
+        println!("Proof tree already exists at {:?}.", proof_tree_path);
+        println!("Aborting.");
+        return;
     }
+
+    println!("Creating proof tree at {:?}.", proof_tree_path);
+
+    let start_time = Instant::now();
+    let Some(tree) = proof_tree(solution, State::initial()) else {
+        println!("The initial position is a draw, so there is nothing to prove.");
+        return;
+    };
+
+    let mut writer = BufWriter::new(File::create(proof_tree_path).unwrap());
+    tree.write_to(&mut writer).unwrap();
+    writer.flush().unwrap();
+
+    println!(
+        "Created proof tree ({} prover moves) for outcome {} at {:?}. It took {:?}.",
+        tree.moves.len(),
+        tree.outcome.pretty(),
+        proof_tree_path,
+        start_time.elapsed()
+    );
 }
 
-fn compute_state_stats_and_log(stats_map: &mut StateMap<StateStats>) {
-    println!("Starting retrograde analysis. This will probably take several hours.");
+fn create_training_dataset(solution: &BestChildMap, training_data_path: &Path) {
+    const SAMPLE_COUNT: usize = 1_000_000;
+    const SEED: u64 = 2024;
+
+    if training_data_path.exists() {
+        println!(
+            "Training dataset already exists at {:?}.",
+            training_data_path
+        );
+        println!("Aborting.");
+        return;
+    }
+
+    println!(
+        "Creating training dataset ({SAMPLE_COUNT} samples, seed {SEED}) at {:?}.",
+        training_data_path
+    );
+
     let start_time = Instant::now();
-    let mut prev_time = start_time;
-    let mut countup = 0;
-    let mut checkpoints = 0;
-    let mut progress = Progress::default();
-    const CHECKPOINT_SIZE: u64 = 1_000_000;
-    compute_stats(stats_map, &mut progress, |current_progress| {
-        countup += 1;
-
-        if countup >= CHECKPOINT_SIZE {
-            countup %= CHECKPOINT_SIZE;
-            checkpoints += 1;
-            println!(
-                "Backtracked {checkpoints} checkpoints. Duration: {:?}",
-                prev_time.elapsed()
-            );
-            println!("Progress:\n{:#?}", current_progress);
-            println!();
-            prev_time = Instant::now();
-
-            true
-        } else {
-            false
-        }
-    });
+    let dataset = TrainingDataset::sample(
+        solution,
+        SAMPLE_COUNT,
+        SEED,
+        &mut PlainTextObserver::stdout(),
+    );
+
+    let mut writer = BufWriter::new(File::create(training_data_path).unwrap());
+    dataset.write_to(&mut writer).unwrap();
+    writer.flush().unwrap();
+
     println!(
-        "Completed retrograde analysis on {} states. It took {:?}.",
-        checkpoints * CHECKPOINT_SIZE + countup,
+        "Created training dataset ({} samples) at {:?}. It took {:?}.",
+        dataset.samples.len(),
+        training_data_path,
         start_time.elapsed()
     );
 }
 
-fn compute_initial_stats_map_and_log(reachable: &StateSet) -> StateMap<StateStats> {
-    println!("Initializing stat map.");
+fn check_proof_tree_and_log(proof_tree_path: &Path) {
+    println!("Checking proof tree at {:?}.", proof_tree_path);
+
     let start_time = Instant::now();
-    let mut prev_time = start_time;
-    let mut countup = 0;
-    let mut checkpoints = 0;
-    const CHECKPOINT_SIZE: u64 = 1_000_000;
-    let stats_map = initial_stat_map(reachable, |_| {
-        countup += 1;
-
-        if countup >= CHECKPOINT_SIZE {
-            countup %= CHECKPOINT_SIZE;
-            checkpoints += 1;
-            println!(
-                "Processed {checkpoints} checkpoints. Duration: {:?}",
-                prev_time.elapsed()
-            );
-            prev_time = Instant::now();
-        }
-    });
+    let file = File::open(proof_tree_path)
+        .unwrap_or_else(|error| panic!("Failed to open {:?}: {error}", proof_tree_path));
+    let tree = ProofTree::read_from(std::io::BufReader::new(file)).unwrap();
+
+    match check_proof_tree(&tree) {
+        Ok(report) => println!(
+            "The proof tree proves outcome {} ({} reachable moves, {} unreachable moves). It took {:?}.",
+            tree.outcome.pretty(),
+            report.reachable_move_count,
+            report.unreachable_move_count,
+            start_time.elapsed()
+        ),
+
+        Err(error) => println!(
+            "The proof tree is invalid. It took {:?}.\n\n{error}",
+            start_time.elapsed()
+        ),
+    }
+}
+
+fn create_summary(solution: &BestChildMap, summary_path: &Path) {
+    println!("Creating summary report at {:?}.", summary_path);
+
+    let start_time = Instant::now();
+    // The solution file does not record how long the solve took,
+    // so this summary has no phase timings.
+    let summary = SolutionSummary::from_best_child_map(solution, State::initial());
+    let json = summary.to_json();
+    fs::write(summary_path, format!("{json}\n")).unwrap();
+
+    println!("{json}");
+    println!(
+        "Created summary report at {:?}. It took {:?}.",
+        summary_path,
+        start_time.elapsed()
+    );
+}
+
+fn print_extreme_positions(solution: &BestChildMap) {
+    const POSITION_COUNT: usize = 10;
+
+    println!("Searching for the {POSITION_COUNT} longest wins and losses.");
+
+    let start_time = Instant::now();
+    let catalogue = extreme_positions(solution, POSITION_COUNT, &mut PlainTextObserver::stdout());
+
+    for (i, position) in catalogue.longest_wins.iter().enumerate() {
+        println!("----------------------------------------------------------------");
+        println!("Longest win {i}: {position}");
+    }
+
+    for (i, position) in catalogue.longest_losses.iter().enumerate() {
+        println!("----------------------------------------------------------------");
+        println!("Longest loss {i}: {position}");
+    }
+
     println!(
-        "Initialized stat map with {} states. It took {:?}.",
-        checkpoints * CHECKPOINT_SIZE + countup,
+        "Found {} wins and {} losses. It took {:?}.",
+        catalogue.longest_wins.len(),
+        catalogue.longest_losses.len(),
         start_time.elapsed()
     );
+}
+
+fn measure_evaluation_accuracy_and_log(solution: &BestChildMap) {
+    const SAMPLE_INTERVAL: u64 = 1_000;
 
-    stats_map
+    println!("Grading the heuristic evaluation on every {SAMPLE_INTERVAL}th state.");
+
+    let start_time = Instant::now();
+    let accuracy = measure_evaluation_accuracy(
+        solution,
+        evaluate,
+        SAMPLE_INTERVAL,
+        &mut PlainTextObserver::stdout(),
+    );
+
+    println!("{accuracy}");
+    println!("It took {:?}.", start_time.elapsed());
 }
 
-fn compute_reachable_states_and_log() -> StateSet {
-    println!("Computing reachable states. This will probably take a while (around 45 minutes on my 2018 Macbook Pro).");
+fn fit_evaluation_weights_and_log(solution: &BestChildMap) {
+    const SAMPLE_INTERVAL: u64 = 100;
+
+    println!("Fitting evaluation weights to every {SAMPLE_INTERVAL}th state.");
+
     let start_time = Instant::now();
-    let mut prev_time = start_time;
-    let mut countup = 0;
-    let mut checkpoints = 0;
-    const CHECKPOINT_SIZE: u64 = 1_000_000;
-    let reachable_states = reachable_states(State::initial(), |_| {
-        countup += 1;
-
-        if countup >= CHECKPOINT_SIZE {
-            countup %= CHECKPOINT_SIZE;
-            checkpoints += 1;
-            println!(
-                "Reached {checkpoints} checkpoints. Duration: {:?}",
-                prev_time.elapsed()
-            );
-            prev_time = Instant::now();
-        }
-    });
+    let mut observer = PlainTextObserver::stdout();
+    let fit = fit_evaluation_weights_from_best_child_map(solution, SAMPLE_INTERVAL, &mut observer);
+    println!("{fit}");
+
+    let weights = fit.weights();
+    let accuracy = measure_evaluation_accuracy(
+        solution,
+        |state| weights.evaluate(state),
+        1_000,
+        &mut observer,
+    );
+    println!("Fitted weights:\n{accuracy}");
+    let accuracy = measure_evaluation_accuracy(solution, evaluate, 1_000, &mut observer);
+    println!("Default weights:\n{accuracy}");
+
+    println!("It took {:?}.", start_time.elapsed());
+}
+
+fn verify_solution_and_log(solution: &BestChildMap) {
+    println!("Verifying solution.");
+
+    let start_time = Instant::now();
+    let report = verify_solution(solution, State::initial(), &mut PlainTextObserver::stdout());
+
+    for (i, violation) in report.violations.iter().enumerate() {
+        println!("----------------------------------------------------------------");
+        println!("Violation {i}: {violation}");
+    }
+
+    if report.is_valid() {
+        println!(
+            "Verified solution ({} states). It is valid. It took {:?}.",
+            report.checked_state_count,
+            start_time.elapsed()
+        );
+    } else {
+        println!(
+            "Verified solution ({} states). Found {} violations (showing the first {}). It took {:?}.",
+            report.checked_state_count,
+            report.violation_count,
+            report.violations.len(),
+            start_time.elapsed()
+        );
+    }
+}
+
+fn load_solution_and_log(solution_path: &Path) -> StateMap<StateAndStats> {
+    println!("Loading best child map from {:?}.", solution_path);
+    let file = File::open(solution_path).unwrap();
+    let start_time = Instant::now();
+    let out = read_solution_file(
+        file,
+        Ruleset::SimplifiedTry,
+        &mut PlainTextObserver::stdout().with_report_interval(10_000_000),
+    )
+    .unwrap_or_else(|error| panic!("Failed to load {:?}: {error}", solution_path));
+
     println!(
-        "Computed all {} reachable states. It took {:?}.",
-        checkpoints * CHECKPOINT_SIZE + countup,
+        "Loaded best child map ({} states) from {:?}. It took {:?}.",
+        out.len(),
+        solution_path,
         start_time.elapsed()
     );
 
-    reachable_states
+    out
+}
+
+fn compute_solution_and_log(
+    solution_path: &Path,
+    summary_path: &Path,
+    checkpoint_dir: &Path,
+) -> StateMap<StateAndStats> {
+    let thread_count = std::thread::available_parallelism().map_or(1, |n| n.get());
+    println!("Solving on {thread_count} threads. This will probably take several hours.");
+    println!("Checkpoints will be saved to {checkpoint_dir:?}.");
+
+    let start_time = Instant::now();
+    let solution = Solver::new()
+        .thread_count(thread_count)
+        .checkpoint_dir(checkpoint_dir)
+        .progress(PlainTextObserver::stdout())
+        .output(SolutionFileSink(solution_path.to_path_buf()))
+        .output(SummarySink(summary_path.to_path_buf()))
+        .solve()
+        .unwrap_or_else(|error| panic!("Failed to solve: {error}"));
+
+    println!(
+        "Wrote best child map ({} states) to {:?}. Solving took {:?}.",
+        solution.best_child_map().len(),
+        solution_path,
+        start_time.elapsed()
+    );
+    println!("Wrote summary report to {:?}.", summary_path);
+    solution.into_best_child_map()
 }
 
 impl Command {
@@ -482,10 +792,10 @@ impl Command {
         match input {
             "help" => Ok(Self::Help),
             "parent" => Ok(Self::Parent),
+            "pv" => Ok(Self::PrincipalVariation),
             _ => {
-                if input.starts_with("child ") {
-                    let child_index: usize =
-                        input["child ".len()..].parse().map_err(std::mem::drop)?;
+                if let Some(child_index) = input.strip_prefix("child ") {
+                    let child_index: usize = child_index.parse().map_err(std::mem::drop)?;
                     Ok(Self::Child(child_index))
                 } else {
                     Err(())
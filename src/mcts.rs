@@ -0,0 +1,320 @@
+use super::*;
+
+use crate::prng::SplitMix64;
+
+use std::collections::HashSet;
+
+/// The number of iterations `MctsEngine` runs per search,
+/// unless `MctsEngine::iterations` says otherwise.
+pub const DEFAULT_MCTS_ITERATIONS: u64 = 10_000;
+
+/// The UCT exploration constant `MctsEngine` uses,
+/// unless `MctsEngine::exploration` says otherwise.
+pub const DEFAULT_EXPLORATION: f64 = std::f64::consts::SQRT_2;
+
+/// The number of plies after which a playout counts as a draw,
+/// unless `MctsEngine::max_playout_plies` says otherwise.
+pub const DEFAULT_MAX_PLAYOUT_PLIES: u16 = 200;
+
+/// How `MctsEngine` plays out positions beyond its tree.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Playout {
+    /// Every move is chosen uniformly at random.
+    Random,
+
+    /// A winning move is played if there is one.
+    /// Otherwise, the move with the best `evaluate` score is played,
+    /// except that one move in four is chosen at random
+    /// (so playouts do not keep repeating the same line).
+    Heuristic,
+}
+
+/// The statistics of one of the root's moves.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MctsMoveStats {
+    pub child: State,
+    pub visit_count: u64,
+
+    /// The average playout result for the player making the move,
+    /// where a win is 1, a draw is 0, and a loss is -1.
+    /// This is zero if the move was never visited.
+    pub mean_value: f64,
+}
+
+/// The result of an `MctsEngine` search,
+/// from the perspective of the root's active player.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MctsResult {
+    /// The most visited child,
+    /// or `None` if the root is terminal.
+    pub best_move: Option<State>,
+
+    /// The statistics of every move,
+    /// in the order of `State::visit_children`.
+    pub moves: Vec<MctsMoveStats>,
+
+    pub iteration_count: u64,
+}
+
+/// A Monte Carlo tree search player using UCT (UCB1 applied to trees).
+///
+/// Like `AlphaBetaEngine`, it only needs `State::visit_children`
+/// and `State::terminality`.
+/// A state that repeats an earlier state of its line counts as a draw.
+/// Searches are deterministic for a given seed.
+#[derive(Clone, Debug)]
+pub struct MctsEngine {
+    iterations: u64,
+    exploration: f64,
+    playout: Playout,
+    max_playout_plies: u16,
+    rng: SplitMix64,
+}
+
+/// A node of the search tree.
+/// The same state may appear in several nodes
+/// (i.e., transpositions are not merged).
+#[derive(Clone, Debug)]
+struct Node {
+    state: State,
+
+    /// The indices of the node's children in the arena,
+    /// or `None` if the node has not been expanded.
+    children: Option<Vec<usize>>,
+
+    visit_count: u64,
+
+    /// The sum of the playout results for the player
+    /// who moved to this node.
+    value_sum: f64,
+}
+
+impl Node {
+    const fn new(state: State) -> Self {
+        Self {
+            state,
+            children: None,
+            visit_count: 0,
+            value_sum: 0.0,
+        }
+    }
+
+    fn mean_value(&self) -> f64 {
+        if self.visit_count == 0 {
+            0.0
+        } else {
+            self.value_sum / self.visit_count as f64
+        }
+    }
+}
+
+impl MctsEngine {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            iterations: DEFAULT_MCTS_ITERATIONS,
+            exploration: DEFAULT_EXPLORATION,
+            playout: Playout::Random,
+            max_playout_plies: DEFAULT_MAX_PLAYOUT_PLIES,
+            rng: SplitMix64::new(seed),
+        }
+    }
+
+    /// The number of iterations (i.e., playouts) per search.
+    /// It must be positive.
+    pub fn iterations(mut self, iterations: u64) -> Self {
+        assert!(iterations > 0, "Iteration count must be positive.");
+        self.iterations = iterations;
+        self
+    }
+
+    pub fn exploration(mut self, exploration: f64) -> Self {
+        self.exploration = exploration;
+        self
+    }
+
+    pub fn playout(mut self, playout: Playout) -> Self {
+        self.playout = playout;
+        self
+    }
+
+    pub fn max_playout_plies(mut self, max_playout_plies: u16) -> Self {
+        self.max_playout_plies = max_playout_plies;
+        self
+    }
+
+    /// Searches `root` with a fresh tree.
+    /// The random number generator carries over between searches,
+    /// so repeated searches of the same root may differ.
+    pub fn search(&mut self, root: State) -> MctsResult {
+        if root.is_terminal() {
+            return MctsResult {
+                best_move: None,
+                moves: vec![],
+                iteration_count: 0,
+            };
+        }
+
+        let mut arena = vec![Node::new(root)];
+        for _ in 0..self.iterations {
+            self.iterate(&mut arena);
+        }
+
+        let moves: Vec<MctsMoveStats> = arena[0]
+            .children
+            .as_ref()
+            .expect("The root is expanded in the first iteration.")
+            .iter()
+            .map(|&index| MctsMoveStats {
+                child: arena[index].state,
+                visit_count: arena[index].visit_count,
+                mean_value: arena[index].mean_value(),
+            })
+            .collect();
+
+        // Ties go to the first move.
+        let best_move = moves
+            .iter()
+            .rev()
+            .max_by_key(|stats| stats.visit_count)
+            .map(|stats| stats.child);
+
+        MctsResult {
+            best_move,
+            moves,
+            iteration_count: self.iterations,
+        }
+    }
+
+    /// Runs one selection, expansion, playout, and backpropagation.
+    fn iterate(&mut self, arena: &mut Vec<Node>) {
+        let mut path = vec![0];
+        let mut line = HashSet::from([arena[0].state]);
+
+        // Selection
+        let result = loop {
+            let index = *path.last().unwrap();
+            let state = arena[index].state;
+
+            if let Some(result) = terminal_result(state) {
+                break result;
+            }
+
+            if arena[index].children.is_none() {
+                if arena[index].visit_count == 0 && index != 0 {
+                    break self.play_out(state, &line);
+                }
+
+                // Expansion
+                let first_child_index = arena.len();
+                state.visit_children(|child| arena.push(Node::new(child)));
+                arena[index].children = Some((first_child_index..arena.len()).collect());
+            }
+
+            let children = arena[index].children.as_ref().unwrap();
+            let child = self.select(arena, children, arena[index].visit_count);
+            path.push(child);
+
+            if !line.insert(arena[child].state) {
+                break 0.0;
+            }
+        };
+
+        // Backpropagation.
+        // `result` is from the perspective of the last node's active player,
+        // so it is a loss for the player who moved to that node.
+        let mut value = -result;
+        for &index in path.iter().rev() {
+            arena[index].visit_count += 1;
+            arena[index].value_sum += value;
+            value = -value;
+        }
+    }
+
+    /// Returns the child with the highest UCB1 score.
+    /// Unvisited children are tried first (in order).
+    fn select(&self, arena: &[Node], children: &[usize], parent_visit_count: u64) -> usize {
+        if let Some(&unvisited) = children
+            .iter()
+            .find(|&&index| arena[index].visit_count == 0)
+        {
+            return unvisited;
+        }
+
+        let log_parent_visits = (parent_visit_count as f64).ln();
+        let score = |index: usize| {
+            let node = &arena[index];
+            node.mean_value()
+                + self.exploration * (log_parent_visits / node.visit_count as f64).sqrt()
+        };
+
+        let mut best_index = children[0];
+        let mut best_score = score(best_index);
+        for &index in &children[1..] {
+            let score = score(index);
+            if score > best_score {
+                best_index = index;
+                best_score = score;
+            }
+        }
+        best_index
+    }
+
+    /// Plays out `state` and returns the result
+    /// for its active player.
+    /// `line` holds the states leading to `state` (including it).
+    fn play_out(&mut self, state: State, line: &HashSet<State>) -> f64 {
+        let mut line = line.clone();
+        let mut state = state;
+        let mut sign = 1.0;
+        let mut children = Vec::with_capacity(8 * 12);
+
+        for _ in 0..self.max_playout_plies {
+            children.clear();
+            state.visit_children(|child| children.push(child));
+
+            state = match self.playout {
+                Playout::Random => children[self.rng.below(children.len() as u64) as usize],
+                Playout::Heuristic => self.heuristic_move(&children),
+            };
+            sign = -sign;
+
+            if let Some(result) = terminal_result(state) {
+                return sign * result;
+            }
+
+            if !line.insert(state) {
+                return 0.0;
+            }
+        }
+
+        0.0
+    }
+
+    fn heuristic_move(&mut self, children: &[State]) -> State {
+        if let Some(&winning_move) = children
+            .iter()
+            .find(|child| child.terminality() == Terminality::Loss)
+        {
+            return winning_move;
+        }
+
+        if self.rng.below(4) == 0 {
+            return children[self.rng.below(children.len() as u64) as usize];
+        }
+
+        *children
+            .iter()
+            .min_by_key(|&&child| evaluate(child))
+            .expect("Nonterminal states have children.")
+    }
+}
+
+/// Returns the result of a terminal state for its active player,
+/// or `None` if the state is nonterminal.
+fn terminal_result(state: State) -> Option<f64> {
+    match state.terminality() {
+        Terminality::Win => Some(1.0),
+        Terminality::Loss => Some(-1.0),
+        Terminality::Nonterminal => None,
+    }
+}
@@ -0,0 +1,206 @@
+use super::*;
+
+use std::io::{self, Read, Write};
+
+/// A table that stores the outcome of every move
+/// of every state in a solution.
+///
+/// Each move's outcome is from the perspective of the player making it
+/// (i.e., the parent's active player),
+/// so the best move has the parent's own outcome.
+/// The moves are ordered by `State::visit_children`.
+/// Unsolved children are stored as draws.
+#[derive(Clone, Debug)]
+pub struct MoveOutcomeTable {
+    spans: StateMap<MoveOutcomeSpan>,
+    outcomes: Vec<Outcome>,
+}
+
+/// The location of a state's move outcomes in `MoveOutcomeTable::outcomes`.
+///
+/// The least significant 8 bits store the number of moves,
+/// and the remaining 56 bits store the index of the first move.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct MoveOutcomeSpan(u64);
+
+impl MoveOutcomeSpan {
+    const fn new(start: usize, len: u8) -> Self {
+        Self(((start as u64) << 8) | len as u64)
+    }
+
+    const fn start(self) -> usize {
+        (self.0 >> 8) as usize
+    }
+
+    const fn len(self) -> usize {
+        (self.0 & 0xFF) as usize
+    }
+}
+
+impl Null for MoveOutcomeSpan {
+    fn null() -> Self {
+        // A state never has 255 moves,
+        // so this can never represent a valid span.
+        Self(!0)
+    }
+}
+
+impl MoveOutcomeTable {
+    pub fn empty() -> Self {
+        Self {
+            spans: StateMap::empty(),
+            outcomes: vec![],
+        }
+    }
+
+    /// Builds the table for every entry of `best_child_map`.
+    ///
+    /// Each child's outcome is read from the child's own entry
+    /// (or from its terminality, if it is terminal),
+    /// so every nonterminal child of every entry should have an entry.
+    /// Children without one are treated as draws,
+    /// just like unsolved children in `best_child_map`.
+    pub fn from_best_child_map(
+        best_child_map: &StateMap<StateAndStats>,
+        observer: &mut (impl ProgressObserver + ?Sized),
+    ) -> Self {
+        let mut out = Self::empty();
+        let mut tracker = PhaseTracker::resume(
+            observer,
+            SolverPhase::MoveOutcomes,
+            Some(best_child_map.len()),
+            0,
+        );
+
+        best_child_map.visit_in_key_order(|parent, _| {
+            let mut move_outcomes = Vec::with_capacity(8 * 12);
+            parent.visit_children(|child| {
+                let child_outcome = best_child_map.outcome(child).unwrap_or(Outcome::DRAW);
+                move_outcomes.push(child_outcome.invert().delay_by_one());
+            });
+
+            out.add(parent, &move_outcomes);
+
+            tracker.tick(None);
+        });
+
+        tracker.finish(None);
+
+        out
+    }
+
+    /// Returns the number of states in the table.
+    pub fn len(&self) -> u64 {
+        self.spans.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    /// Returns the outcome of each of `state`'s moves,
+    /// in the order of `State::visit_children`,
+    /// or `None` if `state` is not in the table.
+    pub fn get(&self, state: State) -> Option<&[Outcome]> {
+        let span = self.spans.get(state);
+        if span.is_null() {
+            return None;
+        }
+
+        Some(&self.outcomes[span.start()..span.start() + span.len()])
+    }
+
+    /// Calls `visitor` with every state and its move outcomes,
+    /// in ascending order of state.
+    pub fn visit_in_key_order(&self, mut visitor: impl FnMut(State, &[Outcome])) {
+        self.spans.visit_in_key_order(|state, span| {
+            visitor(
+                state,
+                &self.outcomes[span.start()..span.start() + span.len()],
+            );
+        });
+    }
+
+    fn add(&mut self, state: State, move_outcomes: &[Outcome]) {
+        let span = MoveOutcomeSpan::new(self.outcomes.len(), move_outcomes.len() as u8);
+        self.outcomes.extend_from_slice(move_outcomes);
+        self.spans.add(state, span);
+    }
+
+    /// Each state is stored as a 5-byte state,
+    /// followed by a 1-byte move count `n`,
+    /// followed by `n` 2-byte outcomes,
+    /// all in little-endian order.
+    /// The states are sorted.
+    pub fn write_to(&self, mut writer: impl Write) -> io::Result<()> {
+        let mut result = Ok(());
+        let mut record = Vec::with_capacity(6 + 2 * 8 * 12);
+
+        self.visit_in_key_order(|state, move_outcomes| {
+            if result.is_err() {
+                return;
+            }
+
+            record.clear();
+            record.extend_from_slice(&state.0.to_le_bytes()[0..5]);
+            record.push(move_outcomes.len() as u8);
+            for outcome in move_outcomes {
+                record.extend_from_slice(&outcome.0.to_le_bytes());
+            }
+            result = writer.write_all(&record);
+        });
+
+        result
+    }
+
+    pub fn read_from(mut reader: impl Read) -> io::Result<Self> {
+        let mut out = Self::empty();
+        let mut header = [0; 6];
+        let mut outcome_bytes = [0; 2 * 8 * 12];
+        let mut move_outcomes = Vec::with_capacity(8 * 12);
+
+        loop {
+            let header_len = solution_file::read_up_to(&mut reader, &mut header)?;
+
+            if header_len == 0 {
+                return Ok(out);
+            }
+
+            let incomplete_record = || {
+                io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "The move outcome table ends with an incomplete record.",
+                )
+            };
+
+            if header_len < header.len() {
+                return Err(incomplete_record());
+            }
+
+            let mut state_bytes = [0; 8];
+            state_bytes[0..5].copy_from_slice(&header[0..5]);
+            let state = State(u64::from_le_bytes(state_bytes));
+
+            let move_count = header[5] as usize;
+            if move_count > 8 * 12 {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "The move outcome table has a record with too many moves.",
+                ));
+            }
+
+            let outcome_bytes = &mut outcome_bytes[0..2 * move_count];
+            if solution_file::read_up_to(&mut reader, outcome_bytes)? < outcome_bytes.len() {
+                return Err(incomplete_record());
+            }
+
+            move_outcomes.clear();
+            move_outcomes.extend(
+                outcome_bytes
+                    .chunks_exact(2)
+                    .map(|bytes| Outcome(i16::from_le_bytes([bytes[0], bytes[1]]))),
+            );
+            out.add(state, &move_outcomes);
+        }
+    }
+}
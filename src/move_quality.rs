@@ -0,0 +1,140 @@
+use super::*;
+
+use std::fmt::{self, Display, Formatter};
+use std::io::{Read, Seek};
+
+/// How a move compares to the best move available.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MoveQuality {
+    /// The move gives the best outcome.
+    Optimal,
+
+    /// The move keeps the same result (win, draw, or loss)
+    /// as the best move, but with a worse distance
+    /// (i.e., a slower win or a faster loss).
+    Inaccuracy,
+
+    /// The move gives a worse result than the best move
+    /// (i.e., it throws a win or a draw).
+    Blunder,
+}
+
+/// A legal move, classified from the perspective of the player making it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ClassifiedMove {
+    /// The index of the move,
+    /// where moves are ordered by `State::visit_children`.
+    pub index: u8,
+
+    /// The state the move leads to.
+    pub child: State,
+
+    /// The outcome of the move for the player making it.
+    pub outcome: Outcome,
+
+    /// How much worse the move is than the best move.
+    /// This is `best_outcome.0 - outcome.0`,
+    /// so it is zero for optimal moves and positive otherwise.
+    pub delta: i16,
+
+    pub quality: MoveQuality,
+}
+
+impl ClassifiedMove {
+    pub const fn wdl(self) -> Wdl {
+        self.outcome.wdl()
+    }
+}
+
+impl Display for MoveQuality {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            MoveQuality::Optimal => write!(f, "optimal"),
+            MoveQuality::Inaccuracy => write!(f, "inaccuracy"),
+            MoveQuality::Blunder => write!(f, "blunder"),
+        }
+    }
+}
+
+/// Classifies each move of `state`, given the outcome of each move
+/// for the player making it
+/// (e.g., the slice `MoveOutcomeTable::get` returns).
+///
+/// `move_outcomes` must be in the order of `State::visit_children`,
+/// and must have one outcome per child.
+/// Terminal states have no moves, so they return an empty vector.
+pub fn classify_move_outcomes(state: State, move_outcomes: &[Outcome]) -> Vec<ClassifiedMove> {
+    let Some(&best_outcome) = move_outcomes.iter().max() else {
+        return vec![];
+    };
+
+    let mut out = Vec::with_capacity(move_outcomes.len());
+    state.visit_children(|child| {
+        let index = out.len();
+        let outcome = move_outcomes[index];
+        let quality = if outcome == best_outcome {
+            MoveQuality::Optimal
+        } else if outcome.wdl() == best_outcome.wdl() {
+            MoveQuality::Inaccuracy
+        } else {
+            MoveQuality::Blunder
+        };
+
+        out.push(ClassifiedMove {
+            index: index as u8,
+            child,
+            outcome,
+            delta: best_outcome.0 - outcome.0,
+            quality,
+        });
+    });
+
+    assert_eq!(
+        move_outcomes.len(),
+        out.len(),
+        "The move outcome count does not match the child count."
+    );
+
+    out
+}
+
+impl Solution {
+    /// Classifies each legal move of `state`
+    /// (see `classify_move_outcomes`).
+    ///
+    /// Returns `None` if one of `state`'s children
+    /// is non-terminal and unreachable.
+    pub fn classify_moves(&self, state: State) -> Option<Vec<ClassifiedMove>> {
+        let mut move_outcomes = Vec::with_capacity(8 * 12);
+        let mut is_complete = true;
+        state.visit_children(|child| match self.outcome(child) {
+            Some(outcome) => move_outcomes.push(outcome.invert().delay_by_one()),
+            None => is_complete = false,
+        });
+
+        is_complete.then(|| classify_move_outcomes(state, &move_outcomes))
+    }
+}
+
+impl<R: Read + Seek> SolutionIndex<R> {
+    /// Classifies each legal move of `state`
+    /// (see `classify_move_outcomes`).
+    ///
+    /// Returns `None` if one of `state`'s children
+    /// is non-terminal and unreachable.
+    pub fn classify_moves(
+        &mut self,
+        state: State,
+    ) -> Result<Option<Vec<ClassifiedMove>>, SolutionFileError> {
+        let mut move_outcomes = Vec::with_capacity(8 * 12);
+        for child in state.children() {
+            let Some(outcome) = self.outcome(child)? else {
+                return Ok(None);
+            };
+
+            move_outcomes.push(outcome.invert().delay_by_one());
+        }
+
+        Ok(Some(classify_move_outcomes(state, &move_outcomes)))
+    }
+}
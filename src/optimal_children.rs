@@ -0,0 +1,216 @@
+use super::*;
+
+use std::io::{self, Read, Write};
+
+/// The number of bits in an `OptimalChildren` child mask.
+/// This is the conservative upper bound on the number of children
+/// (8 pieces * 12 destination squares) used by `<StateStats as Null>::null`.
+pub const CHILD_MASK_BITS: u32 = 8 * 12;
+
+/// This struct stores a state's best outcome
+/// and the indices of **every** child that achieves it.
+///
+/// The least significant 9 bits store the state's best outcome
+/// as an `i9` (i.e., a signed two's complement 9-bit integer).
+/// Note that this is the outcome of the state itself
+/// (not the outcome of its children).
+/// Unsolved states are stored as draws.
+///
+/// The next 96 bits store a mask of the optimal children,
+/// where bit `i` is set if the child with index `i` is optimal,
+/// and the children are ordered by `State::visit_children`.
+///
+/// The most significant 23 bits are always zero.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OptimalChildren(pub u128);
+
+impl OptimalChildren {
+    /// The number of bytes used to store each entry
+    /// of an optimal children file.
+    pub const RECORD_LEN: usize = 5 + 14;
+
+    pub const fn new(outcome: Outcome, child_mask: u128) -> Self {
+        Self((outcome.into_i9() as u128) | (child_mask << 9))
+    }
+
+    /// Returns the optimal children of `parent`,
+    /// which must have an entry in `best_child_map`.
+    ///
+    /// Each child's outcome is read from the child's own entry
+    /// (or from its terminality, if it is terminal),
+    /// so every nonterminal child of `parent` should have an entry.
+    /// Children without one are treated as draws,
+    /// just like unsolved children in `best_child_map`.
+    pub fn of(best_child_map: &StateMap<StateAndStats>, parent: State) -> Self {
+        let parent_outcome = best_child_map.get(parent).parent_outcome();
+
+        let mut i = 0;
+        let mut child_mask: u128 = 0;
+        parent.visit_children(|child| {
+            let child_outcome = best_child_map.outcome(child).unwrap_or(Outcome::DRAW);
+            if child_outcome.invert().delay_by_one() == parent_outcome {
+                child_mask |= 1 << i;
+            }
+
+            i += 1;
+        });
+
+        Self::new(parent_outcome, child_mask)
+    }
+
+    pub const fn outcome(self) -> Outcome {
+        Outcome::from_i9((self.0 & 0b1_1111_1111) as u64)
+    }
+
+    pub const fn child_mask(self) -> u128 {
+        self.0 >> 9
+    }
+
+    /// Returns the number of optimal children.
+    pub const fn len(self) -> u32 {
+        self.child_mask().count_ones()
+    }
+
+    pub const fn is_empty(self) -> bool {
+        self.child_mask() == 0
+    }
+
+    pub const fn is_optimal_index(self, child_index: u8) -> bool {
+        (child_index as u32) < CHILD_MASK_BITS && self.child_mask() & (1 << child_index) != 0
+    }
+
+    /// Returns whether `child` is an optimal child of `parent`.
+    /// Returns `false` if `child` is not a child of `parent`.
+    pub fn is_optimal(self, parent: State, child: State) -> bool {
+        let mut i = 0;
+        let mut out = false;
+        parent.visit_children(|candidate| {
+            if candidate == child && self.is_optimal_index(i) {
+                out = true;
+            }
+
+            i += 1;
+        });
+        out
+    }
+
+    /// Returns the optimal children of `parent`,
+    /// in the order of `State::visit_children`.
+    pub fn children(self, parent: State) -> Vec<State> {
+        let mut i = 0;
+        let mut out = vec![];
+        parent.visit_children(|child| {
+            if self.is_optimal_index(i) {
+                out.push(child);
+            }
+
+            i += 1;
+        });
+        out
+    }
+
+    /// Appends `parent`'s entry in an optimal move packet of the database
+    /// to `packet`:
+    /// the 5-byte parent, the number of optimal children (1 byte),
+    /// and then each optimal child (5 bytes each),
+    /// all in little-endian order.
+    ///
+    /// The children are listed as states (rather than as a mask)
+    /// so that clients do not need to generate children
+    /// in the same order as `State::visit_children`.
+    pub fn append_packet_entry(self, parent: State, packet: &mut Vec<u8>) {
+        let children = self.children(parent);
+        packet.extend_from_slice(&parent.0.to_le_bytes()[0..5]);
+        packet.push(children.len() as u8);
+        for child in children {
+            packet.extend_from_slice(&child.0.to_le_bytes()[0..5]);
+        }
+    }
+}
+
+impl Null for OptimalChildren {
+    fn null() -> Self {
+        // `!0` can never represent a valid `OptimalChildren` value,
+        // because its most significant bits are always zero.
+        Self(!0)
+    }
+}
+
+/// Builds an `OptimalChildren` entry for every entry of `best_child_map`
+/// (see `OptimalChildren::of`).
+pub fn optimal_children_map(
+    best_child_map: &StateMap<StateAndStats>,
+    observer: &mut (impl ProgressObserver + ?Sized),
+) -> StateMap<OptimalChildren> {
+    let mut out = StateMap::empty();
+    let mut tracker = PhaseTracker::resume(
+        observer,
+        SolverPhase::OptimalChildren,
+        Some(best_child_map.len()),
+        0,
+    );
+
+    best_child_map.visit_in_key_order(|parent, _| {
+        out.add(parent, OptimalChildren::of(best_child_map, parent));
+
+        tracker.tick(None);
+    });
+
+    tracker.finish(None);
+
+    out
+}
+
+/// Each entry is stored as a 5-byte parent state
+/// followed by the 14 least significant bytes of its `OptimalChildren`,
+/// both in little-endian order.
+/// The entries are sorted by their parents.
+pub fn write_optimal_children(
+    map: &StateMap<OptimalChildren>,
+    mut writer: impl Write,
+) -> io::Result<()> {
+    let mut result = Ok(());
+
+    map.visit_in_key_order(|parent, optimal| {
+        if result.is_err() {
+            return;
+        }
+
+        let mut record = [0; OptimalChildren::RECORD_LEN];
+        record[0..5].copy_from_slice(&parent.0.to_le_bytes()[0..5]);
+        record[5..19].copy_from_slice(&optimal.0.to_le_bytes()[0..14]);
+        result = writer.write_all(&record);
+    });
+
+    result
+}
+
+pub fn read_optimal_children(mut reader: impl Read) -> io::Result<StateMap<OptimalChildren>> {
+    let mut out = StateMap::empty();
+    let mut record = [0; OptimalChildren::RECORD_LEN];
+
+    loop {
+        let record_len = solution_file::read_up_to(&mut reader, &mut record)?;
+
+        if record_len == 0 {
+            return Ok(out);
+        }
+
+        if record_len < record.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "The optimal children file ends with an incomplete record.",
+            ));
+        }
+
+        let mut parent_bytes = [0; 8];
+        parent_bytes[0..5].copy_from_slice(&record[0..5]);
+        let parent = State(u64::from_le_bytes(parent_bytes));
+
+        let mut optimal_bytes = [0; 16];
+        optimal_bytes[0..14].copy_from_slice(&record[5..19]);
+        let optimal = OptimalChildren(u128::from_le_bytes(optimal_bytes));
+
+        out.add(parent, optimal);
+    }
+}
@@ -50,6 +50,7 @@ impl IntoPretty for StateAndStats {}
 impl IntoPretty for Hands {}
 impl IntoPretty for BoardWithPromotionData {}
 impl IntoPretty for Outcome {}
+impl IntoPretty for Action {}
 impl IntoPretty for Vec<State> {}
 impl IntoPretty for Vec<StateAndStats> {}
 
@@ -343,6 +344,18 @@ impl Hands {
     }
 }
 
+impl Display for Pretty<Action> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} to row {}, column {}",
+            self.0.actor_name(),
+            self.0.dest_row(),
+            self.0.dest_column()
+        )
+    }
+}
+
 impl Display for Pretty<Outcome> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         if self.0 .0 < 0 {
@@ -414,7 +427,7 @@ impl Display for Indented<'_> {
             };
 
             let line = &self.s[start..end];
-            write!(f, "{indent}{line}\n")?;
+            writeln!(f, "{indent}{line}")?;
             start = end + 1;
         }
     }
@@ -0,0 +1,68 @@
+use super::*;
+
+use std::collections::HashSet;
+use std::convert::Infallible;
+use std::io::{Read, Seek};
+
+/// Returns the line of play from `state`
+/// in which both players always move to their best child.
+///
+/// Each element is the action played, the state it leads to,
+/// and that state's outcome (from the perspective of its active player).
+/// `state` itself is not included.
+///
+/// The line ends at the first state without an entry
+/// (i.e., a terminal or unsolved state).
+/// Draws never end, so their lines end at the first state
+/// that repeats an earlier state of the line (including `state`).
+pub fn principal_variation(
+    state: State,
+    solution: &StateMap<StateAndStats>,
+) -> Vec<(Action, State, Outcome)> {
+    let Ok(line) = follow_best_children::<Infallible>(state, |parent| {
+        let best_child = solution.get(parent);
+        Ok((!best_child.is_null()).then_some(best_child))
+    });
+    line
+}
+
+impl<R: Read + Seek> SolutionIndex<R> {
+    /// This is equivalent to `principal_variation`,
+    /// but reads the best children from the solution file.
+    pub fn principal_variation(
+        &mut self,
+        state: State,
+    ) -> Result<Vec<(Action, State, Outcome)>, SolutionFileError> {
+        follow_best_children(state, |parent| self.best_child(parent))
+    }
+}
+
+fn follow_best_children<E>(
+    state: State,
+    mut best_child: impl FnMut(State) -> Result<Option<StateAndStats>, E>,
+) -> Result<Vec<(Action, State, Outcome)>, E> {
+    let mut line = vec![];
+    let mut visited = HashSet::from([state]);
+
+    let mut current = state;
+    while let Some(next) = best_child(current)? {
+        let action = current.action_to(next.state()).unwrap_or_else(|| {
+            use crate::pretty::*;
+            panic!(
+                "Best child is not a child of its parent.\n\nPARENT:\n\n{}\n\nBEST CHILD:\n\n{}",
+                current.pretty(),
+                next.pretty()
+            )
+        });
+        let outcome = next.stats().best_outcome().unwrap_or(Outcome::DRAW);
+        line.push((action, next.state(), outcome));
+
+        if !visited.insert(next.state()) {
+            break;
+        }
+
+        current = next.state();
+    }
+
+    Ok(line)
+}
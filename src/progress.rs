@@ -0,0 +1,394 @@
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+/// A stage of the solver pipeline,
+/// or a pass over the solution it produces.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SolverPhase {
+    ReachableStates,
+    InitialStats,
+    Retrograde,
+    BestChildren,
+    WriteSolution,
+    ReadSolution,
+    WdlTable,
+    CompactSolution,
+    Verification,
+    ExtremePositions,
+    OptimalChildren,
+    MoveOutcomes,
+    EvaluationAccuracy,
+    WeightFitting,
+    TrainingData,
+    SimpleDatabase,
+}
+
+/// Receives progress reports from the solver phases.
+///
+/// Every method has a default implementation that does nothing,
+/// so implementors only need to override the events they care about.
+pub trait ProgressObserver {
+    /// The number of states a phase processes between calls to `progress`.
+    fn report_interval(&self) -> u64 {
+        1_000_000
+    }
+
+    fn phase_started(&mut self, _phase: SolverPhase) {}
+
+    fn progress(&mut self, _report: &ProgressReport) {}
+
+    fn phase_finished(&mut self, _report: &ProgressReport) {}
+}
+
+/// A snapshot of a phase's progress.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ProgressReport {
+    pub phase: SolverPhase,
+
+    /// The number of states the phase has processed so far.
+    pub processed: u64,
+
+    /// The number of states the phase had already processed
+    /// when it resumed (i.e., zero, unless it was cancelled before).
+    /// These are included in `processed`, but not in `elapsed`.
+    pub resumed_from: u64,
+
+    /// The number of states the phase will process in total, if it is known.
+    ///
+    /// The retrograde phase never processes draws,
+    /// so its total is an upper bound.
+    pub total: Option<u64>,
+
+    /// The number of states waiting in the phase's queue,
+    /// if the phase uses a queue.
+    pub queue_depth: Option<u64>,
+
+    /// The time since the phase started (or resumed).
+    pub elapsed: Duration,
+}
+
+/// Ignores every report.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SilentObserver;
+
+/// Writes one human-readable line per event.
+///
+/// Write errors are ignored,
+/// since a broken progress log should not abort a solve.
+#[derive(Debug)]
+pub struct PlainTextObserver<W> {
+    writer: W,
+    report_interval: u64,
+}
+
+/// Writes one JSON object per event, one per line.
+///
+/// Every object has an `"event"` field
+/// (`"phase_started"`, `"progress"`, or `"phase_finished"`)
+/// and a `"phase"` field (see `SolverPhase::name`).
+/// Progress and phase-finished events also have
+/// `"processed"`, `"total"`, `"queue_depth"`, `"remaining"`,
+/// `"elapsed_secs"`, `"throughput"` (states per second), and `"eta_secs"` fields,
+/// where unknown values are `null`.
+///
+/// Write errors are ignored,
+/// since a broken progress log should not abort a solve.
+#[derive(Debug)]
+pub struct JsonLinesObserver<W> {
+    writer: W,
+    report_interval: u64,
+}
+
+/// Counts the states a phase processes,
+/// and reports to the observer at the observer's interval.
+///
+/// The library's own passes use this internally,
+/// but it is public so that callers can report passes of their own.
+pub struct PhaseTracker<'a, O: ?Sized> {
+    observer: &'a mut O,
+    phase: SolverPhase,
+    start_time: Instant,
+    processed: u64,
+    resumed_from: u64,
+    total: Option<u64>,
+    report_interval: u64,
+    next_report: u64,
+}
+
+impl SolverPhase {
+    /// Returns the phase's name in `snake_case`.
+    pub const fn name(self) -> &'static str {
+        match self {
+            SolverPhase::ReachableStates => "reachable_states",
+            SolverPhase::InitialStats => "initial_stats",
+            SolverPhase::Retrograde => "retrograde",
+            SolverPhase::BestChildren => "best_children",
+            SolverPhase::WriteSolution => "write_solution",
+            SolverPhase::ReadSolution => "read_solution",
+            SolverPhase::WdlTable => "wdl_table",
+            SolverPhase::CompactSolution => "compact_solution",
+            SolverPhase::Verification => "verification",
+            SolverPhase::ExtremePositions => "extreme_positions",
+            SolverPhase::OptimalChildren => "optimal_children",
+            SolverPhase::MoveOutcomes => "move_outcomes",
+            SolverPhase::EvaluationAccuracy => "evaluation_accuracy",
+            SolverPhase::WeightFitting => "weight_fitting",
+            SolverPhase::TrainingData => "training_data",
+            SolverPhase::SimpleDatabase => "simple_database",
+        }
+    }
+}
+
+impl ProgressReport {
+    /// Returns the number of states processed per second
+    /// since the phase started (or resumed).
+    pub fn throughput(&self) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs == 0.0 {
+            return 0.0;
+        }
+
+        self.processed.saturating_sub(self.resumed_from) as f64 / secs
+    }
+
+    /// Returns an estimate of the number of states left to process.
+    ///
+    /// If the total is unknown, the queue depth is used instead,
+    /// which underestimates the remaining work
+    /// (since processing a state may enqueue more states).
+    pub fn remaining(&self) -> Option<u64> {
+        match self.total {
+            Some(total) => Some(total.saturating_sub(self.processed)),
+            None => self.queue_depth,
+        }
+    }
+
+    /// Returns an estimate of the time left in the phase,
+    /// assuming the throughput stays the same.
+    pub fn eta(&self) -> Option<Duration> {
+        let remaining = self.remaining()?;
+        let throughput = self.throughput();
+        if throughput == 0.0 {
+            return None;
+        }
+
+        Some(Duration::from_secs_f64(remaining as f64 / throughput))
+    }
+}
+
+impl ProgressObserver for SilentObserver {
+    fn report_interval(&self) -> u64 {
+        u64::MAX
+    }
+}
+
+impl<O: ProgressObserver + ?Sized> ProgressObserver for &mut O {
+    fn report_interval(&self) -> u64 {
+        (**self).report_interval()
+    }
+
+    fn phase_started(&mut self, phase: SolverPhase) {
+        (**self).phase_started(phase)
+    }
+
+    fn progress(&mut self, report: &ProgressReport) {
+        (**self).progress(report)
+    }
+
+    fn phase_finished(&mut self, report: &ProgressReport) {
+        (**self).phase_finished(report)
+    }
+}
+
+impl PlainTextObserver<io::Stdout> {
+    pub fn stdout() -> Self {
+        Self::new(io::stdout())
+    }
+}
+
+impl<W: Write> PlainTextObserver<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            report_interval: 1_000_000,
+        }
+    }
+
+    pub fn with_report_interval(mut self, report_interval: u64) -> Self {
+        assert!(report_interval > 0, "Report interval must be positive.");
+        self.report_interval = report_interval;
+        self
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> ProgressObserver for PlainTextObserver<W> {
+    fn report_interval(&self) -> u64 {
+        self.report_interval
+    }
+
+    fn phase_started(&mut self, phase: SolverPhase) {
+        let _ = writeln!(self.writer, "Started {} phase.", phase.name());
+    }
+
+    fn progress(&mut self, report: &ProgressReport) {
+        let mut line = format!(
+            "{}: processed {} states ({:.0} states/s)",
+            report.phase.name(),
+            report.processed,
+            report.throughput()
+        );
+        if let Some(queue_depth) = report.queue_depth {
+            line += &format!(", queue depth {queue_depth}");
+        }
+        if let Some(remaining) = report.remaining() {
+            line += &format!(", about {remaining} remaining");
+        }
+        if let Some(eta) = report.eta() {
+            line += &format!(", ETA {:?}", Duration::from_secs(eta.as_secs()));
+        }
+        line += ".";
+
+        let _ = writeln!(self.writer, "{line}");
+    }
+
+    fn phase_finished(&mut self, report: &ProgressReport) {
+        let _ = writeln!(
+            self.writer,
+            "Completed {} phase: processed {} states. It took {:?}.",
+            report.phase.name(),
+            report.processed,
+            report.elapsed
+        );
+    }
+}
+
+impl<W: Write> JsonLinesObserver<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            report_interval: 1_000_000,
+        }
+    }
+
+    pub fn with_report_interval(mut self, report_interval: u64) -> Self {
+        assert!(report_interval > 0, "Report interval must be positive.");
+        self.report_interval = report_interval;
+        self
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    fn write_report(&mut self, event: &str, report: &ProgressReport) {
+        fn json_or_null(value: Option<impl std::fmt::Display>) -> String {
+            match value {
+                Some(value) => value.to_string(),
+                None => "null".to_string(),
+            }
+        }
+
+        let _ = writeln!(
+            self.writer,
+            r#"{{"event":"{event}","phase":"{}","processed":{},"total":{},"queue_depth":{},"remaining":{},"elapsed_secs":{:.3},"throughput":{:.3},"eta_secs":{}}}"#,
+            report.phase.name(),
+            report.processed,
+            json_or_null(report.total),
+            json_or_null(report.queue_depth),
+            json_or_null(report.remaining()),
+            report.elapsed.as_secs_f64(),
+            report.throughput(),
+            json_or_null(report.eta().map(|eta| format!("{:.3}", eta.as_secs_f64()))),
+        );
+    }
+}
+
+impl<W: Write> ProgressObserver for JsonLinesObserver<W> {
+    fn report_interval(&self) -> u64 {
+        self.report_interval
+    }
+
+    fn phase_started(&mut self, phase: SolverPhase) {
+        let _ = writeln!(
+            self.writer,
+            r#"{{"event":"phase_started","phase":"{}"}}"#,
+            phase.name()
+        );
+    }
+
+    fn progress(&mut self, report: &ProgressReport) {
+        self.write_report("progress", report);
+    }
+
+    fn phase_finished(&mut self, report: &ProgressReport) {
+        self.write_report("phase_finished", report);
+    }
+}
+
+impl<'a, O: ProgressObserver + ?Sized> PhaseTracker<'a, O> {
+    /// Starts tracking a phase that already processed `processed` states
+    /// (i.e., zero, unless the phase is resuming after being cancelled).
+    /// The elapsed time only counts the time since this call.
+    pub fn resume(
+        observer: &'a mut O,
+        phase: SolverPhase,
+        total: Option<u64>,
+        processed: u64,
+    ) -> Self {
+        observer.phase_started(phase);
+        let report_interval = observer.report_interval().max(1);
+        Self {
+            observer,
+            phase,
+            start_time: Instant::now(),
+            processed,
+            resumed_from: processed,
+            total,
+            report_interval,
+            next_report: (processed / report_interval + 1).saturating_mul(report_interval),
+        }
+    }
+
+    pub fn processed(&self) -> u64 {
+        self.processed
+    }
+
+    /// Records that one more state has been processed.
+    #[inline(always)]
+    pub fn tick(&mut self, queue_depth: Option<u64>) {
+        self.add(1, queue_depth);
+    }
+
+    /// Records that `count` more states have been processed.
+    #[inline(always)]
+    pub fn add(&mut self, count: u64, queue_depth: Option<u64>) {
+        self.processed += count;
+
+        if self.processed >= self.next_report {
+            self.next_report =
+                (self.processed / self.report_interval + 1).saturating_mul(self.report_interval);
+            let report = self.report(queue_depth);
+            self.observer.progress(&report);
+        }
+    }
+
+    pub fn finish(self, queue_depth: Option<u64>) {
+        let report = self.report(queue_depth);
+        self.observer.phase_finished(&report);
+    }
+
+    fn report(&self, queue_depth: Option<u64>) -> ProgressReport {
+        ProgressReport {
+            phase: self.phase,
+            processed: self.processed,
+            resumed_from: self.resumed_from,
+            total: self.total,
+            queue_depth,
+            elapsed: self.start_time.elapsed(),
+        }
+    }
+}
@@ -0,0 +1,246 @@
+use super::*;
+
+use crate::pretty::*;
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{self, Display, Formatter};
+
+/// The result of a successful `check_proof_tree` call.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProofTreeReport {
+    /// The number of prover nodes the strategy can reach.
+    pub reachable_move_count: u64,
+
+    /// The number of stored moves the strategy never reaches.
+    /// These do not invalidate the proof.
+    pub unreachable_move_count: u64,
+}
+
+/// A reason a proof tree does not prove its claimed outcome.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProofTreeError {
+    /// A nonterminal prover node the strategy can reach has no move.
+    MissingMove { state: State },
+
+    /// The stored move of `parent` is not one of its children.
+    IllegalMove { parent: State, child: State },
+
+    /// The strategy can reach a terminal state
+    /// that is not a win for the prover.
+    NonWinningLeaf { state: State },
+
+    /// The defender can force the strategy to repeat `state`,
+    /// so the strategy does not force a win.
+    Cycle { state: State },
+
+    /// The strategy wins, but not in the number of plies
+    /// that `claimed_outcome` implies.
+    /// `proven_plies` is the length of the longest line
+    /// the defender can force.
+    OutcomeMismatch {
+        claimed_outcome: Outcome,
+        proven_plies: u32,
+    },
+}
+
+impl Display for ProofTreeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match *self {
+            ProofTreeError::MissingMove { state } => write!(
+                f,
+                "Prover node has no move.\n\nSTATE:\n\n{}",
+                state.pretty()
+            ),
+
+            ProofTreeError::IllegalMove { parent, child } => write!(
+                f,
+                "Stored move is not a child of its parent.\n\nPARENT:\n\n{}\n\nCHILD:\n\n{}",
+                parent.pretty(),
+                child.pretty()
+            ),
+
+            ProofTreeError::NonWinningLeaf { state } => write!(
+                f,
+                "Strategy reaches a terminal state that is not a win for the prover.\n\nSTATE:\n\n{}",
+                state.pretty()
+            ),
+
+            ProofTreeError::Cycle { state } => write!(
+                f,
+                "Defender can force the strategy to repeat a state.\n\nSTATE:\n\n{}",
+                state.pretty()
+            ),
+
+            ProofTreeError::OutcomeMismatch {
+                claimed_outcome,
+                proven_plies,
+            } => write!(
+                f,
+                "Claimed outcome {} does not match the strategy, which wins in {proven_plies} plies.",
+                claimed_outcome.pretty()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ProofTreeError {}
+
+/// Checks that `tree` forces a win for the prover
+/// in exactly the number of plies its outcome claims.
+///
+/// This only relies on `State::visit_children` and `State::terminality`,
+/// so it is independent of the solver
+/// (in particular, it never reads a `StateMap`).
+pub fn check_proof_tree(tree: &ProofTree) -> Result<ProofTreeReport, ProofTreeError> {
+    let mut checker = ProofChecker {
+        moves: &tree.moves,
+        depths: HashMap::new(),
+    };
+
+    let (proven_plies, claimed_plies) = if tree.outcome.0 > 0 {
+        let claimed_plies = (Outcome::win_in(0).0 - tree.outcome.0) as u32;
+        (checker.prover_depth(tree.root)?, claimed_plies)
+    } else if tree.outcome.0 < 0 {
+        let claimed_plies = (tree.outcome.0 - Outcome::loss_in(0).0) as u32;
+        (checker.defender_depth(tree.root)?, claimed_plies)
+    } else {
+        return Err(ProofTreeError::OutcomeMismatch {
+            claimed_outcome: tree.outcome,
+            proven_plies: 0,
+        });
+    };
+
+    if proven_plies != claimed_plies {
+        return Err(ProofTreeError::OutcomeMismatch {
+            claimed_outcome: tree.outcome,
+            proven_plies,
+        });
+    }
+
+    // Terminal prover nodes are memoized too, but never have moves.
+    let reachable_move_count = checker
+        .depths
+        .keys()
+        .filter(|state| tree.moves.contains_key(state))
+        .count() as u64;
+
+    Ok(ProofTreeReport {
+        reachable_move_count,
+        unreachable_move_count: tree.moves.len() as u64 - reachable_move_count,
+    })
+}
+
+struct ProofChecker<'a> {
+    moves: &'a BTreeMap<State, State>,
+
+    /// Maps each visited prover node to the number of plies
+    /// until the strategy wins from it,
+    /// or to `None` if the node is still being checked
+    /// (i.e., it is an ancestor of the current node).
+    depths: HashMap<State, Option<u32>>,
+}
+
+impl ProofChecker<'_> {
+    /// Returns the number of plies until the strategy wins,
+    /// if the defender moves first from `defender`.
+    fn defender_depth(&mut self, defender: State) -> Result<u32, ProofTreeError> {
+        match defender.terminality() {
+            Terminality::Loss => return Ok(0),
+            Terminality::Win => return Err(ProofTreeError::NonWinningLeaf { state: defender }),
+            Terminality::Nonterminal => {}
+        }
+
+        let mut max_depth = 0;
+        for reply in defender.children() {
+            max_depth = max_depth.max(self.prover_depth(reply)?);
+        }
+        Ok(1 + max_depth)
+    }
+
+    /// Returns the number of plies until the strategy wins,
+    /// if the prover moves first from `prover`.
+    ///
+    /// This uses an explicit stack (rather than recursion),
+    /// since a claimed tree may be arbitrarily deep.
+    fn prover_depth(&mut self, prover: State) -> Result<u32, ProofTreeError> {
+        let mut stack = vec![prover];
+
+        while let Some(&top) = stack.last() {
+            match self.depths.get(&top) {
+                Some(Some(_)) => {
+                    stack.pop();
+                }
+
+                // Every reply of `top`'s move has been checked.
+                Some(None) => {
+                    let depth = self.finished_prover_depth(top)?;
+                    self.depths.insert(top, Some(depth));
+                    stack.pop();
+                }
+
+                None => match top.terminality() {
+                    Terminality::Win => {
+                        self.depths.insert(top, Some(0));
+                        stack.pop();
+                    }
+
+                    Terminality::Loss => {
+                        return Err(ProofTreeError::NonWinningLeaf { state: top });
+                    }
+
+                    Terminality::Nonterminal => {
+                        self.depths.insert(top, None);
+                        let defender = self.prover_move(top)?;
+                        let mut cycle = None;
+                        defender.visit_children(|reply| match self.depths.get(&reply) {
+                            Some(Some(_)) => {}
+                            Some(None) => cycle = Some(reply),
+                            None => stack.push(reply),
+                        });
+
+                        if let Some(state) = cycle {
+                            return Err(ProofTreeError::Cycle { state });
+                        }
+                    }
+                },
+            }
+        }
+
+        Ok(self.depths[&prover].expect("The stack is empty, so every node is finished."))
+    }
+
+    /// `prover` must be nonterminal,
+    /// and every nonterminal reply to its move must be finished.
+    fn finished_prover_depth(&self, prover: State) -> Result<u32, ProofTreeError> {
+        let defender = self.prover_move(prover)?;
+        match defender.terminality() {
+            Terminality::Loss => return Ok(1),
+            Terminality::Win => return Err(ProofTreeError::NonWinningLeaf { state: defender }),
+            Terminality::Nonterminal => {}
+        }
+
+        let mut max_depth = 0;
+        for reply in defender.children() {
+            let depth = self.depths[&reply].expect("Every reply is finished.");
+            max_depth = max_depth.max(depth);
+        }
+        Ok(2 + max_depth)
+    }
+
+    fn prover_move(&self, prover: State) -> Result<State, ProofTreeError> {
+        let Some(&child) = self.moves.get(&prover) else {
+            return Err(ProofTreeError::MissingMove { state: prover });
+        };
+
+        let mut is_child = false;
+        prover.visit_children(|candidate| is_child |= candidate == child);
+        if !is_child {
+            return Err(ProofTreeError::IllegalMove {
+                parent: prover,
+                child,
+            });
+        }
+
+        Ok(child)
+    }
+}
@@ -0,0 +1,189 @@
+use super::*;
+
+use std::collections::BTreeMap;
+use std::io::{self, Read, Write};
+
+/// The first 8 bytes of every proof tree file.
+pub const PROOF_TREE_MAGIC: [u8; 8] = *b"DBTPROOF";
+
+/// A strategy that forces a win, along with the result it claims.
+///
+/// The player the strategy wins for is called the _prover_,
+/// and the other player is called the _defender_.
+/// The tree stores the prover's move at every prover node
+/// the strategy can reach.
+/// Defender nodes store nothing,
+/// since every defender reply must be covered anyway,
+/// and the replies can be regenerated with `State::visit_children`.
+/// Since each prover node is stored once,
+/// transpositions are stored once.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProofTree {
+    pub root: State,
+
+    /// The root's outcome, from the perspective of the root's active player.
+    /// This is a win if the prover moves first,
+    /// and a loss if the defender moves first.
+    pub outcome: Outcome,
+
+    /// Maps each nonterminal prover node to the child the prover moves to.
+    pub moves: BTreeMap<State, State>,
+}
+
+/// Returns the tree of the strategy that `solution` plays
+/// for the winner of `root`.
+///
+/// At each prover node, the prover plays its best child,
+/// and every defender reply is followed.
+/// Returns `None` if `root` is a draw,
+/// or if it is nonterminal and has no entry.
+pub fn proof_tree(solution: &StateMap<StateAndStats>, root: State) -> Option<ProofTree> {
+    let outcome = solution.outcome(root)?;
+    if outcome == Outcome::DRAW {
+        return None;
+    }
+
+    let mut moves = BTreeMap::new();
+
+    // Every state on the stack is a nonterminal prover node.
+    let mut stack = vec![];
+    if !root.is_terminal() {
+        if outcome.0 > 0 {
+            stack.push(root);
+        } else {
+            push_defender_replies(root, &mut stack);
+        }
+    }
+
+    while let Some(prover) = stack.pop() {
+        if moves.contains_key(&prover) {
+            continue;
+        }
+
+        let best_child = solution.get(prover);
+        if best_child.is_null() {
+            use crate::pretty::*;
+            panic!("Prover node has no entry.\n\nSTATE:\n\n{}", prover.pretty());
+        }
+        let best_child = best_child.state();
+        moves.insert(prover, best_child);
+
+        if !best_child.is_terminal() {
+            push_defender_replies(best_child, &mut stack);
+        }
+    }
+
+    Some(ProofTree {
+        root,
+        outcome,
+        moves,
+    })
+}
+
+impl ProofTree {
+    /// The file starts with a 15-byte header:
+    /// the 8-byte `PROOF_TREE_MAGIC`,
+    /// the 5-byte root state,
+    /// and the 2-byte root outcome.
+    /// It is followed by one 6-byte record per prover move:
+    /// the 5-byte prover node,
+    /// followed by the 1-byte index of the prover's move
+    /// (in the order of `State::visit_children`).
+    /// Everything is stored in little-endian order,
+    /// and the records are sorted by their prover nodes.
+    pub fn write_to(&self, mut writer: impl Write) -> io::Result<()> {
+        let mut header = [0; 15];
+        header[0..8].copy_from_slice(&PROOF_TREE_MAGIC);
+        header[8..13].copy_from_slice(&self.root.0.to_le_bytes()[0..5]);
+        header[13..15].copy_from_slice(&self.outcome.0.to_le_bytes());
+        writer.write_all(&header)?;
+
+        for (&prover, &child) in &self.moves {
+            let child_index = prover
+                .children()
+                .iter()
+                .position(|&candidate| candidate == child)
+                .ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "The proof tree has a move to a state that is not a child.",
+                    )
+                })?;
+
+            let mut record = [0; 6];
+            record[0..5].copy_from_slice(&prover.0.to_le_bytes()[0..5]);
+            record[5] = child_index as u8;
+            writer.write_all(&record)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn read_from(mut reader: impl Read) -> io::Result<Self> {
+        let mut header = [0; 15];
+        if solution_file::read_up_to(&mut reader, &mut header)? < header.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "The proof tree ends before its header does.",
+            ));
+        }
+
+        if header[0..8] != PROOF_TREE_MAGIC {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "The file is not a proof tree.",
+            ));
+        }
+
+        let root = read_state(&header[8..13]);
+        let outcome = Outcome(i16::from_le_bytes([header[13], header[14]]));
+
+        let mut moves = BTreeMap::new();
+        let mut record = [0; 6];
+        loop {
+            let record_len = solution_file::read_up_to(&mut reader, &mut record)?;
+
+            if record_len == 0 {
+                return Ok(Self {
+                    root,
+                    outcome,
+                    moves,
+                });
+            }
+
+            if record_len < record.len() {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "The proof tree ends with an incomplete record.",
+                ));
+            }
+
+            let prover = read_state(&record[0..5]);
+            let child = prover
+                .children()
+                .get(record[5] as usize)
+                .copied()
+                .ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "The proof tree has a move index that is out of range.",
+                    )
+                })?;
+            moves.insert(prover, child);
+        }
+    }
+}
+
+fn push_defender_replies(defender: State, stack: &mut Vec<State>) {
+    defender.visit_children(|reply| {
+        if !reply.is_terminal() {
+            stack.push(reply);
+        }
+    });
+}
+
+fn read_state(bytes: &[u8]) -> State {
+    let mut state_bytes = [0; 8];
+    state_bytes[0..5].copy_from_slice(bytes);
+    State(u64::from_le_bytes(state_bytes))
+}
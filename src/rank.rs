@@ -0,0 +1,461 @@
+use super::*;
+
+/// Describes how a pair of same-species non-lions
+/// (e.g., `chick0` and `chick1`) is ranked.
+///
+/// Each piece is described by a _token_ (its allegiance,
+/// and its promotion status, if applicable) and a location
+/// (a board square or the hand).
+/// Since the two pieces of a pair are interchangeable,
+/// we rank the unordered pair of (token, location) pairs.
+#[derive(Clone, Copy, Debug)]
+struct PairSpec {
+    piece0_offset: Offset,
+    piece1_offset: Offset,
+    /// The number of bits to the right of the coordinates.
+    /// This is `1` for chicks (because of the promotion bit), and `0` otherwise.
+    coords_shift: u8,
+    hand_token_count: u64,
+    board_token_count: u64,
+}
+
+const PAIRS: [PairSpec; 3] = [
+    PairSpec {
+        piece0_offset: Offset::CHICK0,
+        piece1_offset: Offset::CHICK1,
+        coords_shift: 1,
+        hand_token_count: 2,
+        board_token_count: 4,
+    },
+    PairSpec {
+        piece0_offset: Offset::ELEPHANT0,
+        piece1_offset: Offset::ELEPHANT1,
+        coords_shift: 0,
+        hand_token_count: 2,
+        board_token_count: 2,
+    },
+    PairSpec {
+        piece0_offset: Offset::GIRAFFE0,
+        piece1_offset: Offset::GIRAFFE1,
+        coords_shift: 0,
+        hand_token_count: 2,
+        board_token_count: 2,
+    },
+];
+
+/// `COMPLETION_COUNTS[i][f]` is the number of ways to place
+/// the pairs `PAIRS[i..]` when there are `f` empty squares.
+const COMPLETION_COUNTS: [[u64; 13]; 4] = compute_completion_counts();
+
+/// The 12 board squares, as a bitset indexed by `Coords`.
+const ALL_BOARD_SQUARES: u16 = 0b0111_0111_0111_0111;
+
+impl State {
+    /// The number of constructible states.
+    ///
+    /// A state is _constructible_ if
+    ///
+    /// - no two pieces occupy the same square,
+    /// - the passive lion is on the board, and
+    /// - every chick in a hand is unpromoted.
+    ///
+    /// Every reachable state is constructible.
+    /// The constructible states are in one-to-one correspondence
+    /// with the ranks `0..State::RANK_COUNT`.
+    pub const RANK_COUNT: u64 = 12 * COMPLETION_COUNTS[0][11] + 12 * 11 * COMPLETION_COUNTS[0][10];
+
+    /// Returns the dense index of this state.
+    /// The returned rank is less than `State::RANK_COUNT`.
+    ///
+    /// The state must be constructible (see `State::RANK_COUNT`).
+    /// Otherwise, the returned rank is meaningless.
+    pub fn rank(self) -> u64 {
+        let active_lion = Coords(((self.0 >> Offset::ACTIVE_LION.0) & 0b1111) as u8);
+        let passive_lion = Coords(((self.0 >> Offset::PASSIVE_LION.0) & 0b1111) as u8);
+
+        let mut free = ALL_BOARD_SQUARES & !(1 << passive_lion.0);
+
+        let rank = if active_lion == Coords::HAND {
+            let lion_index = free_index(ALL_BOARD_SQUARES, passive_lion);
+            lion_index * COMPLETION_COUNTS[0][11]
+        } else {
+            let passive_lion_index =
+                free_index(ALL_BOARD_SQUARES & !(1 << active_lion.0), passive_lion);
+            let lion_index = free_index(ALL_BOARD_SQUARES, active_lion) * 11 + passive_lion_index;
+            free &= !(1 << active_lion.0);
+            12 * COMPLETION_COUNTS[0][11] + lion_index * COMPLETION_COUNTS[0][10]
+        };
+
+        let mut rest = 0;
+        for (i, pair) in PAIRS.iter().enumerate() {
+            let (pair_rank, new_free) = pair.rank(self, free, i);
+            rest += pair_rank;
+            free = new_free;
+        }
+
+        rank + rest
+    }
+
+    /// Returns the constructible state with the given rank.
+    ///
+    /// The rank must be less than `State::RANK_COUNT`.
+    pub fn unrank(rank: u64) -> Self {
+        assert!(
+            rank < Self::RANK_COUNT,
+            "Rank {rank} is out of bounds (there are only {} constructible states).",
+            Self::RANK_COUNT
+        );
+
+        let mut raw = 0;
+        let mut free;
+        let mut rest;
+
+        let hand_lion_count = 12 * COMPLETION_COUNTS[0][11];
+        if rank < hand_lion_count {
+            let passive_lion = nth_free(ALL_BOARD_SQUARES, rank / COMPLETION_COUNTS[0][11]);
+            rest = rank % COMPLETION_COUNTS[0][11];
+            free = ALL_BOARD_SQUARES & !(1 << passive_lion.0);
+            raw |= (Coords::HAND.0 as u64) << Offset::ACTIVE_LION.0;
+            raw |= (passive_lion.0 as u64) << Offset::PASSIVE_LION.0;
+        } else {
+            let rank = rank - hand_lion_count;
+            let lion_index = rank / COMPLETION_COUNTS[0][10];
+            rest = rank % COMPLETION_COUNTS[0][10];
+            let active_lion = nth_free(ALL_BOARD_SQUARES, lion_index / 11);
+            let passive_lion = nth_free(ALL_BOARD_SQUARES & !(1 << active_lion.0), lion_index % 11);
+            free = ALL_BOARD_SQUARES & !(1 << active_lion.0) & !(1 << passive_lion.0);
+            raw |= (active_lion.0 as u64) << Offset::ACTIVE_LION.0;
+            raw |= (passive_lion.0 as u64) << Offset::PASSIVE_LION.0;
+        }
+
+        for (i, pair) in PAIRS.iter().enumerate() {
+            let (fields, new_free, new_rest) = pair.unrank(rest, free, i);
+            raw |= fields;
+            free = new_free;
+            rest = new_rest;
+        }
+
+        State(raw)
+    }
+
+    /// Returns whether this state is constructible (see `State::RANK_COUNT`).
+    ///
+    /// This also checks that the unused bits are zero,
+    /// and that `chick0 <= chick1` (and all similar invariants) hold.
+    pub fn is_constructible(self) -> bool {
+        if self.0 >> 40 != 0 || self.into_builder().build() != self {
+            return false;
+        }
+
+        let active_lion = Coords(((self.0 >> Offset::ACTIVE_LION.0) & 0b1111) as u8);
+        let passive_lion = Coords(((self.0 >> Offset::PASSIVE_LION.0) & 0b1111) as u8);
+        if passive_lion == Coords::HAND {
+            return false;
+        }
+
+        let mut occupied: u16 = 0;
+        let mut occupy = |coords: Coords| {
+            if coords == Coords::HAND {
+                return true;
+            }
+
+            let mask = 1 << coords.0;
+            if ALL_BOARD_SQUARES & mask == 0 || occupied & mask != 0 {
+                return false;
+            }
+
+            occupied |= mask;
+            true
+        };
+
+        if !occupy(active_lion) || !occupy(passive_lion) {
+            return false;
+        }
+
+        for pair in PAIRS {
+            let (field0, field1) = pair.fields(self);
+            for field in [field0, field1] {
+                let coords = pair.field_coords(field);
+                if !occupy(coords) {
+                    return false;
+                }
+
+                let is_promoted = field & ((1 << pair.coords_shift) - 1) != 0;
+                if coords == Coords::HAND && is_promoted {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+}
+
+impl PairSpec {
+    const fn field_width(self) -> u8 {
+        self.coords_shift + 5
+    }
+
+    const fn both_in_hand_count(self) -> u64 {
+        self.hand_token_count * (self.hand_token_count + 1) / 2
+    }
+
+    const fn one_in_hand_count(self, free_count: u64) -> u64 {
+        self.hand_token_count * free_count * self.board_token_count
+    }
+
+    const fn none_in_hand_count(self, free_count: u64) -> u64 {
+        if free_count < 2 {
+            return 0;
+        }
+
+        free_count * (free_count - 1) / 2 * self.board_token_count * self.board_token_count
+    }
+
+    fn fields(self, state: State) -> (u64, u64) {
+        let mask = (1 << self.field_width()) - 1;
+        (
+            (state.0 >> self.piece0_offset.0) & mask,
+            (state.0 >> self.piece1_offset.0) & mask,
+        )
+    }
+
+    fn field_coords(self, field: u64) -> Coords {
+        Coords(((field >> self.coords_shift) & 0b1111) as u8)
+    }
+
+    /// A hand token is simply the allegiance,
+    /// since pieces in hand are never promoted.
+    fn hand_token(self, field: u64) -> u64 {
+        field >> (self.coords_shift + 4)
+    }
+
+    /// The allegiance is the most significant bit of a board token,
+    /// and the promotion status (if applicable) is the least significant bit.
+    fn board_token(self, field: u64) -> u64 {
+        let allegiance = field >> (self.coords_shift + 4);
+        let promotion = field & ((1 << self.coords_shift) - 1);
+        (allegiance << self.coords_shift) | promotion
+    }
+
+    fn hand_field(self, token: u64) -> u64 {
+        (token << (self.coords_shift + 4)) | ((Coords::HAND.0 as u64) << self.coords_shift)
+    }
+
+    fn board_field(self, token: u64, coords: Coords) -> u64 {
+        let allegiance = token >> self.coords_shift;
+        let promotion = token & ((1 << self.coords_shift) - 1);
+        (allegiance << (self.coords_shift + 4))
+            | ((coords.0 as u64) << self.coords_shift)
+            | promotion
+    }
+
+    /// Returns the rank of this pair (scaled by the number of ways
+    /// to place the remaining pairs) and the remaining empty squares.
+    fn rank(self, state: State, free: u16, pair_index: usize) -> (u64, u16) {
+        let next = &COMPLETION_COUNTS[pair_index + 1];
+        let free_count = free.count_ones() as u64;
+        let (field0, field1) = self.fields(state);
+        let coords0 = self.field_coords(field0);
+        let coords1 = self.field_coords(field1);
+
+        match (coords0 == Coords::HAND, coords1 == Coords::HAND) {
+            (true, true) => {
+                let index = multiset_index(
+                    self.hand_token_count,
+                    self.hand_token(field0),
+                    self.hand_token(field1),
+                );
+                (index * next[free_count as usize], free)
+            }
+
+            (true, false) | (false, true) => {
+                let (hand_field, board_field) = if coords0 == Coords::HAND {
+                    (field0, field1)
+                } else {
+                    (field1, field0)
+                };
+                let board_coords = self.field_coords(board_field);
+                let index = (self.hand_token(hand_field) * free_count
+                    + free_index(free, board_coords))
+                    * self.board_token_count
+                    + self.board_token(board_field);
+                let offset = self.both_in_hand_count() * next[free_count as usize];
+                (
+                    offset + index * next[free_count as usize - 1],
+                    free & !(1 << board_coords.0),
+                )
+            }
+
+            (false, false) => {
+                let (low_field, high_field) = if coords0.0 < coords1.0 {
+                    (field0, field1)
+                } else {
+                    (field1, field0)
+                };
+                let low_coords = self.field_coords(low_field);
+                let high_coords = self.field_coords(high_field);
+                let squares_index = combination_index(
+                    free_count,
+                    free_index(free, low_coords),
+                    free_index(free, high_coords),
+                );
+                let index = (squares_index * self.board_token_count + self.board_token(low_field))
+                    * self.board_token_count
+                    + self.board_token(high_field);
+                let offset = self.both_in_hand_count() * next[free_count as usize]
+                    + self.one_in_hand_count(free_count) * next[free_count as usize - 1];
+                (
+                    offset + index * next[free_count as usize - 2],
+                    free & !(1 << low_coords.0) & !(1 << high_coords.0),
+                )
+            }
+        }
+    }
+
+    /// Returns the pair's fields (positioned at their offsets),
+    /// the remaining empty squares, and the remaining rank.
+    fn unrank(self, rank: u64, free: u16, pair_index: usize) -> (u64, u16, u64) {
+        let next = &COMPLETION_COUNTS[pair_index + 1];
+        let free_count = free.count_ones() as u64;
+
+        let both_in_hand_total = self.both_in_hand_count() * next[free_count as usize];
+        let one_in_hand_total = if free_count >= 1 {
+            self.one_in_hand_count(free_count) * next[free_count as usize - 1]
+        } else {
+            0
+        };
+
+        let (field_a, field_b, free, rest) = if rank < both_in_hand_total {
+            let block = next[free_count as usize];
+            let (token_a, token_b) = multiset_unindex(self.hand_token_count, rank / block);
+            (
+                self.hand_field(token_a),
+                self.hand_field(token_b),
+                free,
+                rank % block,
+            )
+        } else if rank < both_in_hand_total + one_in_hand_total {
+            let rank = rank - both_in_hand_total;
+            let block = next[free_count as usize - 1];
+            let index = rank / block;
+            let board_token = index % self.board_token_count;
+            let index = index / self.board_token_count;
+            let board_coords = nth_free(free, index % free_count);
+            let hand_token = index / free_count;
+            (
+                self.hand_field(hand_token),
+                self.board_field(board_token, board_coords),
+                free & !(1 << board_coords.0),
+                rank % block,
+            )
+        } else {
+            let rank = rank - both_in_hand_total - one_in_hand_total;
+            let block = next[free_count as usize - 2];
+            let index = rank / block;
+            let high_token = index % self.board_token_count;
+            let index = index / self.board_token_count;
+            let low_token = index % self.board_token_count;
+            let (low_index, high_index) =
+                combination_unindex(free_count, index / self.board_token_count);
+            let low_coords = nth_free(free, low_index);
+            let high_coords = nth_free(free, high_index);
+            (
+                self.board_field(low_token, low_coords),
+                self.board_field(high_token, high_coords),
+                free & !(1 << low_coords.0) & !(1 << high_coords.0),
+                rank % block,
+            )
+        };
+
+        // We must uphold the `piece0 <= piece1` invariant.
+        let (field0, field1) = if field_a <= field_b {
+            (field_a, field_b)
+        } else {
+            (field_b, field_a)
+        };
+
+        (
+            (field0 << self.piece0_offset.0) | (field1 << self.piece1_offset.0),
+            free,
+            rest,
+        )
+    }
+}
+
+const fn compute_completion_counts() -> [[u64; 13]; 4] {
+    let mut out = [[0; 13]; 4];
+
+    let mut free_count = 0;
+    while free_count <= 12 {
+        out[PAIRS.len()][free_count] = 1;
+        free_count += 1;
+    }
+
+    let mut pair_index = PAIRS.len();
+    while pair_index > 0 {
+        pair_index -= 1;
+        let pair = PAIRS[pair_index];
+
+        let mut free_count = 0;
+        while free_count <= 12 {
+            let mut count = pair.both_in_hand_count() * out[pair_index + 1][free_count];
+            if free_count >= 1 {
+                count +=
+                    pair.one_in_hand_count(free_count as u64) * out[pair_index + 1][free_count - 1];
+            }
+            if free_count >= 2 {
+                count += pair.none_in_hand_count(free_count as u64)
+                    * out[pair_index + 1][free_count - 2];
+            }
+            out[pair_index][free_count] = count;
+            free_count += 1;
+        }
+    }
+
+    out
+}
+
+/// Returns the number of empty squares that come before `coords`.
+fn free_index(free: u16, coords: Coords) -> u64 {
+    (free & ((1 << coords.0) - 1)).count_ones() as u64
+}
+
+/// Returns the `n`th (zero-based) empty square.
+fn nth_free(free: u16, n: u64) -> Coords {
+    let mut remaining = free;
+    for _ in 0..n {
+        remaining &= remaining - 1;
+    }
+    Coords(remaining.trailing_zeros() as u8)
+}
+
+/// Returns the lexicographic index of the multiset `{low, high}`
+/// (where `low <= high < token_count`).
+fn multiset_index(token_count: u64, low: u64, high: u64) -> u64 {
+    low * token_count - low * low.saturating_sub(1) / 2 + (high - low)
+}
+
+fn multiset_unindex(token_count: u64, mut index: u64) -> (u64, u64) {
+    let mut low = 0;
+    while index >= token_count - low {
+        index -= token_count - low;
+        low += 1;
+    }
+    (low, low + index)
+}
+
+/// Returns the lexicographic index of the combination `{low, high}`
+/// (where `low < high < n`).
+fn combination_index(n: u64, low: u64, high: u64) -> u64 {
+    low * (2 * n - low - 1) / 2 + (high - low - 1)
+}
+
+fn combination_unindex(n: u64, mut index: u64) -> (u64, u64) {
+    let mut low = 0;
+    while index >= n - low - 1 {
+        index -= n - low - 1;
+        low += 1;
+    }
+    (low, low + 1 + index)
+}
@@ -0,0 +1,442 @@
+use super::*;
+
+use std::fmt::{self, Display, Formatter};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+
+/// A solution file is a `SolutionHeader`
+/// followed by a sorted list of parent-child pairs,
+/// sorted by their parents.
+/// Each pair is stored as a 16-byte record:
+/// the parent `State` followed by the best child `StateAndStats`,
+/// both as little-endian `u64`s.
+pub const SOLUTION_RECORD_LEN: usize = 2 * U64_BYTES;
+
+/// The first 8 bytes of every solution file.
+pub const SOLUTION_MAGIC: [u8; 8] = *b"DBTSOLN\0";
+
+/// The version of the solution file format written by `write_solution_file`.
+/// Bump this whenever the layout of the header or the records changes.
+pub const SOLUTION_FORMAT_VERSION: u32 = 1;
+
+const U64_BYTES: usize = std::mem::size_of::<u64>();
+const U32_BYTES: usize = std::mem::size_of::<u32>();
+
+const BUFFER_CAPACITY: usize = 1 << 20;
+
+/// The rules a solution was computed under.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Ruleset {
+    /// The official rules, with the Try Rule simplification
+    /// and without the threefold repetition rule
+    /// (see `docs/spec.md`).
+    SimplifiedTry = 1,
+}
+
+impl Ruleset {
+    pub const fn from_tag(tag: u32) -> Option<Self> {
+        match tag {
+            1 => Some(Ruleset::SimplifiedTry),
+            _ => None,
+        }
+    }
+
+    pub const fn tag(self) -> u32 {
+        self as u32
+    }
+}
+
+/// The header at the start of every solution file.
+///
+/// It is stored as the 8-byte `SOLUTION_MAGIC`,
+/// followed by the version (`u32`), the ruleset tag (`u32`),
+/// the entry count (`u64`), and the body checksum (`u64`),
+/// all in little-endian order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SolutionHeader {
+    pub version: u32,
+    pub ruleset: Ruleset,
+    pub entry_count: u64,
+
+    /// The 64-bit FNV-1a hash of every record in the body.
+    pub checksum: u64,
+}
+
+impl SolutionHeader {
+    pub const LEN: usize = SOLUTION_MAGIC.len() + 2 * U32_BYTES + 2 * U64_BYTES;
+
+    pub fn to_bytes(self) -> [u8; Self::LEN] {
+        let mut out = [0; Self::LEN];
+        out[0..8].copy_from_slice(&SOLUTION_MAGIC);
+        out[8..12].copy_from_slice(&self.version.to_le_bytes());
+        out[12..16].copy_from_slice(&self.ruleset.tag().to_le_bytes());
+        out[16..24].copy_from_slice(&self.entry_count.to_le_bytes());
+        out[24..32].copy_from_slice(&self.checksum.to_le_bytes());
+        out
+    }
+
+    /// Reads the header and checks that the file is a solution file
+    /// that this version of the crate can read.
+    pub fn read_from(reader: &mut impl Read) -> Result<Self, SolutionFileError> {
+        let mut bytes = [0; Self::LEN];
+        let header_len = read_up_to(reader, &mut bytes)?;
+
+        if header_len < SOLUTION_MAGIC.len() || bytes[0..8] != SOLUTION_MAGIC {
+            return Err(SolutionFileError::MissingMagic);
+        }
+
+        if header_len < Self::LEN {
+            return Err(SolutionFileError::TruncatedHeader {
+                byte_len: header_len as u64,
+            });
+        }
+
+        let version = u32::from_le_bytes(bytes[8..12].try_into().unwrap());
+        if version != SOLUTION_FORMAT_VERSION {
+            return Err(SolutionFileError::UnsupportedVersion { version });
+        }
+
+        let ruleset_tag = u32::from_le_bytes(bytes[12..16].try_into().unwrap());
+        let ruleset = Ruleset::from_tag(ruleset_tag)
+            .ok_or(SolutionFileError::UnknownRuleset { tag: ruleset_tag })?;
+
+        Ok(Self {
+            version,
+            ruleset,
+            entry_count: u64::from_le_bytes(bytes[16..24].try_into().unwrap()),
+            checksum: u64::from_le_bytes(bytes[24..32].try_into().unwrap()),
+        })
+    }
+
+    pub fn expect_ruleset(self, ruleset: Ruleset) -> Result<(), SolutionFileError> {
+        if self.ruleset != ruleset {
+            return Err(SolutionFileError::RulesetMismatch {
+                expected: ruleset,
+                found: self.ruleset,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// A 64-bit FNV-1a hash.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Checksum(pub(crate) u64);
+
+impl Checksum {
+    pub(crate) const fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+
+    pub(crate) fn update(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum SolutionFileError {
+    Io(io::Error),
+
+    /// The file does not start with `SOLUTION_MAGIC`.
+    /// This is also the error for files written
+    /// before the header was introduced.
+    MissingMagic,
+
+    /// The file ended before the end of its `SolutionHeader`.
+    TruncatedHeader {
+        byte_len: u64,
+    },
+
+    UnsupportedVersion {
+        version: u32,
+    },
+
+    UnknownRuleset {
+        tag: u32,
+    },
+
+    /// The file was written under different rules than the reader expected.
+    RulesetMismatch {
+        expected: Ruleset,
+        found: Ruleset,
+    },
+
+    /// The body contains a different number of records
+    /// than the header declares
+    /// (e.g., because the file was only partially written).
+    EntryCountMismatch {
+        expected: u64,
+        found: u64,
+    },
+
+    ChecksumMismatch {
+        expected: u64,
+        found: u64,
+    },
+
+    /// The file ended partway through a `u64`.
+    Truncated {
+        byte_len: u64,
+    },
+
+    /// The file contains an odd number of `u64`s,
+    /// so the last parent has no best child.
+    OddRecordLength {
+        byte_len: u64,
+    },
+
+    /// The record contains a parent or best child
+    /// that is not a constructible state.
+    InvalidState {
+        record_index: u64,
+        parent: u64,
+        best_child: u64,
+    },
+}
+
+impl Display for SolutionFileError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            SolutionFileError::Io(error) => write!(f, "I/O error: {error}"),
+
+            SolutionFileError::MissingMagic => write!(
+                f,
+                "The file is not a solution file, or was written by an older version of this crate."
+            ),
+
+            SolutionFileError::TruncatedHeader { byte_len } => write!(
+                f,
+                "The file ends after {byte_len} bytes, before the end of its {}-byte header.",
+                SolutionHeader::LEN
+            ),
+
+            SolutionFileError::UnsupportedVersion { version } => write!(
+                f,
+                "The file has format version {version}, but only version {SOLUTION_FORMAT_VERSION} is supported."
+            ),
+
+            SolutionFileError::UnknownRuleset { tag } => {
+                write!(f, "The file has an unknown ruleset tag ({tag}).")
+            }
+
+            SolutionFileError::RulesetMismatch { expected, found } => write!(
+                f,
+                "The file was computed under the {found:?} ruleset, but {expected:?} was expected."
+            ),
+
+            SolutionFileError::EntryCountMismatch { expected, found } => write!(
+                f,
+                "The header declares {expected} records, but the file contains {found} records."
+            ),
+
+            SolutionFileError::ChecksumMismatch { expected, found } => write!(
+                f,
+                "The body checksum is {found:#018x}, but the header declares {expected:#018x}."
+            ),
+
+            SolutionFileError::Truncated { byte_len } => write!(
+                f,
+                "The file is truncated ({byte_len} bytes is not a multiple of {U64_BYTES})."
+            ),
+
+            SolutionFileError::OddRecordLength { byte_len } => write!(
+                f,
+                "The last record is incomplete ({byte_len} bytes is not a multiple of {SOLUTION_RECORD_LEN})."
+            ),
+
+            SolutionFileError::InvalidState {
+                record_index,
+                parent,
+                best_child,
+            } => write!(
+                f,
+                "Record {record_index} is invalid (parent = {parent:#x}, best child = {best_child:#x})."
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SolutionFileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SolutionFileError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for SolutionFileError {
+    fn from(error: io::Error) -> Self {
+        SolutionFileError::Io(error)
+    }
+}
+
+pub fn write_solution_file(
+    solution: &StateMap<StateAndStats>,
+    ruleset: Ruleset,
+    writer: impl Write,
+    observer: &mut (impl ProgressObserver + ?Sized),
+) -> Result<(), SolutionFileError> {
+    // The header precedes the body,
+    // so we make one pass to count and checksum the records
+    // before making another pass to write them.
+    let mut entry_count = 0;
+    let mut checksum = Checksum::new();
+    solution.visit_in_key_order(|parent, best_child| {
+        entry_count += 1;
+        checksum.update(&encode_record(parent, best_child));
+    });
+
+    let header = SolutionHeader {
+        version: SOLUTION_FORMAT_VERSION,
+        ruleset,
+        entry_count,
+        checksum: checksum.0,
+    };
+
+    let mut writer = BufWriter::with_capacity(BUFFER_CAPACITY, writer);
+    writer.write_all(&header.to_bytes())?;
+
+    let mut result = Ok(());
+    let mut tracker =
+        PhaseTracker::resume(observer, SolverPhase::WriteSolution, Some(entry_count), 0);
+
+    solution.visit_in_key_order(|parent, best_child| {
+        if result.is_err() {
+            return;
+        }
+
+        result = writer.write_all(&encode_record(parent, best_child));
+
+        tracker.tick(None);
+    });
+
+    tracker.finish(None);
+
+    result?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Returns an error if the file was not written under `ruleset`,
+/// or if it is corrupted or incomplete.
+pub fn read_solution_file(
+    reader: impl Read,
+    ruleset: Ruleset,
+    observer: &mut (impl ProgressObserver + ?Sized),
+) -> Result<StateMap<StateAndStats>, SolutionFileError> {
+    let mut reader = BufReader::with_capacity(BUFFER_CAPACITY, reader);
+    let header = SolutionHeader::read_from(&mut reader)?;
+
+    header.expect_ruleset(ruleset)?;
+
+    let mut out = StateMap::empty();
+    let mut record = [0; SOLUTION_RECORD_LEN];
+    let mut record_index = 0;
+    let mut checksum = Checksum::new();
+    let mut tracker = PhaseTracker::resume(
+        observer,
+        SolverPhase::ReadSolution,
+        Some(header.entry_count),
+        0,
+    );
+
+    loop {
+        let record_len = read_up_to(&mut reader, &mut record)?;
+
+        if record_len == 0 {
+            break;
+        }
+
+        if record_len < SOLUTION_RECORD_LEN {
+            let byte_len = (SolutionHeader::LEN + record_len) as u64
+                + record_index * SOLUTION_RECORD_LEN as u64;
+            if record_len % U64_BYTES != 0 {
+                return Err(SolutionFileError::Truncated { byte_len });
+            }
+            return Err(SolutionFileError::OddRecordLength { byte_len });
+        }
+
+        checksum.update(&record);
+
+        let (parent, best_child) = decode_record(&record, record_index)?;
+        out.add(parent, best_child);
+
+        tracker.tick(None);
+
+        record_index += 1;
+    }
+
+    tracker.finish(None);
+
+    if record_index != header.entry_count {
+        return Err(SolutionFileError::EntryCountMismatch {
+            expected: header.entry_count,
+            found: record_index,
+        });
+    }
+
+    if checksum.0 != header.checksum {
+        return Err(SolutionFileError::ChecksumMismatch {
+            expected: header.checksum,
+            found: checksum.0,
+        });
+    }
+
+    Ok(out)
+}
+
+fn encode_record(parent: State, best_child: StateAndStats) -> [u8; SOLUTION_RECORD_LEN] {
+    let mut record = [0; SOLUTION_RECORD_LEN];
+    record[..U64_BYTES].copy_from_slice(&parent.0.to_le_bytes());
+    record[U64_BYTES..].copy_from_slice(&best_child.0.to_le_bytes());
+    record
+}
+
+pub(crate) fn decode_record(
+    record: &[u8; SOLUTION_RECORD_LEN],
+    record_index: u64,
+) -> Result<(State, StateAndStats), SolutionFileError> {
+    let mut parent_bytes = [0; U64_BYTES];
+    parent_bytes.copy_from_slice(&record[..U64_BYTES]);
+    let raw_parent = u64::from_le_bytes(parent_bytes);
+
+    let mut best_child_bytes = [0; U64_BYTES];
+    best_child_bytes.copy_from_slice(&record[U64_BYTES..]);
+    let raw_best_child = u64::from_le_bytes(best_child_bytes);
+
+    let parent = State(raw_parent);
+    let best_child = StateAndStats(raw_best_child);
+
+    if !parent.is_constructible()
+        || raw_best_child >> Offset::STATS.0 >> 16 != 0
+        || !best_child.state().is_constructible()
+    {
+        return Err(SolutionFileError::InvalidState {
+            record_index,
+            parent: raw_parent,
+            best_child: raw_best_child,
+        });
+    }
+
+    Ok((parent, best_child))
+}
+
+/// Reads until the buffer is full or the reader is exhausted.
+/// Returns the number of bytes read.
+pub(crate) fn read_up_to(reader: &mut impl Read, buffer: &mut [u8]) -> io::Result<usize> {
+    let mut len = 0;
+    while len < buffer.len() {
+        match reader.read(&mut buffer[len..]) {
+            Ok(0) => break,
+            Ok(bytes_read) => len += bytes_read,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+            Err(error) => return Err(error),
+        }
+    }
+    Ok(len)
+}
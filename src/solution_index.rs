@@ -0,0 +1,142 @@
+use super::*;
+
+use solution_file::decode_record;
+
+use std::cmp::Ordering;
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// Answers queries about a solution file
+/// without loading it into a `StateMap`.
+///
+/// Since the records are sorted by their parents,
+/// each query is a binary search over the records,
+/// which takes `O(log n)` seeks.
+///
+/// Unlike `read_solution_file`, this does not verify the body checksum,
+/// since that would require reading the entire file.
+#[derive(Debug)]
+pub struct SolutionIndex<R> {
+    reader: BufReader<R>,
+    header: SolutionHeader,
+
+    /// The reader's logical position (i.e., the position of the
+    /// next byte `reader` would return), relative to the start of the file.
+    position: u64,
+}
+
+impl SolutionIndex<File> {
+    pub fn open(path: impl AsRef<Path>, ruleset: Ruleset) -> Result<Self, SolutionFileError> {
+        Self::new(File::open(path)?, ruleset)
+    }
+}
+
+impl<R: Read + Seek> SolutionIndex<R> {
+    /// The reader must be positioned at the start of the solution file.
+    pub fn new(reader: R, ruleset: Ruleset) -> Result<Self, SolutionFileError> {
+        // Most lookups after the first few binary search steps
+        // land near the previous one, so a small buffer suffices.
+        let mut reader = BufReader::with_capacity(64 * SOLUTION_RECORD_LEN, reader);
+        let header = SolutionHeader::read_from(&mut reader)?;
+        header.expect_ruleset(ruleset)?;
+
+        let byte_len = reader.seek(SeekFrom::End(0))?;
+        let body_len = byte_len - SolutionHeader::LEN as u64;
+        if !body_len.is_multiple_of(SOLUTION_RECORD_LEN as u64) {
+            if !body_len.is_multiple_of(std::mem::size_of::<u64>() as u64) {
+                return Err(SolutionFileError::Truncated { byte_len });
+            }
+            return Err(SolutionFileError::OddRecordLength { byte_len });
+        }
+
+        let entry_count = body_len / SOLUTION_RECORD_LEN as u64;
+        if entry_count != header.entry_count {
+            return Err(SolutionFileError::EntryCountMismatch {
+                expected: header.entry_count,
+                found: entry_count,
+            });
+        }
+
+        Ok(Self {
+            reader,
+            header,
+            position: byte_len,
+        })
+    }
+
+    pub fn header(&self) -> SolutionHeader {
+        self.header
+    }
+
+    /// Returns the number of records.
+    pub fn len(&self) -> u64 {
+        self.header.entry_count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `None` if `state` has no record
+    /// (i.e., it is terminal or unreachable).
+    pub fn best_child(&mut self, state: State) -> Result<Option<StateAndStats>, SolutionFileError> {
+        let mut low = 0;
+        let mut high = self.len();
+
+        while low < high {
+            let mid = low + (high - low) / 2;
+            let (parent, best_child) = self.record(mid)?;
+
+            match parent.cmp(&state) {
+                Ordering::Less => low = mid + 1,
+                Ordering::Greater => high = mid,
+                Ordering::Equal => return Ok(Some(best_child)),
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Returns the best outcome of `state`.
+    /// Terminal states are wins or losses in zero plies.
+    /// Returns `None` if `state` is non-terminal but has no record
+    /// (i.e., it is unreachable).
+    pub fn outcome(&mut self, state: State) -> Result<Option<Outcome>, SolutionFileError> {
+        match state.terminality() {
+            Terminality::Loss => return Ok(Some(Outcome::loss_in(0))),
+            Terminality::Win => return Ok(Some(Outcome::win_in(0))),
+            Terminality::Nonterminal => {}
+        }
+
+        Ok(self
+            .best_child(state)?
+            .map(|best_child| best_child.parent_outcome()))
+    }
+
+    /// Returns the record at the given index.
+    /// Panics if the index is out of bounds.
+    pub fn record(&mut self, index: u64) -> Result<(State, StateAndStats), SolutionFileError> {
+        assert!(
+            index < self.len(),
+            "Record index {index} is out of bounds (there are {} records).",
+            self.len()
+        );
+
+        let target = SolutionHeader::LEN as u64 + index * SOLUTION_RECORD_LEN as u64;
+        // Unlike `seek`, `seek_relative` keeps the buffer
+        // if the target is already in it.
+        self.reader
+            .seek_relative(target as i64 - self.position as i64)?;
+        self.position = target;
+
+        let mut record = [0; SOLUTION_RECORD_LEN];
+        if let Err(error) = self.reader.read_exact(&mut record) {
+            self.position = self.reader.stream_position()?;
+            return Err(error.into());
+        }
+        self.position += SOLUTION_RECORD_LEN as u64;
+
+        decode_record(&record, index)
+    }
+}
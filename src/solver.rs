@@ -0,0 +1,700 @@
+use super::*;
+
+use checkpoint::{
+    read_reachable_states_checkpoint, read_state_stats_checkpoint,
+    write_reachable_states_checkpoint, write_state_stats_checkpoint, CheckpointError,
+    CheckpointKind,
+};
+
+use std::fmt::{self, Display, Formatter};
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// Runs the solver pipeline
+/// (`reachable_states`, `initial_stat_map`, `compute_stats`, then `best_child_map`).
+///
+/// ```no_run
+/// use dobutsu_shogi_2024::*;
+///
+/// let solution = Solver::new()
+///     .thread_count(8)
+///     .checkpoint_dir("checkpoints")
+///     .output(SolutionFileSink("solution.dat".into()))
+///     .solve()
+///     .unwrap();
+/// ```
+pub struct Solver<'a> {
+    root: State,
+    ruleset: Ruleset,
+    thread_count: usize,
+    tie_breaks: Vec<TieBreak>,
+    checkpoint_dir: Option<PathBuf>,
+    observer: Box<dyn ProgressObserver + 'a>,
+    token: CancellationToken,
+    snapshot: Option<SolverSnapshot>,
+    outputs: Vec<Box<dyn SolutionSink + 'a>>,
+    phase_timings: Vec<(SolverPhase, Duration)>,
+}
+
+/// The progress of a cancelled solve.
+/// Pass it to `Solver::resume_from` to continue the solve.
+#[derive(Clone, Debug)]
+pub struct SolverSnapshot {
+    root: State,
+    ruleset: Ruleset,
+    tie_breaks: Vec<TieBreak>,
+    phase: PhaseSnapshot,
+    phase_timings: Vec<(SolverPhase, Duration)>,
+}
+
+#[derive(Clone, Debug)]
+enum PhaseSnapshot {
+    ReachableStates(ReachableStatesSnapshot),
+
+    InitialStats {
+        reachable: StateSet,
+        snapshot: InitialStatsSnapshot,
+    },
+
+    Retrograde {
+        map: StateMap<StateStats>,
+        snapshot: RetrogradeSnapshot,
+    },
+
+    BestChildren {
+        map: StateMap<StateStats>,
+        snapshot: BestChildrenSnapshot,
+    },
+}
+
+enum PhaseResult {
+    Next(PhaseSnapshot),
+    Done(StateMap<StateAndStats>),
+}
+
+/// The result of a solve.
+/// It maps every non-terminal state reachable from the root
+/// to its best child.
+#[derive(Clone, Debug)]
+pub struct Solution {
+    root: State,
+    ruleset: Ruleset,
+    best_child_map: StateMap<StateAndStats>,
+    phase_timings: Vec<(SolverPhase, Duration)>,
+}
+
+/// A destination that `Solver::solve` writes the solution to.
+pub trait SolutionSink {
+    /// `observer` is the solver's observer (see `Solver::progress`),
+    /// which the sink reports its own passes to.
+    fn write_solution(
+        &mut self,
+        solution: &Solution,
+        observer: &mut dyn ProgressObserver,
+    ) -> Result<(), SolverError>;
+}
+
+/// Writes the solution to a [solution file](crate::solution_file).
+#[derive(Clone, Debug)]
+pub struct SolutionFileSink(pub PathBuf);
+
+/// Writes the solution to a [compact solution](crate::compact_best) file.
+#[derive(Clone, Debug)]
+pub struct CompactSolutionSink(pub PathBuf);
+
+/// Writes the outcome of every move of every state
+/// to a [move outcome table](crate::move_outcomes) file.
+#[derive(Clone, Debug)]
+pub struct MoveOutcomeTableSink(pub PathBuf);
+
+/// Writes every optimal child of every state
+/// to an [optimal children](crate::optimal_children) file.
+#[derive(Clone, Debug)]
+pub struct OptimalChildrenSink(pub PathBuf);
+
+/// Writes the [proof tree](crate::proof_tree) of the root
+/// (if the root is not a draw).
+#[derive(Clone, Debug)]
+pub struct ProofTreeSink(pub PathBuf);
+
+/// Writes the solution to a [WDL table](crate::wdl) file.
+#[derive(Clone, Debug)]
+pub struct WdlTableSink(pub PathBuf);
+
+/// Writes the solution's [summary](crate::summary) to a JSON file.
+#[derive(Clone, Debug)]
+pub struct SummarySink(pub PathBuf);
+
+#[derive(Debug)]
+pub enum SolverError {
+    Io(io::Error),
+
+    SolutionFile(SolutionFileError),
+
+    /// A checkpoint file exists, but it cannot be used.
+    /// Delete it to recompute the phase.
+    Checkpoint {
+        path: PathBuf,
+        error: CheckpointError,
+    },
+
+    /// The cancellation token was cancelled.
+    /// The snapshot can be passed to `Solver::resume_from`.
+    Cancelled(Box<SolverSnapshot>),
+}
+
+impl Display for SolverError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            SolverError::Io(error) => write!(f, "I/O error: {error}"),
+
+            SolverError::SolutionFile(error) => write!(f, "{error}"),
+
+            SolverError::Checkpoint { path, error } => {
+                write!(f, "Invalid checkpoint {:?}: {error}", path)
+            }
+
+            SolverError::Cancelled(snapshot) => write!(
+                f,
+                "The solve was cancelled during the {} phase.",
+                snapshot.phase().name()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SolverError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SolverError::Io(error) => Some(error),
+            SolverError::SolutionFile(error) => Some(error),
+            SolverError::Checkpoint { error, .. } => Some(error),
+            SolverError::Cancelled(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for SolverError {
+    fn from(error: io::Error) -> Self {
+        SolverError::Io(error)
+    }
+}
+
+impl From<SolutionFileError> for SolverError {
+    fn from(error: SolutionFileError) -> Self {
+        SolverError::SolutionFile(error)
+    }
+}
+
+impl Default for Solver<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> Solver<'a> {
+    /// By default, the solver solves the whole game
+    /// under `Ruleset::SimplifiedTry` on one thread,
+    /// without checkpoints, progress reports, or outputs.
+    pub fn new() -> Self {
+        Self {
+            root: State::initial(),
+            ruleset: Ruleset::SimplifiedTry,
+            thread_count: 1,
+            tie_breaks: vec![],
+            checkpoint_dir: None,
+            observer: Box::new(SilentObserver),
+            token: CancellationToken::new(),
+            snapshot: None,
+            outputs: vec![],
+            phase_timings: vec![],
+        }
+    }
+
+    /// Only states reachable from `root` are solved.
+    pub fn root(mut self, root: State) -> Self {
+        self.root = root;
+        self
+    }
+
+    pub fn ruleset(mut self, ruleset: Ruleset) -> Self {
+        self.ruleset = ruleset;
+        self
+    }
+
+    /// Only the best child phase is parallelized.
+    /// The other phases always run on the calling thread.
+    pub fn thread_count(mut self, thread_count: usize) -> Self {
+        assert!(thread_count > 0, "Thread count must be positive.");
+        self.thread_count = thread_count;
+        self
+    }
+
+    /// Breaks ties between best children with `tie_breaks`
+    /// (see `TieBreak`).
+    pub fn tie_breaks(mut self, tie_breaks: Vec<TieBreak>) -> Self {
+        self.tie_breaks = tie_breaks;
+        self
+    }
+
+    /// The solver saves the reachable states and the state stats
+    /// to this directory once they are computed.
+    /// If a later solve (with the same root and ruleset)
+    /// finds them there, it skips the phases that produced them.
+    pub fn checkpoint_dir(mut self, checkpoint_dir: impl Into<PathBuf>) -> Self {
+        self.checkpoint_dir = Some(checkpoint_dir.into());
+        self
+    }
+
+    /// Phases that are skipped because of a checkpoint
+    /// are not reported.
+    /// The outputs report their passes to `observer` too.
+    pub fn progress(mut self, observer: impl ProgressObserver + 'a) -> Self {
+        self.observer = Box::new(observer);
+        self
+    }
+
+    /// If `token` is cancelled, `solve` returns `SolverError::Cancelled`
+    /// once the current phase notices.
+    /// If `token` is paused, the current phase blocks until it is resumed.
+    pub fn cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.token = token;
+        self
+    }
+
+    /// Continues a cancelled solve instead of starting a new one.
+    /// This overrides the root, the ruleset, and the tie-breaks
+    /// with the snapshot's.
+    pub fn resume_from(mut self, snapshot: SolverSnapshot) -> Self {
+        self.root = snapshot.root;
+        self.ruleset = snapshot.ruleset;
+        self.tie_breaks = snapshot.tie_breaks.clone();
+        self.snapshot = Some(snapshot);
+        self
+    }
+
+    /// Sinks are written to in the order they are added.
+    pub fn output(mut self, sink: impl SolutionSink + 'a) -> Self {
+        self.outputs.push(Box::new(sink));
+        self
+    }
+
+    pub fn solve(mut self) -> Result<Solution, SolverError> {
+        let mut phase = match self.snapshot.take() {
+            Some(snapshot) => {
+                self.phase_timings = snapshot.phase_timings;
+                snapshot.phase
+            }
+            None => self.first_phase()?,
+        };
+
+        loop {
+            let solver_phase = phase.solver_phase();
+            let start_time = Instant::now();
+            let result = self.run_phase(phase);
+            self.record_timing(solver_phase, start_time.elapsed());
+
+            phase = match result {
+                Ok(PhaseResult::Next(next_phase)) => next_phase,
+
+                Ok(PhaseResult::Done(best_child_map)) => return self.finish(best_child_map),
+
+                Err(SolverError::Cancelled(mut snapshot)) => {
+                    snapshot.phase_timings = self.phase_timings.clone();
+                    return Err(SolverError::Cancelled(snapshot));
+                }
+
+                Err(error) => return Err(error),
+            };
+        }
+    }
+
+    /// Runs `phase` to completion (including saving its checkpoint, if any).
+    fn run_phase(&mut self, phase: PhaseSnapshot) -> Result<PhaseResult, SolverError> {
+        match phase {
+            PhaseSnapshot::ReachableStates(snapshot) => {
+                let reachable = resume_reachable_states(snapshot, &mut *self.observer, &self.token)
+                    .map_err(|cancelled| {
+                        self.cancelled(PhaseSnapshot::ReachableStates(cancelled.snapshot))
+                    })?;
+
+                self.save_checkpoint(CheckpointKind::ReachableStates, |file| {
+                    write_reachable_states_checkpoint(&reachable, self.root, self.ruleset, file)
+                })?;
+
+                Ok(PhaseResult::Next(PhaseSnapshot::InitialStats {
+                    reachable,
+                    snapshot: InitialStatsSnapshot::new(),
+                }))
+            }
+
+            PhaseSnapshot::InitialStats {
+                reachable,
+                snapshot,
+            } => {
+                match resume_initial_stat_map(
+                    &reachable,
+                    snapshot,
+                    &mut *self.observer,
+                    &self.token,
+                ) {
+                    Ok(map) => Ok(PhaseResult::Next(PhaseSnapshot::Retrograde {
+                        snapshot: RetrogradeSnapshot::new(&map),
+                        map,
+                    })),
+
+                    Err(cancelled) => Err(self.cancelled(PhaseSnapshot::InitialStats {
+                        reachable,
+                        snapshot: cancelled.snapshot,
+                    })),
+                }
+            }
+
+            PhaseSnapshot::Retrograde { mut map, snapshot } => {
+                if let Err(cancelled) =
+                    resume_compute_stats(&mut map, snapshot, &mut *self.observer, &self.token)
+                {
+                    return Err(self.cancelled(PhaseSnapshot::Retrograde {
+                        map,
+                        snapshot: cancelled.snapshot,
+                    }));
+                }
+
+                self.save_checkpoint(CheckpointKind::StateStats, |file| {
+                    write_state_stats_checkpoint(&map, self.root, self.ruleset, file)
+                })?;
+
+                Ok(PhaseResult::Next(PhaseSnapshot::BestChildren {
+                    map,
+                    snapshot: BestChildrenSnapshot::with_tie_breaks(self.tie_breaks.clone()),
+                }))
+            }
+
+            PhaseSnapshot::BestChildren { map, snapshot } => {
+                let result = if self.thread_count == 1 {
+                    resume_best_child_map(&map, snapshot, &mut *self.observer, &self.token)
+                } else {
+                    resume_best_child_map_parallel(
+                        &map,
+                        snapshot,
+                        self.thread_count,
+                        &mut *self.observer,
+                        &self.token,
+                    )
+                };
+
+                match result {
+                    Ok(best_child_map) => Ok(PhaseResult::Done(best_child_map)),
+
+                    Err(cancelled) => Err(self.cancelled(PhaseSnapshot::BestChildren {
+                        map,
+                        snapshot: cancelled.snapshot,
+                    })),
+                }
+            }
+        }
+    }
+
+    /// Adds to the phase's timing if it already has one
+    /// (i.e., if the phase was cancelled and resumed).
+    fn record_timing(&mut self, phase: SolverPhase, duration: Duration) {
+        match self
+            .phase_timings
+            .iter_mut()
+            .find(|(timed_phase, _)| *timed_phase == phase)
+        {
+            Some((_, total)) => *total += duration,
+            None => self.phase_timings.push((phase, duration)),
+        }
+    }
+
+    /// Skips as many phases as the checkpoints allow.
+    fn first_phase(&self) -> Result<PhaseSnapshot, SolverError> {
+        if let Some(map) = self.load_checkpoint(CheckpointKind::StateStats, |file| {
+            read_state_stats_checkpoint(file, self.root, self.ruleset)
+        })? {
+            return Ok(PhaseSnapshot::BestChildren {
+                map,
+                snapshot: BestChildrenSnapshot::with_tie_breaks(self.tie_breaks.clone()),
+            });
+        }
+
+        if let Some(reachable) = self.load_checkpoint(CheckpointKind::ReachableStates, |file| {
+            read_reachable_states_checkpoint(file, self.root, self.ruleset)
+        })? {
+            return Ok(PhaseSnapshot::InitialStats {
+                reachable,
+                snapshot: InitialStatsSnapshot::new(),
+            });
+        }
+
+        Ok(PhaseSnapshot::ReachableStates(
+            ReachableStatesSnapshot::new(self.root),
+        ))
+    }
+
+    fn finish(mut self, best_child_map: StateMap<StateAndStats>) -> Result<Solution, SolverError> {
+        let solution = Solution {
+            root: self.root,
+            ruleset: self.ruleset,
+            best_child_map,
+            phase_timings: std::mem::take(&mut self.phase_timings),
+        };
+
+        for sink in &mut self.outputs {
+            sink.write_solution(&solution, &mut *self.observer)?;
+        }
+
+        Ok(solution)
+    }
+
+    fn cancelled(&self, phase: PhaseSnapshot) -> SolverError {
+        SolverError::Cancelled(Box::new(SolverSnapshot {
+            root: self.root,
+            ruleset: self.ruleset,
+            tie_breaks: self.tie_breaks.clone(),
+            phase,
+            // `solve` fills these in, since it times the phase
+            // after the phase returns.
+            phase_timings: vec![],
+        }))
+    }
+
+    /// Returns `None` if there is no checkpoint directory,
+    /// or if the checkpoint does not exist.
+    fn load_checkpoint<T>(
+        &self,
+        kind: CheckpointKind,
+        read: impl FnOnce(File) -> Result<T, CheckpointError>,
+    ) -> Result<Option<T>, SolverError> {
+        let Some(checkpoint_dir) = &self.checkpoint_dir else {
+            return Ok(None);
+        };
+
+        let path = checkpoint_dir.join(kind.file_name());
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let file = File::open(&path)?;
+        read(file)
+            .map(Some)
+            .map_err(|error| SolverError::Checkpoint { path, error })
+    }
+
+    fn save_checkpoint(
+        &self,
+        kind: CheckpointKind,
+        write: impl FnOnce(&mut File) -> io::Result<()>,
+    ) -> Result<(), SolverError> {
+        let Some(checkpoint_dir) = &self.checkpoint_dir else {
+            return Ok(());
+        };
+
+        fs::create_dir_all(checkpoint_dir)?;
+
+        // We write to a temporary file first,
+        // so that an interrupted write never leaves
+        // a partial checkpoint under the real name.
+        let path = checkpoint_dir.join(kind.file_name());
+        let temp_path = path.with_extension("ckpt.tmp");
+        write(&mut File::create(&temp_path)?)?;
+        fs::rename(temp_path, path)?;
+
+        Ok(())
+    }
+}
+
+impl SolverSnapshot {
+    pub fn root(&self) -> State {
+        self.root
+    }
+
+    pub fn ruleset(&self) -> Ruleset {
+        self.ruleset
+    }
+
+    pub fn tie_breaks(&self) -> &[TieBreak] {
+        &self.tie_breaks
+    }
+
+    /// Returns the phase that was cancelled.
+    pub fn phase(&self) -> SolverPhase {
+        self.phase.solver_phase()
+    }
+
+    /// Returns the time each phase took before the solve was cancelled.
+    pub fn phase_timings(&self) -> &[(SolverPhase, Duration)] {
+        &self.phase_timings
+    }
+}
+
+impl PhaseSnapshot {
+    fn solver_phase(&self) -> SolverPhase {
+        match self {
+            PhaseSnapshot::ReachableStates(_) => SolverPhase::ReachableStates,
+            PhaseSnapshot::InitialStats { .. } => SolverPhase::InitialStats,
+            PhaseSnapshot::Retrograde { .. } => SolverPhase::Retrograde,
+            PhaseSnapshot::BestChildren { .. } => SolverPhase::BestChildren,
+        }
+    }
+}
+
+impl Solution {
+    pub fn root(&self) -> State {
+        self.root
+    }
+
+    pub fn ruleset(&self) -> Ruleset {
+        self.ruleset
+    }
+
+    pub fn best_child_map(&self) -> &StateMap<StateAndStats> {
+        &self.best_child_map
+    }
+
+    pub fn into_best_child_map(self) -> StateMap<StateAndStats> {
+        self.best_child_map
+    }
+
+    /// Returns the time each phase took, in the order the phases ran.
+    /// Each phase's time includes saving its checkpoint.
+    /// Phases skipped because of a checkpoint are omitted.
+    pub fn phase_timings(&self) -> &[(SolverPhase, Duration)] {
+        &self.phase_timings
+    }
+
+    pub fn summary(&self) -> SolutionSummary {
+        SolutionSummary::from_best_child_map(&self.best_child_map, self.root)
+            .with_phase_timings(self.phase_timings.clone())
+    }
+
+    /// Returns `None` if `state` is terminal or unreachable.
+    pub fn best_child(&self, state: State) -> Option<StateAndStats> {
+        let best_child = self.best_child_map.get(state);
+        if best_child.is_null() {
+            return None;
+        }
+
+        Some(best_child)
+    }
+
+    /// Returns the best outcome of `state`.
+    /// Terminal states are wins or losses in zero plies.
+    /// Returns `None` if `state` is non-terminal and unreachable.
+    pub fn outcome(&self, state: State) -> Option<Outcome> {
+        self.best_child_map.outcome(state)
+    }
+}
+
+impl<F: FnMut(&Solution) -> Result<(), SolverError>> SolutionSink for F {
+    fn write_solution(
+        &mut self,
+        solution: &Solution,
+        _: &mut dyn ProgressObserver,
+    ) -> Result<(), SolverError> {
+        self(solution)
+    }
+}
+
+impl SolutionSink for SolutionFileSink {
+    fn write_solution(
+        &mut self,
+        solution: &Solution,
+        observer: &mut dyn ProgressObserver,
+    ) -> Result<(), SolverError> {
+        write_solution_file(
+            solution.best_child_map(),
+            solution.ruleset(),
+            File::create(&self.0)?,
+            observer,
+        )?;
+        Ok(())
+    }
+}
+
+impl SolutionSink for CompactSolutionSink {
+    fn write_solution(
+        &mut self,
+        solution: &Solution,
+        observer: &mut dyn ProgressObserver,
+    ) -> Result<(), SolverError> {
+        let compact = compact_best_child_map(solution.best_child_map(), observer);
+        let mut writer = BufWriter::new(File::create(&self.0)?);
+        write_compact_solution(&compact, &mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+impl SolutionSink for MoveOutcomeTableSink {
+    fn write_solution(
+        &mut self,
+        solution: &Solution,
+        observer: &mut dyn ProgressObserver,
+    ) -> Result<(), SolverError> {
+        let table = MoveOutcomeTable::from_best_child_map(solution.best_child_map(), observer);
+        let mut writer = BufWriter::new(File::create(&self.0)?);
+        table.write_to(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+impl SolutionSink for OptimalChildrenSink {
+    fn write_solution(
+        &mut self,
+        solution: &Solution,
+        observer: &mut dyn ProgressObserver,
+    ) -> Result<(), SolverError> {
+        let optimal = optimal_children_map(solution.best_child_map(), observer);
+        let mut writer = BufWriter::new(File::create(&self.0)?);
+        write_optimal_children(&optimal, &mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+impl SolutionSink for ProofTreeSink {
+    fn write_solution(
+        &mut self,
+        solution: &Solution,
+        _: &mut dyn ProgressObserver,
+    ) -> Result<(), SolverError> {
+        let Some(tree) = proof_tree(solution.best_child_map(), solution.root()) else {
+            return Ok(());
+        };
+
+        let mut writer = BufWriter::new(File::create(&self.0)?);
+        tree.write_to(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+impl SolutionSink for SummarySink {
+    fn write_solution(
+        &mut self,
+        solution: &Solution,
+        _: &mut dyn ProgressObserver,
+    ) -> Result<(), SolverError> {
+        let mut json = solution.summary().to_json();
+        json.push('\n');
+        fs::write(&self.0, json)?;
+        Ok(())
+    }
+}
+
+impl SolutionSink for WdlTableSink {
+    fn write_solution(
+        &mut self,
+        solution: &Solution,
+        observer: &mut dyn ProgressObserver,
+    ) -> Result<(), SolverError> {
+        let table = wdl_table_from_best_child_map(solution.best_child_map(), observer);
+        table.write_to(File::create(&self.0)?)?;
+        Ok(())
+    }
+}
@@ -1,8 +1,13 @@
 use super::*;
 
+/// The number of top-level buckets in a `StateMap` or `StateSet`.
+/// Sharding either by this count makes each shard exactly one bucket.
+pub(crate) const TOP_LEVEL_BUCKET_COUNT: usize = 256 * 256;
+
 #[derive(Clone, Debug)]
 pub struct StateMap<T> {
-    raw: [Option<Box<Bucket0<T>>>; 256 * 256],
+    raw: Box<[Option<Box<Bucket0<T>>>]>,
+    len: u64,
 }
 
 pub type StateMapNode<T> = [Option<Box<T>>; 16];
@@ -113,10 +118,14 @@ impl Null for StateStats {
 impl<T: Copy + Null + std::fmt::Debug> StateMap<T> {
     pub fn empty() -> Self {
         Self {
-            raw: core::array::from_fn(|_| None),
+            raw: (0..256 * 256).map(|_| None).collect(),
+            len: 0,
         }
     }
 
+    // The last bucket level's shift is spelled out (`40 - 16 - 6 * 4`)
+    // for symmetry with the other levels, even though it is zero.
+    #[allow(clippy::identity_op, clippy::eq_op)]
     pub fn add(&mut self, state: State, value: T) -> DidAddendAlreadyExist {
         let bucket0 = self.raw[(state.0 >> (40 - 16)) as usize].get_or_insert_with(Null::null);
         let bucket1 = bucket0.0[((state.0 >> (40 - 16 - 4)) & 0b1111) as usize]
@@ -135,11 +144,28 @@ impl<T: Copy + Null + std::fmt::Debug> StateMap<T> {
 
         *item = value;
 
+        // Adding a null value removes the entry.
+        match (did_addend_already_exist, value.is_null()) {
+            (false, false) => self.len += 1,
+            (true, true) => self.len -= 1,
+            _ => {}
+        }
+
         DidAddendAlreadyExist {
             did_addend_already_exist,
         }
     }
 
+    /// Returns the number of entries in the map.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[allow(clippy::identity_op, clippy::eq_op)]
     pub fn get(&self, state: State) -> T {
         let Some(bucket0) = self.raw[(state.0 >> (40 - 16)) as usize].as_ref() else {
             return Null::null();
@@ -173,35 +199,14 @@ impl<T: Copy + Null + std::fmt::Debug> StateMap<T> {
         bucket5.0[((state.0 >> (40 - 16 - 6 * 4)) & 0b1111) as usize]
     }
 
+    #[allow(clippy::identity_op, clippy::eq_op)]
     pub fn get_mut(&mut self, state: State) -> Option<&mut T> {
-        let Some(bucket0) = self.raw[(state.0 >> (40 - 16)) as usize].as_mut() else {
-            return None;
-        };
-
-        let Some(bucket1) = bucket0.0[((state.0 >> (40 - 16 - 4)) & 0b1111) as usize].as_mut()
-        else {
-            return None;
-        };
-
-        let Some(bucket2) = bucket1.0[((state.0 >> (40 - 16 - 2 * 4)) & 0b1111) as usize].as_mut()
-        else {
-            return None;
-        };
-
-        let Some(bucket3) = bucket2.0[((state.0 >> (40 - 16 - 3 * 4)) & 0b1111) as usize].as_mut()
-        else {
-            return None;
-        };
-
-        let Some(bucket4) = bucket3.0[((state.0 >> (40 - 16 - 4 * 4)) & 0b1111) as usize].as_mut()
-        else {
-            return None;
-        };
-
-        let Some(bucket5) = bucket4.0[((state.0 >> (40 - 16 - 5 * 4)) & 0b1111) as usize].as_mut()
-        else {
-            return None;
-        };
+        let bucket0 = self.raw[(state.0 >> (40 - 16)) as usize].as_mut()?;
+        let bucket1 = bucket0.0[((state.0 >> (40 - 16 - 4)) & 0b1111) as usize].as_mut()?;
+        let bucket2 = bucket1.0[((state.0 >> (40 - 16 - 2 * 4)) & 0b1111) as usize].as_mut()?;
+        let bucket3 = bucket2.0[((state.0 >> (40 - 16 - 3 * 4)) & 0b1111) as usize].as_mut()?;
+        let bucket4 = bucket3.0[((state.0 >> (40 - 16 - 4 * 4)) & 0b1111) as usize].as_mut()?;
+        let bucket5 = bucket4.0[((state.0 >> (40 - 16 - 5 * 4)) & 0b1111) as usize].as_mut()?;
 
         let out = &mut bucket5.0[((state.0 >> (40 - 16 - 6 * 4)) & 0b1111) as usize];
 
@@ -220,6 +225,34 @@ impl<T: Copy + Null + std::fmt::Debug> StateMap<T> {
         self
     }
 
+    /// Unlike `union`, this moves `other`'s buckets into `self`
+    /// wherever `self` has no corresponding bucket,
+    /// so merging maps with disjoint key ranges is cheap.
+    pub fn union_in_place(&mut self, other: Self) {
+        let mut overlap = Self::empty();
+
+        // We count every entry of `other` as new,
+        // and then correct the count for the overlapping entries below.
+        self.len += other.len;
+
+        for (i0, other_bucket0) in other.raw.into_vec().into_iter().enumerate() {
+            let Some(other_bucket0) = other_bucket0 else {
+                continue;
+            };
+
+            if self.raw[i0].is_none() {
+                self.raw[i0] = Some(other_bucket0);
+            } else {
+                overlap.raw[i0] = Some(other_bucket0);
+            }
+        }
+
+        overlap.visit_in_key_order(|state, value| {
+            self.len -= 1;
+            self.add(state, value);
+        });
+    }
+
     pub fn to_sorted_vec(&self) -> Vec<(State, T)> {
         let mut raw = Vec::new();
 
@@ -240,6 +273,34 @@ impl<T: Copy + Null + std::fmt::Debug> StateMap<T> {
         }
     }
 
+    /// Splits the key space into `shard_count` contiguous ranges,
+    /// and visits the entries in the `shard_index`th range
+    /// in the order of their keys.
+    ///
+    /// Visiting every shard (in any order) visits every entry exactly once,
+    /// so the shards can be processed in parallel.
+    pub fn visit_shard_in_key_order(
+        &self,
+        shard_index: usize,
+        shard_count: usize,
+        mut visitor: impl FnMut(State, T),
+    ) {
+        assert!(
+            shard_index < shard_count,
+            "Shard index {shard_index} is out of bounds (there are {shard_count} shards)."
+        );
+
+        let start = self.raw.len() * shard_index / shard_count;
+        let end = self.raw.len() * (shard_index + 1) / shard_count;
+        for (i0, bucket0) in self.raw[start..end].iter().enumerate() {
+            let Some(bucket0) = bucket0 else {
+                continue;
+            };
+            let prefix = ((start + i0) as u64) << (40 - 16);
+            self.visit0(prefix, bucket0, &mut visitor);
+        }
+    }
+
     fn visit0(&self, prefix: u64, bucket0: &Bucket0<T>, mut visitor: impl FnMut(State, T)) {
         for (i1, bucket1) in bucket0.0.iter().enumerate() {
             let Some(bucket1) = bucket1 else {
@@ -290,6 +351,7 @@ impl<T: Copy + Null + std::fmt::Debug> StateMap<T> {
         }
     }
 
+    #[allow(clippy::identity_op, clippy::eq_op)]
     fn visit5(&self, prefix: u64, bucket5: &Bucket5<T>, mut visitor: impl FnMut(State, T)) {
         for i6 in 0..16 {
             let item = bucket5.0[i6];
@@ -2,7 +2,8 @@ use super::*;
 
 #[derive(Clone, Debug)]
 pub struct StateSet {
-    raw: [Option<Box<Bucket0>>; 256 * 256],
+    raw: Box<[Option<Box<Bucket0>>]>,
+    len: u64,
 }
 
 #[derive(Clone, Copy, Debug, Default)]
@@ -33,10 +34,14 @@ impl StateSet {
         }
 
         Self {
-            raw: v.try_into().unwrap(),
+            raw: v.into_boxed_slice(),
+            len: 0,
         }
     }
 
+    // The last bucket level's shift is spelled out (`40 - 16 - 6 * 4`)
+    // for symmetry with the other levels, even though it is zero.
+    #[allow(clippy::identity_op, clippy::eq_op)]
     pub fn add(&mut self, state: State) -> DidAddendAlreadyExist {
         let bucket0 =
             self.raw[(state.0 >> (40 - 16)) as usize].get_or_insert_with(Default::default);
@@ -57,11 +62,24 @@ impl StateSet {
 
         bucket5.0 |= mask;
 
+        if !did_addend_already_exist {
+            self.len += 1;
+        }
+
         DidAddendAlreadyExist {
             did_addend_already_exist,
         }
     }
 
+    /// Returns the number of states in the set.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
     pub fn union(mut self, other: &Self) -> Self {
         other.visit_in_order(|state| {
             self.add(state);
@@ -88,6 +106,32 @@ impl StateSet {
         }
     }
 
+    /// Splits the key space into `shard_count` contiguous ranges,
+    /// and visits the states in the `shard_index`th range in order.
+    ///
+    /// Visiting every shard (in any order) visits every state exactly once.
+    pub fn visit_shard_in_order(
+        &self,
+        shard_index: usize,
+        shard_count: usize,
+        mut visitor: impl FnMut(State),
+    ) {
+        assert!(
+            shard_index < shard_count,
+            "Shard index {shard_index} is out of bounds (there are {shard_count} shards)."
+        );
+
+        let start = self.raw.len() * shard_index / shard_count;
+        let end = self.raw.len() * (shard_index + 1) / shard_count;
+        for (i0, bucket0) in self.raw[start..end].iter().enumerate() {
+            let Some(bucket0) = bucket0 else {
+                continue;
+            };
+            let prefix = ((start + i0) as u64) << (40 - 16);
+            self.visit0(prefix, bucket0, &mut visitor);
+        }
+    }
+
     fn visit0(&self, prefix: u64, bucket0: &Bucket0, mut visitor: impl FnMut(State)) {
         for (i1, bucket1) in bucket0.iter().enumerate() {
             let Some(bucket1) = bucket1 else {
@@ -135,6 +179,7 @@ impl StateSet {
         }
     }
 
+    #[allow(clippy::identity_op, clippy::eq_op)]
     fn visit5(&self, prefix: u64, bucket5: Bucket5, mut visitor: impl FnMut(State)) {
         for i6 in 0..16 {
             if bucket5.0 & (1 << i6) != 0 {
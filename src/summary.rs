@@ -0,0 +1,182 @@
+use super::*;
+
+use std::time::Duration;
+
+/// The number of distinct delays a win or loss can have.
+/// `Outcome::win_in(n)` and `Outcome::loss_in(n)` are distinct from a draw
+/// for every `n` less than this.
+pub const OUTCOME_DELAY_COUNT: usize = 201;
+
+/// Headline numbers about a solution.
+///
+/// Outcomes are from the perspective of each state's active player,
+/// so every state counted in `win_count` is a win for
+/// the player about to move.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SolutionSummary {
+    pub root: State,
+
+    /// The number of states reachable from the root,
+    /// including terminal states.
+    pub reachable_state_count: u64,
+
+    pub win_count: u64,
+    pub loss_count: u64,
+    pub draw_count: u64,
+
+    /// `win_in[n]` is the number of states that are wins in `n` plies.
+    pub win_in: [u64; OUTCOME_DELAY_COUNT],
+
+    /// `loss_in[n]` is the number of states that are losses in `n` plies.
+    pub loss_in: [u64; OUTCOME_DELAY_COUNT],
+
+    /// This is `None` if the root is not in the map.
+    pub root_outcome: Option<Outcome>,
+
+    /// The time each phase took, in the order the phases ran.
+    /// Phases that were skipped (e.g., because of a checkpoint) are omitted.
+    pub phase_timings: Vec<(SolverPhase, Duration)>,
+}
+
+impl SolutionSummary {
+    fn empty(root: State) -> Self {
+        Self {
+            root,
+            reachable_state_count: 0,
+            win_count: 0,
+            loss_count: 0,
+            draw_count: 0,
+            win_in: [0; OUTCOME_DELAY_COUNT],
+            loss_in: [0; OUTCOME_DELAY_COUNT],
+            root_outcome: None,
+            phase_timings: vec![],
+        }
+    }
+
+    /// `map` must be the result of `compute_stats`
+    /// for the states reachable from `root`.
+    pub fn from_stats_map(map: &StateMap<StateStats>, root: State) -> Self {
+        let mut out = Self::empty(root);
+
+        map.visit_in_key_order(|_, stats| {
+            out.record(stats.best_outcome().unwrap_or(Outcome::DRAW));
+        });
+
+        let root_stats = map.get(root);
+        if !root_stats.is_null() {
+            out.root_outcome = Some(root_stats.best_outcome().unwrap_or(Outcome::DRAW));
+        }
+
+        out
+    }
+
+    /// `map` must be the best child map of every
+    /// nonterminal state reachable from `root`.
+    ///
+    /// Since terminal states have no entries,
+    /// they are counted by collecting the terminal children
+    /// of every entry (and the root, if it is terminal).
+    pub fn from_best_child_map(map: &StateMap<StateAndStats>, root: State) -> Self {
+        let mut out = Self::empty(root);
+        let mut terminal_states = StateSet::empty();
+
+        if root.terminality() != Terminality::Nonterminal {
+            terminal_states.add(root);
+        }
+
+        map.visit_in_key_order(|parent, best_child| {
+            out.record(best_child.parent_outcome());
+
+            parent.visit_children(|child| {
+                if child.terminality() != Terminality::Nonterminal {
+                    terminal_states.add(child);
+                }
+            });
+        });
+
+        terminal_states.visit_in_order(|state| {
+            out.record(
+                map.outcome(state)
+                    .expect("Terminal states always have an outcome."),
+            );
+        });
+
+        out.root_outcome = map.outcome(root);
+
+        out
+    }
+
+    pub fn with_phase_timings(mut self, phase_timings: Vec<(SolverPhase, Duration)>) -> Self {
+        self.phase_timings = phase_timings;
+        self
+    }
+
+    /// Returns the summary as a single-line JSON object.
+    ///
+    /// Outcomes are objects with a `"result"` field
+    /// (`"win"`, `"loss"`, or `"draw"`)
+    /// and, for wins and losses, a `"plies"` field.
+    /// Timings are in seconds.
+    pub fn to_json(&self) -> String {
+        fn json_array(counts: &[u64]) -> String {
+            let counts: Vec<String> = counts.iter().map(u64::to_string).collect();
+            format!("[{}]", counts.join(","))
+        }
+
+        let root_outcome = match self.root_outcome {
+            Some(outcome) => outcome_json(outcome),
+            None => "null".to_string(),
+        };
+
+        let phase_timings: Vec<String> = self
+            .phase_timings
+            .iter()
+            .map(|(phase, duration)| format!(r#""{}":{:.3}"#, phase.name(), duration.as_secs_f64()))
+            .collect();
+
+        format!(
+            r#"{{"root":"{:#x}","reachable_state_count":{},"win_count":{},"loss_count":{},"draw_count":{},"root_outcome":{},"win_in":{},"loss_in":{},"phase_timings":{{{}}}}}"#,
+            self.root.0,
+            self.reachable_state_count,
+            self.win_count,
+            self.loss_count,
+            self.draw_count,
+            root_outcome,
+            json_array(&self.win_in),
+            json_array(&self.loss_in),
+            phase_timings.join(","),
+        )
+    }
+
+    fn record(&mut self, outcome: Outcome) {
+        self.reachable_state_count += 1;
+
+        match outcome.0 {
+            0 => self.draw_count += 1,
+
+            1.. => {
+                self.win_count += 1;
+                self.win_in[(Outcome::win_in(0).0 - outcome.0) as usize] += 1;
+            }
+
+            _ => {
+                self.loss_count += 1;
+                self.loss_in[(outcome.0 - Outcome::loss_in(0).0) as usize] += 1;
+            }
+        }
+    }
+}
+
+fn outcome_json(outcome: Outcome) -> String {
+    match outcome.0 {
+        0 => r#"{"result":"draw"}"#.to_string(),
+        1.. => format!(
+            r#"{{"result":"win","plies":{}}}"#,
+            Outcome::win_in(0).0 - outcome.0
+        ),
+        _ => format!(
+            r#"{{"result":"loss","plies":{}}}"#,
+            outcome.0 - Outcome::loss_in(0).0
+        ),
+    }
+}
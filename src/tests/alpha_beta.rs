@@ -0,0 +1,157 @@
+use super::*;
+
+use std::time::Duration;
+
+#[test]
+fn alpha_beta_agrees_with_small_root_solve() {
+    let mut engine = AlphaBetaEngine::new(1 << 16).max_depth(24);
+    let mut proven_count = 0;
+
+    for root in SMALL_ROOTS {
+        let stats_map = solve(root);
+        stats_map.visit_in_key_order(|state, stats| {
+            if state.is_terminal() {
+                return;
+            }
+
+            let outcome = stats.best_outcome().unwrap_or(Outcome::DRAW);
+            engine.clear_table();
+            let result = engine.search(state);
+
+            if outcome == Outcome::DRAW {
+                assert_eq!(None, result.proven_outcome());
+                return;
+            }
+
+            assert_eq!(Some(outcome), result.proven_outcome());
+
+            // The best move must keep the outcome.
+            let best_move = result.best_move.unwrap();
+            let move_outcome = match best_move.terminality() {
+                Terminality::Loss => Outcome::win_in(1),
+                Terminality::Win => Outcome::loss_in(1),
+                Terminality::Nonterminal => stats_map
+                    .get(best_move)
+                    .best_outcome()
+                    .unwrap_or(Outcome::DRAW)
+                    .invert()
+                    .delay_by_one(),
+            };
+            assert_eq!(outcome, move_outcome);
+            proven_count += 1;
+        });
+    }
+
+    assert!(proven_count > 0);
+}
+
+#[test]
+fn immediate_wins_are_found_at_depth_one() {
+    let mut engine = AlphaBetaEngine::new(1 << 12).max_depth(1);
+    let mut win_count = 0;
+
+    fuzz(100, |state| {
+        if state.is_terminal()
+            || !state
+                .children()
+                .iter()
+                .any(|child| child.terminality() == Terminality::Loss)
+        {
+            return;
+        }
+
+        let result = engine.search(state);
+        assert_eq!(Some(Outcome::win_in(1)), result.proven_outcome());
+        assert_eq!(Terminality::Loss, result.best_move.unwrap().terminality());
+        win_count += 1;
+    });
+
+    assert!(win_count > 0);
+}
+
+#[test]
+fn proven_outcome_rejects_unencodable_delays() {
+    let result = |score| AlphaBetaResult {
+        best_move: None,
+        score,
+        depth: u8::MAX,
+        node_count: 0,
+    };
+    let max_delay = OUTCOME_DELAY_COUNT as i32 - 1;
+
+    assert_eq!(
+        Some(Outcome::win_in(max_delay as u8)),
+        result(WIN_SCORE - max_delay).proven_outcome()
+    );
+    assert_eq!(
+        Some(Outcome::loss_in(max_delay as u8)),
+        result(-(WIN_SCORE - max_delay)).proven_outcome()
+    );
+    assert_eq!(None, result(WIN_SCORE - max_delay - 1).proven_outcome());
+    assert_eq!(None, result(-(WIN_SCORE - 255)).proven_outcome());
+    assert_eq!(None, result(0).proven_outcome());
+}
+
+#[test]
+fn terminal_roots_have_no_best_move() {
+    let mut engine = AlphaBetaEngine::new(1 << 12).max_depth(2);
+    fuzz(100, |state| {
+        let result = engine.search(state);
+        match state.terminality() {
+            Terminality::Win => assert_eq!(Some(Outcome::win_in(0)), result.proven_outcome()),
+            Terminality::Loss => assert_eq!(Some(Outcome::loss_in(0)), result.proven_outcome()),
+            Terminality::Nonterminal => {
+                assert!(state.children().contains(&result.best_move.unwrap()))
+            }
+        }
+
+        if state.is_terminal() {
+            assert_eq!(None, result.best_move);
+            assert_eq!(0, result.depth);
+        }
+    });
+}
+
+#[test]
+fn node_limit_is_respected() {
+    let mut engine = AlphaBetaEngine::new(1 << 12)
+        .max_depth(u8::MAX)
+        .node_limit(10_000);
+    let result = engine.search(State::initial());
+
+    assert!(result.node_count <= 10_001);
+    assert!(result.depth < u8::MAX);
+    assert!(State::initial()
+        .children()
+        .contains(&result.best_move.unwrap()));
+}
+
+#[test]
+fn time_limit_is_respected() {
+    let mut engine = AlphaBetaEngine::new(1 << 12)
+        .max_depth(u8::MAX)
+        .time_limit(Duration::from_millis(50));
+    let result = engine.search(State::initial());
+
+    assert!(result.depth < u8::MAX);
+    assert!(State::initial()
+        .children()
+        .contains(&result.best_move.unwrap()));
+}
+
+#[test]
+fn evaluator_scores_leaves() {
+    let mut engine = AlphaBetaEngine::new(1 << 12)
+        .max_depth(1)
+        .evaluator(|state| state.0 as i32 & 0xff);
+    let result = engine.search(State::initial());
+
+    let best_score = State::initial()
+        .children()
+        .iter()
+        .map(|child| -(child.0 as i32 & 0xff))
+        .max()
+        .unwrap();
+    assert_eq!(best_score, result.score);
+    assert_eq!(1, result.depth);
+}
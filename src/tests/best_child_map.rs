@@ -0,0 +1,114 @@
+use super::*;
+
+const ALL_TIE_BREAKS: [TieBreak; 3] = [
+    TieBreak::PreferCaptures,
+    TieBreak::FewestNonLosingReplies,
+    TieBreak::MostLosingRepliesInDraws,
+];
+
+fn child_outcome(stats_map: &StateMap<StateStats>, child: State) -> Outcome {
+    stats_map.get(child).best_outcome().unwrap_or(Outcome::DRAW)
+}
+
+#[test]
+fn initial_state_has_one_capture() {
+    let initial = State::initial();
+    let captures = initial
+        .children()
+        .into_iter()
+        .filter(|&child| initial.is_capture(child))
+        .count();
+
+    // Only the chicks face each other.
+    assert_eq!(1, captures);
+}
+
+#[test]
+fn tie_breaks_do_not_change_outcomes() {
+    for root in SMALL_ROOTS {
+        let stats_map = solve(root);
+        let expected = best_child_map(&stats_map, &mut SilentObserver);
+
+        for tie_break in ALL_TIE_BREAKS {
+            let actual =
+                best_child_map_with_tie_breaks(&stats_map, vec![tie_break], &mut SilentObserver);
+
+            assert_eq!(expected.len(), actual.len());
+            expected.visit_in_key_order(|parent, best_child| {
+                assert_eq!(
+                    best_child.parent_outcome(),
+                    actual.get(parent).parent_outcome()
+                );
+            });
+            assert!(verify_solution(&actual, root, &mut SilentObserver).is_valid());
+        }
+    }
+}
+
+#[test]
+fn capture_is_preferred_among_tied_children() {
+    for root in SMALL_ROOTS {
+        let stats_map = solve(root);
+        let solution = best_child_map_with_tie_breaks(
+            &stats_map,
+            vec![TieBreak::PreferCaptures],
+            &mut SilentObserver,
+        );
+
+        solution.visit_in_key_order(|parent, best_child| {
+            if parent.is_capture(best_child.state()) {
+                return;
+            }
+
+            let best_outcome = child_outcome(&stats_map, best_child.state());
+            for child in parent.children() {
+                if child_outcome(&stats_map, child) == best_outcome {
+                    assert!(!parent.is_capture(child));
+                }
+            }
+        });
+    }
+}
+
+#[test]
+fn parallel_tie_breaks_agree_with_sequential() {
+    for root in SMALL_ROOTS {
+        let stats_map = solve(root);
+        let sequential = best_child_map_with_tie_breaks(
+            &stats_map,
+            ALL_TIE_BREAKS.to_vec(),
+            &mut SilentObserver,
+        );
+        let parallel = resume_best_child_map_parallel(
+            &stats_map,
+            BestChildrenSnapshot::with_tie_breaks(ALL_TIE_BREAKS.to_vec()),
+            3,
+            &mut SilentObserver,
+            &CancellationToken::new(),
+        )
+        .unwrap();
+
+        assert_eq!(sequential.to_sorted_vec(), parallel.to_sorted_vec());
+    }
+}
+
+#[test]
+fn solver_applies_tie_breaks() {
+    for root in SMALL_ROOTS {
+        let expected = best_child_map_with_tie_breaks(
+            &solve(root),
+            ALL_TIE_BREAKS.to_vec(),
+            &mut SilentObserver,
+        );
+        let solution = Solver::new()
+            .root(root)
+            .tie_breaks(ALL_TIE_BREAKS.to_vec())
+            .solve()
+            .unwrap();
+
+        assert_eq!(
+            expected.to_sorted_vec(),
+            solution.best_child_map().to_sorted_vec()
+        );
+    }
+}
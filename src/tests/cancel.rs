@@ -0,0 +1,316 @@
+use super::*;
+
+use std::time::Duration;
+
+/// Cancels the token after `step` progress reports.
+struct CancelAfter {
+    token: CancellationToken,
+    step: u64,
+    reports: u64,
+}
+
+impl CancelAfter {
+    fn new(step: u64) -> Self {
+        Self {
+            token: CancellationToken::new(),
+            step,
+            reports: 0,
+        }
+    }
+}
+
+impl ProgressObserver for CancelAfter {
+    fn report_interval(&self) -> u64 {
+        1
+    }
+
+    fn progress(&mut self, _report: &ProgressReport) {
+        self.reports += 1;
+        if self.reports == self.step {
+            self.token.cancel();
+        }
+    }
+}
+
+/// Runs a phase from `snapshot` to completion,
+/// cancelling and resuming it every `step` states.
+/// Returns the result and the number of times the phase was cancelled.
+fn run_with_cancellations<S, T>(
+    mut snapshot: S,
+    step: u64,
+    mut run: impl FnMut(S, &mut CancelAfter) -> Result<T, Cancelled<S>>,
+) -> (T, u64) {
+    let mut cancellation_count = 0;
+    loop {
+        let mut observer = CancelAfter::new(step);
+        match run(snapshot, &mut observer) {
+            Ok(out) => return (out, cancellation_count),
+            Err(cancelled) => {
+                snapshot = cancelled.snapshot;
+                cancellation_count += 1;
+            }
+        }
+    }
+}
+
+fn cancelled_token() -> CancellationToken {
+    let token = CancellationToken::new();
+    token.cancel();
+    token
+}
+
+#[test]
+fn token_state_transitions_are_correct() {
+    let token = CancellationToken::new();
+    let clone = token.clone();
+    assert!(!token.is_paused() && !token.is_cancelled());
+
+    clone.pause();
+    assert!(token.is_paused());
+
+    token.resume();
+    assert!(!clone.is_paused());
+
+    clone.cancel();
+    assert!(token.is_cancelled());
+
+    // Cancellation cannot be undone.
+    token.pause();
+    token.resume();
+    assert!(token.is_cancelled() && !token.is_paused());
+}
+
+#[test]
+fn cancelled_token_stops_every_phase_immediately() {
+    let token = cancelled_token();
+    let root = SMALL_ROOTS[0];
+
+    let cancelled = resume_reachable_states(
+        ReachableStatesSnapshot::new(root),
+        &mut SilentObserver,
+        &token,
+    )
+    .unwrap_err();
+    assert_eq!(1, cancelled.snapshot.reachable().len());
+    assert_eq!(1, cancelled.snapshot.queue_len());
+
+    let reachable = reachable_states(root, &mut SilentObserver);
+    assert!(resume_initial_stat_map(
+        &reachable,
+        InitialStatsSnapshot::new(),
+        &mut SilentObserver,
+        &token
+    )
+    .is_err());
+
+    let mut map = initial_stat_map(&reachable, &mut SilentObserver);
+    let original = map.to_sorted_vec();
+    let snapshot = RetrogradeSnapshot::new(&map);
+    let queue_len = snapshot.queue_len();
+    let cancelled =
+        resume_compute_stats(&mut map, snapshot, &mut SilentObserver, &token).unwrap_err();
+    assert_eq!(queue_len, cancelled.snapshot.queue_len());
+    assert_eq!(original, map.to_sorted_vec());
+
+    compute_stats(&mut map, &mut SilentObserver);
+    assert!(resume_best_child_map(
+        &map,
+        BestChildrenSnapshot::new(),
+        &mut SilentObserver,
+        &token
+    )
+    .is_err());
+    assert!(resume_best_child_map_parallel(
+        &map,
+        BestChildrenSnapshot::new(),
+        3,
+        &mut SilentObserver,
+        &token
+    )
+    .is_err());
+}
+
+#[test]
+fn resumed_reachable_states_are_correct() {
+    for root in SMALL_ROOTS {
+        let expected = reachable_states(root, &mut SilentObserver);
+        let (reachable, cancellation_count) = run_with_cancellations(
+            ReachableStatesSnapshot::new(root),
+            3,
+            |snapshot, observer| {
+                let token = observer.token.clone();
+                resume_reachable_states(snapshot, observer, &token)
+            },
+        );
+
+        assert_eq!(expected.to_sorted_vec(), reachable.to_sorted_vec());
+        assert_eq!(expected.len(), reachable.len());
+        assert!(cancellation_count > 0);
+    }
+}
+
+#[test]
+fn resumed_initial_stat_map_is_correct() {
+    for root in SMALL_ROOTS {
+        let reachable = reachable_states(root, &mut SilentObserver);
+        let expected = initial_stat_map(&reachable, &mut SilentObserver);
+        let (map, _) =
+            run_with_cancellations(InitialStatsSnapshot::new(), 1, |snapshot, observer| {
+                let token = observer.token.clone();
+                resume_initial_stat_map(&reachable, snapshot, observer, &token)
+            });
+
+        assert_eq!(expected.to_sorted_vec(), map.to_sorted_vec());
+        assert_eq!(expected.len(), map.len());
+    }
+}
+
+#[test]
+fn resumed_compute_stats_is_correct() {
+    for root in SMALL_ROOTS {
+        let expected = solve(root);
+
+        let reachable = reachable_states(root, &mut SilentObserver);
+        let mut map = initial_stat_map(&reachable, &mut SilentObserver);
+        let snapshot = RetrogradeSnapshot::new(&map);
+        let ((), cancellation_count) = run_with_cancellations(snapshot, 5, |snapshot, observer| {
+            let token = observer.token.clone();
+            resume_compute_stats(&mut map, snapshot, observer, &token)
+        });
+
+        assert_eq!(expected.to_sorted_vec(), map.to_sorted_vec());
+        assert!(cancellation_count > 0);
+    }
+}
+
+#[test]
+fn resumed_best_child_map_is_correct() {
+    for root in SMALL_ROOTS {
+        let map = solve(root);
+        let expected = best_child_map(&map, &mut SilentObserver);
+
+        let (sequential, _) =
+            run_with_cancellations(BestChildrenSnapshot::new(), 1, |snapshot, observer| {
+                let token = observer.token.clone();
+                resume_best_child_map(&map, snapshot, observer, &token)
+            });
+        assert_eq!(expected.to_sorted_vec(), sequential.to_sorted_vec());
+
+        let (parallel, _) =
+            run_with_cancellations(BestChildrenSnapshot::new(), 1, |snapshot, observer| {
+                let token = observer.token.clone();
+                resume_best_child_map_parallel(&map, snapshot, 3, observer, &token)
+            });
+        assert_eq!(expected.to_sorted_vec(), parallel.to_sorted_vec());
+        assert_eq!(expected.len(), parallel.len());
+    }
+}
+
+#[test]
+fn parallel_snapshot_can_be_resumed_sequentially() {
+    let map = solve(SMALL_ROOTS[0]);
+    let expected = best_child_map(&map, &mut SilentObserver);
+
+    let cancelled = resume_best_child_map_parallel(
+        &map,
+        BestChildrenSnapshot::new(),
+        3,
+        &mut SilentObserver,
+        &cancelled_token(),
+    )
+    .unwrap_err();
+    let resumed = resume_best_child_map(
+        &map,
+        cancelled.snapshot,
+        &mut SilentObserver,
+        &CancellationToken::new(),
+    )
+    .unwrap();
+
+    assert_eq!(expected.to_sorted_vec(), resumed.to_sorted_vec());
+}
+
+#[test]
+fn paused_phase_waits_until_resumed() {
+    let token = CancellationToken::new();
+    token.pause();
+
+    let handle = std::thread::spawn({
+        let token = token.clone();
+        move || {
+            resume_reachable_states(
+                ReachableStatesSnapshot::new(SMALL_ROOTS[0]),
+                &mut SilentObserver,
+                &token,
+            )
+            .map(|reachable| reachable.len())
+        }
+    });
+
+    std::thread::sleep(Duration::from_millis(100));
+    assert!(!handle.is_finished());
+
+    token.resume();
+    let expected = reachable_states(SMALL_ROOTS[0], &mut SilentObserver).len();
+    assert_eq!(expected, handle.join().unwrap().unwrap());
+}
+
+#[test]
+fn cancelling_paused_phase_wakes_it() {
+    let token = CancellationToken::new();
+    token.pause();
+
+    let handle = std::thread::spawn({
+        let token = token.clone();
+        move || {
+            resume_reachable_states(
+                ReachableStatesSnapshot::new(SMALL_ROOTS[0]),
+                &mut SilentObserver,
+                &token,
+            )
+            .is_err()
+        }
+    });
+
+    token.cancel();
+    assert!(handle.join().unwrap());
+}
+
+#[test]
+fn solver_resumes_from_every_phase() {
+    for root in SMALL_ROOTS {
+        let expected = solution(root);
+
+        let mut phases = vec![];
+        let mut snapshot: Option<SolverSnapshot> = None;
+        let solution = loop {
+            let observer = CancelAfter::new(2);
+            let mut solver = Solver::new()
+                .root(root)
+                .thread_count(2)
+                .cancellation_token(observer.token.clone())
+                .progress(observer);
+            if let Some(snapshot) = snapshot.take() {
+                solver = solver.resume_from(snapshot);
+            }
+
+            match solver.solve() {
+                Ok(solution) => break solution,
+                Err(SolverError::Cancelled(cancelled)) => {
+                    assert_eq!(root, cancelled.root());
+                    phases.push(cancelled.phase());
+                    snapshot = Some(*cancelled);
+                }
+                Err(error) => panic!("Unexpected error: {error}"),
+            }
+        };
+
+        assert_eq!(root, solution.root());
+        assert_eq!(
+            expected.to_sorted_vec(),
+            solution.best_child_map().to_sorted_vec()
+        );
+        assert!(phases.contains(&SolverPhase::ReachableStates));
+        assert!(phases.contains(&SolverPhase::Retrograde));
+    }
+}
@@ -0,0 +1,98 @@
+use super::*;
+
+use crate::checkpoint::*;
+
+fn reachable_checkpoint_bytes(root: State) -> (StateSet, Vec<u8>) {
+    let reachable = reachable_states(root, &mut SilentObserver);
+    let mut bytes = vec![];
+    write_reachable_states_checkpoint(&reachable, root, Ruleset::SimplifiedTry, &mut bytes)
+        .unwrap();
+    (reachable, bytes)
+}
+
+#[test]
+fn reachable_states_checkpoint_round_trips() {
+    for root in SMALL_ROOTS {
+        let (reachable, bytes) = reachable_checkpoint_bytes(root);
+        let read = read_reachable_states_checkpoint(bytes.as_slice(), root, Ruleset::SimplifiedTry)
+            .unwrap();
+
+        assert_eq!(reachable.to_sorted_vec(), read.to_sorted_vec());
+    }
+}
+
+#[test]
+fn state_stats_checkpoint_round_trips() {
+    for root in SMALL_ROOTS {
+        let stats_map = solve(root);
+        let mut bytes = vec![];
+        write_state_stats_checkpoint(&stats_map, root, Ruleset::SimplifiedTry, &mut bytes).unwrap();
+        let read =
+            read_state_stats_checkpoint(bytes.as_slice(), root, Ruleset::SimplifiedTry).unwrap();
+
+        assert_eq!(stats_map.to_sorted_vec(), read.to_sorted_vec());
+    }
+}
+
+#[test]
+fn checkpoint_rejects_wrong_kind() {
+    let (_, bytes) = reachable_checkpoint_bytes(SMALL_ROOTS[0]);
+
+    let error =
+        read_state_stats_checkpoint(bytes.as_slice(), SMALL_ROOTS[0], Ruleset::SimplifiedTry)
+            .unwrap_err();
+    assert!(matches!(
+        error,
+        CheckpointError::KindMismatch {
+            expected: CheckpointKind::StateStats,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn checkpoint_rejects_wrong_root() {
+    let (_, bytes) = reachable_checkpoint_bytes(SMALL_ROOTS[0]);
+
+    let error =
+        read_reachable_states_checkpoint(bytes.as_slice(), SMALL_ROOTS[1], Ruleset::SimplifiedTry)
+            .unwrap_err();
+    assert!(matches!(error, CheckpointError::RootMismatch { .. }));
+}
+
+#[test]
+fn checkpoint_rejects_partial_write() {
+    let (_, mut bytes) = reachable_checkpoint_bytes(SMALL_ROOTS[0]);
+    bytes.truncate(bytes.len() - 5);
+
+    let error =
+        read_reachable_states_checkpoint(bytes.as_slice(), SMALL_ROOTS[0], Ruleset::SimplifiedTry)
+            .unwrap_err();
+    assert!(matches!(error, CheckpointError::EntryCountMismatch { .. }));
+
+    bytes.pop();
+    let error =
+        read_reachable_states_checkpoint(bytes.as_slice(), SMALL_ROOTS[0], Ruleset::SimplifiedTry)
+            .unwrap_err();
+    assert!(matches!(error, CheckpointError::Truncated { .. }));
+}
+
+#[test]
+fn checkpoint_rejects_corrupted_body() {
+    let stats_map = solve(SMALL_ROOTS[0]);
+    let mut bytes = vec![];
+    write_state_stats_checkpoint(
+        &stats_map,
+        SMALL_ROOTS[0],
+        Ruleset::SimplifiedTry,
+        &mut bytes,
+    )
+    .unwrap();
+    // Flip a bit in the last record's stats.
+    *bytes.last_mut().unwrap() ^= 1;
+
+    let error =
+        read_state_stats_checkpoint(bytes.as_slice(), SMALL_ROOTS[0], Ruleset::SimplifiedTry)
+            .unwrap_err();
+    assert!(matches!(error, CheckpointError::ChecksumMismatch { .. }));
+}
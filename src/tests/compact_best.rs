@@ -0,0 +1,104 @@
+use super::*;
+
+#[test]
+fn compact_best_child_map_agrees_with_best_child_map() {
+    for root in SMALL_ROOTS {
+        let best_child_map = solution(root);
+        let compact = compact_best_child_map(&best_child_map, &mut SilentObserver);
+
+        let mut compact_len = 0;
+        compact.visit_in_key_order(|_, _| compact_len += 1);
+        let mut best_child_map_len = 0;
+        best_child_map.visit_in_key_order(|parent, best_child| {
+            best_child_map_len += 1;
+
+            let best = compact.get(parent);
+            assert_eq!(Some(best_child.state()), best.best_child(parent));
+            assert_eq!(best_child.parent_outcome(), best.outcome());
+
+            let converted = best.best_child_with_stats(parent).unwrap();
+            assert_eq!(best_child.state(), converted.state());
+            assert_eq!(best.outcome(), converted.parent_outcome());
+        });
+
+        assert_eq!(best_child_map_len, compact_len);
+    }
+}
+
+#[test]
+fn undelay_by_one_inverts_delay_by_one() {
+    // Delaying a win (or loss) in 200 plies produces a draw,
+    // so we exclude those outcomes.
+    for outcome in (-201..=201).filter(|n: &i16| n.abs() != 1).map(Outcome) {
+        assert_eq!(outcome, outcome.delay_by_one().undelay_by_one());
+    }
+}
+
+#[test]
+fn compact_solution_round_trips() {
+    for root in SMALL_ROOTS {
+        let compact = compact_best_child_map(&solution(root), &mut SilentObserver);
+
+        let mut bytes = vec![];
+        write_compact_solution(&compact, &mut bytes).unwrap();
+        let read = read_compact_solution(bytes.as_slice()).unwrap();
+
+        assert_eq!(compact.to_sorted_vec(), read.to_sorted_vec());
+        assert_eq!(
+            compact.to_sorted_vec().len() * CompactBest::RECORD_LEN,
+            bytes.len()
+        );
+    }
+}
+
+#[test]
+fn compact_solution_rejects_incomplete_record() {
+    let compact = compact_best_child_map(&solution(SMALL_ROOTS[0]), &mut SilentObserver);
+
+    let mut bytes = vec![];
+    write_compact_solution(&compact, &mut bytes).unwrap();
+    bytes.pop();
+
+    assert!(read_compact_solution(bytes.as_slice()).is_err());
+}
+
+/// Reads one byte at a time,
+/// and is interrupted before every byte.
+struct InterruptingReader<'a> {
+    bytes: &'a [u8],
+    is_interrupted: bool,
+}
+
+impl std::io::Read for InterruptingReader<'_> {
+    fn read(&mut self, buffer: &mut [u8]) -> std::io::Result<usize> {
+        self.is_interrupted = !self.is_interrupted;
+        if self.is_interrupted {
+            return Err(std::io::ErrorKind::Interrupted.into());
+        }
+
+        let Some((&first, rest)) = self.bytes.split_first() else {
+            return Ok(0);
+        };
+        if buffer.is_empty() {
+            return Ok(0);
+        }
+        buffer[0] = first;
+        self.bytes = rest;
+        Ok(1)
+    }
+}
+
+#[test]
+fn compact_solution_retries_interrupted_reads() {
+    let compact = compact_best_child_map(&solution(SMALL_ROOTS[2]), &mut SilentObserver);
+
+    let mut bytes = vec![];
+    write_compact_solution(&compact, &mut bytes).unwrap();
+    let read = read_compact_solution(InterruptingReader {
+        bytes: &bytes,
+        is_interrupted: false,
+    })
+    .unwrap();
+
+    assert_eq!(compact.to_sorted_vec(), read.to_sorted_vec());
+}
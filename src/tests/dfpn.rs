@@ -0,0 +1,161 @@
+use super::*;
+
+fn expected_result(outcome: Outcome) -> Option<bool> {
+    match outcome.wdl() {
+        Wdl::Win => Some(true),
+        Wdl::Loss => Some(false),
+        Wdl::Draw => None,
+    }
+}
+
+#[test]
+fn dfpn_agrees_with_small_root_solve() {
+    let mut search = DfpnSearch::new(1 << 16);
+    let mut proven_count = 0;
+
+    for root in SMALL_ROOTS {
+        let stats_map = solve(root);
+        stats_map.visit_in_key_order(|state, stats| {
+            if state.is_terminal() {
+                return;
+            }
+
+            let outcome = stats.best_outcome().unwrap_or(Outcome::DRAW);
+            match (expected_result(outcome), search.search(state)) {
+                (Some(true), DfpnResult::Win { winning_move }) => {
+                    let winning_move = winning_move.unwrap();
+                    let move_outcome = stats_map
+                        .get(winning_move)
+                        .best_outcome()
+                        .map(|outcome| outcome.invert().delay_by_one())
+                        .unwrap_or_else(|| match winning_move.terminality() {
+                            Terminality::Loss => Outcome::win_in(1),
+                            _ => Outcome::DRAW,
+                        });
+                    assert_eq!(Wdl::Win, move_outcome.wdl());
+                    proven_count += 1;
+                }
+
+                (Some(false), DfpnResult::Loss) => proven_count += 1,
+
+                (None, DfpnResult::Unproven) => {}
+
+                (_, result) => {
+                    use crate::pretty::*;
+                    panic!(
+                        "df-pn result does not match the solve.\n\nOUTCOME: {}\n\nRESULT: {result:?}\n\nSTATE:\n\n{}",
+                        outcome.pretty(),
+                        state.pretty()
+                    );
+                }
+            }
+        });
+    }
+
+    assert!(proven_count > 0);
+}
+
+#[test]
+fn terminal_states_are_resolved_without_search() {
+    let mut search = DfpnSearch::new(1);
+    fuzz(100, |state| match state.terminality() {
+        Terminality::Win => {
+            assert_eq!(DfpnResult::Win { winning_move: None }, search.search(state));
+            assert_eq!(0, search.node_count());
+        }
+
+        Terminality::Loss => {
+            assert_eq!(DfpnResult::Loss, search.search(state));
+            assert_eq!(0, search.node_count());
+        }
+
+        Terminality::Nonterminal => {}
+    });
+}
+
+#[test]
+fn immediate_wins_are_proven_with_a_winning_move() {
+    let mut search = DfpnSearch::new(1 << 12).node_limit(100_000);
+    let mut win_count = 0;
+
+    fuzz(100, |state| {
+        if state.is_terminal()
+            || !state
+                .children()
+                .iter()
+                .any(|child| child.terminality() == Terminality::Loss)
+        {
+            return;
+        }
+
+        let DfpnResult::Win {
+            winning_move: Some(winning_move),
+        } = search.search(state)
+        else {
+            panic!("Immediate win was not proven.");
+        };
+
+        // The winning move either wins at once,
+        // or leads to a position that the passive player provably loses.
+        if winning_move.terminality() != Terminality::Loss {
+            assert_eq!(DfpnResult::Loss, search.search(winning_move));
+        }
+        win_count += 1;
+    });
+
+    assert!(win_count > 0);
+}
+
+#[test]
+fn tiny_table_gives_same_results() {
+    let mut large = DfpnSearch::new(1 << 16);
+    let mut tiny = DfpnSearch::new(1);
+
+    for root in SMALL_ROOTS {
+        let is_win = |result| matches!(result, DfpnResult::Win { .. });
+        let large_result = large.search(root);
+        let tiny_result = tiny.search(root);
+        assert_eq!(is_win(large_result), is_win(tiny_result));
+        assert_eq!(
+            large_result == DfpnResult::Loss,
+            tiny_result == DfpnResult::Loss
+        );
+    }
+}
+
+#[test]
+fn node_limit_is_respected() {
+    let mut search = DfpnSearch::new(1 << 12).node_limit(1_000);
+    assert_eq!(DfpnResult::Unproven, search.search(State::initial()));
+    assert!(search.node_count() <= 1_000);
+}
+
+#[test]
+fn depth_one_only_proves_immediate_wins() {
+    let mut search = DfpnSearch::new(1 << 12).max_depth(1);
+
+    fuzz(100, |state| {
+        if state.is_terminal() {
+            return;
+        }
+
+        let children = state.children();
+        let immediate_win = children
+            .iter()
+            .copied()
+            .find(|child| child.terminality() == Terminality::Loss);
+        let is_immediate_loss = children
+            .iter()
+            .all(|child| child.terminality() == Terminality::Win);
+        let expected = if immediate_win.is_some() {
+            DfpnResult::Win {
+                winning_move: immediate_win,
+            }
+        } else if is_immediate_loss {
+            DfpnResult::Loss
+        } else {
+            DfpnResult::Unproven
+        };
+        assert_eq!(expected, search.search(state));
+    });
+}
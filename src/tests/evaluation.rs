@@ -0,0 +1,114 @@
+use super::*;
+
+#[test]
+fn initial_state_is_balanced() {
+    assert_eq!(
+        EvaluationFeatures::default(),
+        EvaluationFeatures::of(State::initial())
+    );
+    assert_eq!(0, evaluate(State::initial()));
+}
+
+#[test]
+fn evaluation_is_antisymmetric() {
+    fuzz(1_000, |state| {
+        if state.is_terminal() {
+            return;
+        }
+
+        let inverted = state.into_builder().invert_active_player().build();
+        if inverted.is_terminal() {
+            return;
+        }
+
+        assert_eq!(evaluate(state), -evaluate(inverted));
+    });
+}
+
+#[test]
+fn features_are_in_range() {
+    fuzz(1_000, |state| {
+        if state.is_terminal() {
+            return;
+        }
+
+        let features = EvaluationFeatures::of(state);
+        let material = [
+            features.chick + features.hen,
+            features.elephant,
+            features.giraffe,
+        ];
+        for count in material {
+            assert!(count.abs() <= 2);
+        }
+        assert!(features.hand_piece.abs() <= 6);
+        assert!(features.lion_advancement.abs() <= 3);
+    });
+}
+
+#[test]
+fn capturing_a_piece_gains_material() {
+    let mut capture_count = 0;
+    fuzz(100, |state| {
+        if state.is_terminal() {
+            return;
+        }
+
+        state.visit_children(|child| {
+            if child.is_terminal() || !state.is_capture(child) {
+                return;
+            }
+
+            let before = EvaluationFeatures::of(state);
+            let after = EvaluationFeatures::of(child);
+            let material = |features: EvaluationFeatures, sign: i32| {
+                sign * (features.chick + features.hen + features.elephant + features.giraffe)
+            };
+
+            // The child is from the opponent's perspective.
+            assert!(material(after, -1) > material(before, 1));
+            capture_count += 1;
+        });
+    });
+
+    assert!(capture_count > 0);
+}
+
+#[test]
+fn accuracy_counts_every_sampled_state() {
+    let solution = combined_solution();
+
+    let mut observer = PlainTextObserver::new(vec![]);
+    let accuracy = measure_evaluation_accuracy(&solution, evaluate, 1, &mut observer);
+
+    let log = String::from_utf8(observer.into_inner()).unwrap();
+    assert!(log.contains(&format!(
+        "Completed evaluation_accuracy phase: processed {} states.",
+        solution.len()
+    )));
+    assert_eq!(solution.len(), accuracy.sampled_state_count);
+    assert!(accuracy.sign_agreement_count <= accuracy.decisive_state_count);
+    assert!(accuracy.ordered_move_pair_count <= accuracy.move_pair_count);
+
+    let every_other = measure_evaluation_accuracy(&solution, evaluate, 2, &mut SilentObserver);
+    assert_eq!(solution.len().div_ceil(2), every_other.sampled_state_count);
+}
+
+#[test]
+fn perfect_evaluator_agrees_with_solution() {
+    let solution = combined_solution();
+
+    let oracle = |state: State| {
+        let best_child = solution.get(state);
+        if best_child.is_null() {
+            0
+        } else {
+            best_child.parent_outcome().0 as i32
+        }
+    };
+    let accuracy = measure_evaluation_accuracy(&solution, oracle, 1, &mut SilentObserver);
+
+    assert!(accuracy.decisive_state_count > 0);
+    assert_eq!(accuracy.decisive_state_count, accuracy.sign_agreement_count);
+    assert_eq!(accuracy.move_pair_count, accuracy.ordered_move_pair_count);
+}
@@ -0,0 +1,137 @@
+use super::*;
+
+/// Returns the number of plies until the game ends.
+fn delay(outcome: Outcome) -> i16 {
+    Outcome::win_in(0).0 - outcome.0.abs()
+}
+
+#[test]
+fn catalogue_lines_end_at_terminal_states() {
+    let solution = combined_solution();
+    let catalogue = extreme_positions(&solution, usize::MAX, &mut SilentObserver);
+
+    for position in catalogue
+        .longest_wins
+        .iter()
+        .chain(&catalogue.longest_losses)
+    {
+        let line = &position.line;
+        assert_eq!(delay(position.outcome) as usize + 1, line.len());
+        assert!(line.last().unwrap().state().is_terminal());
+        for pair in line.windows(2) {
+            assert!(pair[0].state().children().contains(&pair[1].state()));
+        }
+    }
+}
+
+#[test]
+fn catalogue_lists_longest_positions_first() {
+    let solution = combined_solution();
+    let catalogue = extreme_positions(&solution, usize::MAX, &mut SilentObserver);
+
+    let mut expected_wins = vec![];
+    let mut expected_losses = vec![];
+    solution.visit_in_key_order(|state, best_child| {
+        let outcome = best_child.parent_outcome();
+        if outcome.0 > 0 {
+            expected_wins.push((-delay(outcome), state));
+        } else if outcome.0 < 0 {
+            expected_losses.push((-delay(outcome), state));
+        }
+    });
+    expected_wins.sort();
+    expected_losses.sort();
+
+    let states = |positions: &[ExtremePosition]| -> Vec<State> {
+        positions.iter().map(|position| position.state).collect()
+    };
+    assert_eq!(
+        expected_wins
+            .iter()
+            .map(|&(_, state)| state)
+            .collect::<Vec<_>>(),
+        states(&catalogue.longest_wins)
+    );
+    assert_eq!(
+        expected_losses
+            .iter()
+            .map(|&(_, state)| state)
+            .collect::<Vec<_>>(),
+        states(&catalogue.longest_losses)
+    );
+    assert!(!catalogue.longest_wins.is_empty());
+    assert!(!catalogue.longest_losses.is_empty());
+}
+
+#[test]
+fn catalogue_respects_count() {
+    let solution = combined_solution();
+    let full = extreme_positions(&solution, usize::MAX, &mut SilentObserver);
+    let mut observer = PlainTextObserver::new(vec![]);
+    let truncated = extreme_positions(&solution, 1, &mut observer);
+
+    let log = String::from_utf8(observer.into_inner()).unwrap();
+    assert!(log.contains(&format!(
+        "Completed extreme_positions phase: processed {} states.",
+        solution.len()
+    )));
+    assert_eq!(&full.longest_wins[..1], &truncated.longest_wins[..]);
+    assert_eq!(&full.longest_losses[..1], &truncated.longest_losses[..]);
+
+    let empty = extreme_positions(&solution, 0, &mut SilentObserver);
+    assert!(empty.longest_wins.is_empty() && empty.longest_losses.is_empty());
+}
+
+#[test]
+fn catalogue_lines_carry_outcomes() {
+    let solution = combined_solution();
+    let catalogue = extreme_positions(&solution, usize::MAX, &mut SilentObserver);
+
+    for position in catalogue
+        .longest_wins
+        .iter()
+        .chain(&catalogue.longest_losses)
+    {
+        assert_eq!(position.state, position.line[0].state());
+        assert_eq!(
+            principal_variation(position.state, &solution)
+                .into_iter()
+                .map(|(_, state, _)| state)
+                .collect::<Vec<_>>(),
+            position.line[1..]
+                .iter()
+                .map(|state| state.state())
+                .collect::<Vec<_>>()
+        );
+
+        // Each ply brings the end one ply closer,
+        // and the outcome alternates between the players.
+        let total_delay = delay(position.outcome) as u8;
+        for (ply, state) in position.line.iter().enumerate() {
+            let ply = ply as u8;
+            let expected = if (position.outcome.0 > 0) == ply.is_multiple_of(2) {
+                Outcome::win_in(total_delay - ply)
+            } else {
+                Outcome::loss_in(total_delay - ply)
+            };
+            assert_eq!(Some(expected), state.stats().best_outcome());
+        }
+    }
+}
+
+#[test]
+fn extreme_position_display_shows_line() {
+    let solution = combined_solution();
+    let catalogue = extreme_positions(&solution, 1, &mut SilentObserver);
+    let position = &catalogue.longest_wins[0];
+
+    assert_eq!(
+        format!(
+            "Outcome: {}\n\nSTATE:\n\n{}\n\nOPTIMAL LINE:\n\n{}",
+            position.outcome.pretty(),
+            position.state.pretty(),
+            position.line.clone().pretty()
+        ),
+        position.to_string()
+    );
+}
@@ -0,0 +1,134 @@
+use super::*;
+
+fn has_immediate_win(state: State) -> bool {
+    !state.is_terminal()
+        && state
+            .children()
+            .iter()
+            .any(|child| child.terminality() == Terminality::Loss)
+}
+
+#[test]
+fn mcts_agrees_with_small_root_solve() {
+    let mut decisive_count = 0;
+
+    for playout in [Playout::Random, Playout::Heuristic] {
+        let mut engine = MctsEngine::new(0).iterations(2_000).playout(playout);
+
+        for root in SMALL_ROOTS {
+            let stats_map = solve(root);
+            stats_map.visit_in_key_order(|state, stats| {
+                let outcome = stats.best_outcome().unwrap_or(Outcome::DRAW);
+                if state.is_terminal() || outcome.wdl() != Wdl::Win {
+                    return;
+                }
+
+                // The best move must keep the win.
+                let best_move = engine.search(state).best_move.unwrap();
+                let move_wdl = match best_move.terminality() {
+                    Terminality::Loss => Wdl::Win,
+                    Terminality::Win => Wdl::Loss,
+                    Terminality::Nonterminal => stats_map
+                        .get(best_move)
+                        .best_outcome()
+                        .unwrap_or(Outcome::DRAW)
+                        .invert()
+                        .wdl(),
+                };
+                assert_eq!(Wdl::Win, move_wdl);
+                decisive_count += 1;
+            });
+        }
+    }
+
+    assert!(decisive_count > 0);
+}
+
+#[test]
+fn immediate_wins_are_recognized() {
+    let mut engine = MctsEngine::new(1).iterations(1_000);
+    let mut win_count = 0;
+
+    fuzz(100, |state| {
+        if !has_immediate_win(state) {
+            return;
+        }
+
+        // Other moves may win just as surely (e.g., by a forced capture),
+        // so the immediate win need not be the most visited move.
+        let result = engine.search(state);
+        let best_move_stats = result
+            .moves
+            .iter()
+            .find(|stats| Some(stats.child) == result.best_move)
+            .unwrap();
+        assert_eq!(1.0, best_move_stats.mean_value);
+
+        for stats in &result.moves {
+            if stats.child.terminality() == Terminality::Loss {
+                assert_eq!(1.0, stats.mean_value);
+            }
+        }
+        win_count += 1;
+    });
+
+    assert!(win_count > 0);
+}
+
+#[test]
+fn search_is_deterministic_for_a_seed() {
+    for playout in [Playout::Random, Playout::Heuristic] {
+        let search = |seed| {
+            MctsEngine::new(seed)
+                .iterations(500)
+                .playout(playout)
+                .search(State::initial())
+        };
+
+        assert_eq!(search(5), search(5));
+    }
+}
+
+#[test]
+fn visit_counts_add_up_to_the_budget() {
+    let result = MctsEngine::new(2).iterations(777).search(State::initial());
+
+    assert_eq!(777, result.iteration_count);
+    assert_eq!(
+        State::initial().children(),
+        result
+            .moves
+            .iter()
+            .map(|stats| stats.child)
+            .collect::<Vec<_>>()
+    );
+
+    let visit_count: u64 = result.moves.iter().map(|stats| stats.visit_count).sum();
+    assert_eq!(777, visit_count);
+
+    let best_visit_count = result.moves.iter().map(|stats| stats.visit_count).max();
+    let best_move_stats = result
+        .moves
+        .iter()
+        .find(|stats| Some(stats.child) == result.best_move)
+        .unwrap();
+    assert_eq!(best_visit_count, Some(best_move_stats.visit_count));
+
+    for stats in &result.moves {
+        assert!((-1.0..=1.0).contains(&stats.mean_value));
+    }
+}
+
+#[test]
+fn terminal_roots_have_no_best_move() {
+    let mut engine = MctsEngine::new(3).iterations(10);
+    fuzz(100, |state| {
+        if !state.is_terminal() {
+            return;
+        }
+
+        let result = engine.search(state);
+        assert_eq!(None, result.best_move);
+        assert!(result.moves.is_empty());
+    });
+}
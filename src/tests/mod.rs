@@ -5,10 +5,35 @@ use rand_xorshift::XorShiftRng;
 
 use std::collections::HashSet;
 
+mod alpha_beta;
+mod best_child_map;
+mod cancel;
+mod checkpoint;
+mod compact_best;
+mod dfpn;
+mod evaluation;
+mod extremes;
 mod i9;
 mod legal_moves;
+mod mcts;
+mod move_outcomes;
+mod move_quality;
+mod optimal_children;
+mod principal_variation;
+mod progress;
+mod proof_check;
+mod proof_tree;
+mod rank;
+mod solution_file;
+mod solution_index;
+mod solver;
 mod state_map;
 mod state_set;
+mod summary;
+mod training_data;
+mod verify;
+mod wdl;
+mod weight_fitting;
 
 #[test]
 fn initial_state_is_correct() {
@@ -172,6 +197,36 @@ pub fn fuzz<F: FnMut(State)>(game_count: usize, mut callback: F) {
     }
 }
 
+/// Roots of subgames that are small enough to solve in a test.
+const SMALL_ROOTS: [State; 4] = [
+    State(0x2b20785c61),
+    State(0x8b2f7cb820),
+    State(0x2ac0789d89),
+    State(0x69e03dd952),
+];
+
+/// Solves the subgame rooted at `root`.
+fn solve(root: State) -> StateMap<StateStats> {
+    let reachable = reachable_states(root, &mut SilentObserver);
+    let mut map = initial_stat_map(&reachable, &mut SilentObserver);
+    compute_stats(&mut map, &mut SilentObserver);
+    map
+}
+
+/// Returns the best child map of the subgame rooted at `root`.
+fn solution(root: State) -> StateMap<StateAndStats> {
+    best_child_map(&solve(root), &mut SilentObserver)
+}
+
+/// Combines the best child maps of every small root.
+fn combined_solution() -> StateMap<StateAndStats> {
+    let mut combined = StateMap::empty();
+    for root in SMALL_ROOTS {
+        combined.union_in_place(solution(root));
+    }
+    combined
+}
+
 fn deterministic_prng() -> XorShiftRng {
     /// Randomly chosen seed
     const PRNG_SEED: [u8; 16] = [
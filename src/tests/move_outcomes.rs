@@ -0,0 +1,87 @@
+use super::*;
+
+fn table(root: State) -> (StateMap<StateAndStats>, MoveOutcomeTable) {
+    let solution = solution(root);
+    let table = MoveOutcomeTable::from_best_child_map(&solution, &mut SilentObserver);
+    (solution, table)
+}
+
+#[test]
+fn best_move_has_parent_outcome() {
+    for root in SMALL_ROOTS {
+        let (solution, table) = table(root);
+
+        assert_eq!(solution.len(), table.len());
+        solution.visit_in_key_order(|parent, best_child| {
+            let move_outcomes = table.get(parent).unwrap();
+            let children = parent.children();
+            assert_eq!(children.len(), move_outcomes.len());
+
+            let best_index = children
+                .iter()
+                .position(|&child| child == best_child.state())
+                .unwrap();
+            assert_eq!(best_child.parent_outcome(), move_outcomes[best_index]);
+            assert_eq!(
+                Some(&best_child.parent_outcome()),
+                move_outcomes.iter().max()
+            );
+        });
+    }
+}
+
+#[test]
+fn table_reports_every_state() {
+    let solution = solution(SMALL_ROOTS[0]);
+    let mut observer = PlainTextObserver::new(vec![]);
+    MoveOutcomeTable::from_best_child_map(&solution, &mut observer);
+
+    let log = String::from_utf8(observer.into_inner()).unwrap();
+    assert!(log.contains(&format!(
+        "Completed move_outcomes phase: processed {} states.",
+        solution.len()
+    )));
+}
+
+#[test]
+fn move_outcomes_agree_with_optimal_children() {
+    for root in SMALL_ROOTS {
+        let (solution, table) = table(root);
+        let optimal = optimal_children_map(&solution, &mut SilentObserver);
+
+        table.visit_in_key_order(|parent, move_outcomes| {
+            let entry = optimal.get(parent);
+            for (i, &outcome) in move_outcomes.iter().enumerate() {
+                assert_eq!(entry.is_optimal_index(i as u8), outcome == entry.outcome());
+            }
+        });
+    }
+}
+
+#[test]
+fn move_outcome_table_round_trips() {
+    for root in SMALL_ROOTS {
+        let (_, table) = table(root);
+
+        let mut bytes = vec![];
+        table.write_to(&mut bytes).unwrap();
+        let read = MoveOutcomeTable::read_from(bytes.as_slice()).unwrap();
+
+        let mut expected = vec![];
+        table.visit_in_key_order(|state, outcomes| expected.push((state, outcomes.to_vec())));
+        let mut actual = vec![];
+        read.visit_in_key_order(|state, outcomes| actual.push((state, outcomes.to_vec())));
+        assert_eq!(expected, actual);
+    }
+}
+
+#[test]
+fn move_outcome_table_rejects_incomplete_record() {
+    let (_, table) = table(SMALL_ROOTS[2]);
+
+    let mut bytes = vec![];
+    table.write_to(&mut bytes).unwrap();
+    bytes.pop();
+
+    assert!(MoveOutcomeTable::read_from(bytes.as_slice()).is_err());
+}
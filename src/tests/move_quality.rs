@@ -0,0 +1,103 @@
+use super::*;
+
+#[test]
+fn classification_agrees_with_move_outcome_table() {
+    for root in SMALL_ROOTS {
+        let solution = Solver::new().root(root).solve().unwrap();
+        let table =
+            MoveOutcomeTable::from_best_child_map(solution.best_child_map(), &mut SilentObserver);
+
+        table.visit_in_key_order(|state, move_outcomes| {
+            let expected = classify_move_outcomes(state, move_outcomes);
+            assert_eq!(Some(expected), solution.classify_moves(state));
+        });
+    }
+}
+
+#[test]
+fn best_child_is_optimal() {
+    for root in SMALL_ROOTS {
+        let solution = Solver::new().root(root).solve().unwrap();
+
+        solution
+            .best_child_map()
+            .visit_in_key_order(|state, best_child| {
+                let moves = solution.classify_moves(state).unwrap();
+                let best_move = moves
+                    .iter()
+                    .find(|classified| classified.child == best_child.state())
+                    .unwrap();
+
+                assert_eq!(MoveQuality::Optimal, best_move.quality);
+                assert_eq!(0, best_move.delta);
+                assert_eq!(best_child.parent_outcome(), best_move.outcome);
+            });
+    }
+}
+
+#[test]
+fn moves_are_classified_by_result_and_distance() {
+    let state = State::initial();
+    let move_outcomes: Vec<Outcome> = (0..state.children().len())
+        .map(|i| match i % 4 {
+            0 => Outcome::win_in(3),
+            1 => Outcome::win_in(5),
+            2 => Outcome::DRAW,
+            _ => Outcome::loss_in(2),
+        })
+        .collect();
+
+    let moves = classify_move_outcomes(state, &move_outcomes);
+
+    assert_eq!(
+        state.children(),
+        moves.iter().map(|m| m.child).collect::<Vec<_>>()
+    );
+    for (i, classified) in moves.iter().enumerate() {
+        assert_eq!(i, classified.index as usize);
+        assert_eq!(
+            Outcome::win_in(3).0 - classified.outcome.0,
+            classified.delta
+        );
+
+        let expected_quality = match i % 4 {
+            0 => MoveQuality::Optimal,
+            1 => MoveQuality::Inaccuracy,
+            _ => MoveQuality::Blunder,
+        };
+        assert_eq!(expected_quality, classified.quality);
+    }
+}
+
+#[test]
+fn terminal_state_has_no_moves() {
+    let solution = solution(SMALL_ROOTS[0]);
+    let terminal = solution.get(SMALL_ROOTS[0]).state();
+
+    assert!(terminal.is_terminal());
+    assert!(classify_move_outcomes(terminal, &[]).is_empty());
+}
+
+#[test]
+fn solution_index_classification_agrees_with_solution() {
+    for root in SMALL_ROOTS {
+        let solution = Solver::new().root(root).solve().unwrap();
+        let mut bytes = vec![];
+        write_solution_file(
+            solution.best_child_map(),
+            Ruleset::SimplifiedTry,
+            &mut bytes,
+            &mut SilentObserver,
+        )
+        .unwrap();
+        let mut index =
+            SolutionIndex::new(std::io::Cursor::new(bytes), Ruleset::SimplifiedTry).unwrap();
+
+        solution.best_child_map().visit_in_key_order(|state, _| {
+            assert_eq!(
+                solution.classify_moves(state),
+                index.classify_moves(state).unwrap()
+            );
+        });
+    }
+}
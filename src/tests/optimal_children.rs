@@ -0,0 +1,108 @@
+use super::*;
+
+#[test]
+fn optimal_children_are_exactly_the_children_with_the_best_outcome() {
+    for root in SMALL_ROOTS {
+        let solution = solution(root);
+        let optimal = optimal_children_map(&solution, &mut SilentObserver);
+
+        assert_eq!(solution.len(), optimal.len());
+        solution.visit_in_key_order(|parent, best_child| {
+            let entry = optimal.get(parent);
+            assert_eq!(best_child.parent_outcome(), entry.outcome());
+            assert!(entry.is_optimal(parent, best_child.state()));
+
+            let children = parent.children();
+            let optimal_children = entry.children(parent);
+            assert_eq!(entry.len() as usize, optimal_children.len());
+            for child in children {
+                let outcome = solution.outcome(child).unwrap().invert().delay_by_one();
+                if optimal_children.contains(&child) {
+                    assert_eq!(entry.outcome(), outcome);
+                } else {
+                    assert!(outcome < entry.outcome());
+                }
+            }
+        });
+    }
+}
+
+#[test]
+fn ties_are_all_recorded() {
+    // At least one of the small subgames has a position
+    // with several equally good moves.
+    let solution = combined_solution();
+    let optimal = optimal_children_map(&solution, &mut SilentObserver);
+
+    let mut max_len = 0;
+    optimal.visit_in_key_order(|_, entry| max_len = max_len.max(entry.len()));
+    assert!(max_len > 1);
+}
+
+#[test]
+fn optimal_children_round_trip() {
+    for root in SMALL_ROOTS {
+        let optimal = optimal_children_map(&solution(root), &mut SilentObserver);
+
+        let mut bytes = vec![];
+        write_optimal_children(&optimal, &mut bytes).unwrap();
+        let read = read_optimal_children(bytes.as_slice()).unwrap();
+
+        assert_eq!(optimal.to_sorted_vec(), read.to_sorted_vec());
+        assert_eq!(
+            optimal.len() as usize * OptimalChildren::RECORD_LEN,
+            bytes.len()
+        );
+    }
+}
+
+#[test]
+fn optimal_children_reject_incomplete_record() {
+    let optimal = optimal_children_map(&solution(SMALL_ROOTS[0]), &mut SilentObserver);
+
+    let mut bytes = vec![];
+    write_optimal_children(&optimal, &mut bytes).unwrap();
+    bytes.pop();
+
+    assert!(read_optimal_children(bytes.as_slice()).is_err());
+}
+
+#[test]
+fn packet_entry_lists_every_optimal_child() {
+    let solution = solution(SMALL_ROOTS[0]);
+    let optimal = optimal_children_map(&solution, &mut SilentObserver);
+
+    let mut packet = vec![];
+    optimal.visit_in_key_order(|parent, entry| entry.append_packet_entry(parent, &mut packet));
+
+    let mut rest = packet.as_slice();
+    optimal.visit_in_key_order(|parent, entry| {
+        let read_state = |bytes: &[u8]| {
+            let mut state_bytes = [0; 8];
+            state_bytes[0..5].copy_from_slice(&bytes[0..5]);
+            State(u64::from_le_bytes(state_bytes))
+        };
+
+        assert_eq!(parent, read_state(rest));
+        let child_count = rest[5] as usize;
+        let children: Vec<State> = rest[6..6 + 5 * child_count]
+            .chunks(5)
+            .map(read_state)
+            .collect();
+        assert_eq!(entry.children(parent), children);
+
+        rest = &rest[6 + 5 * child_count..];
+    });
+    assert!(rest.is_empty());
+}
+
+#[test]
+fn null_is_not_a_valid_entry() {
+    let full_mask = (1 << CHILD_MASK_BITS) - 1;
+    for outcome in [Outcome::loss_in(0), Outcome::DRAW, Outcome::win_in(0)] {
+        let entry = OptimalChildren::new(outcome, full_mask);
+        assert!(!entry.is_null());
+        assert_eq!(outcome, entry.outcome());
+        assert_eq!(full_mask, entry.child_mask());
+    }
+}
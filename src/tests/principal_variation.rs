@@ -0,0 +1,109 @@
+use super::*;
+
+#[test]
+fn actions_lead_to_children_in_visiting_order() {
+    fuzz(1_000, |state| {
+        let mut children = vec![];
+        state.visit_actions(|action, child| {
+            assert_eq!(Some(action), state.action_to(child));
+            assert!(action.0 < 1 << 7);
+            children.push(child);
+        });
+
+        assert_eq!(state.children(), children);
+    });
+}
+
+#[test]
+fn initial_state_actions_are_correct() {
+    let actions: Vec<String> = {
+        let mut actions = vec![];
+        State::initial().visit_actions(|action, _| actions.push(action.pretty().to_string()));
+        actions
+    };
+
+    insta::assert_snapshot!(actions.join("\n"));
+}
+
+#[test]
+fn principal_variation_follows_optimal_line() {
+    for root in SMALL_ROOTS {
+        let solution = solution(root);
+        let line = principal_variation(root, &solution);
+        let outcome = solution.get(root).parent_outcome();
+        let delay = Outcome::win_in(0).0 - outcome.0.abs();
+        assert_eq!(delay as usize, line.len());
+
+        let mut parent = root;
+        for &(action, state, outcome) in &line {
+            assert_eq!(Some(action), parent.action_to(state));
+            let expected_outcome = if state.is_terminal() {
+                state.guess_stats().best_outcome().unwrap()
+            } else {
+                solution.get(state).parent_outcome()
+            };
+            assert_eq!(expected_outcome, outcome);
+            parent = state;
+        }
+        assert!(parent.is_terminal());
+    }
+}
+
+#[test]
+fn principal_variation_of_terminal_state_is_empty() {
+    let solution = solution(SMALL_ROOTS[0]);
+    let terminal = solution.get(SMALL_ROOTS[0]).state();
+
+    assert!(terminal.is_terminal());
+    assert!(principal_variation(terminal, &solution).is_empty());
+}
+
+#[test]
+fn drawn_principal_variation_ends_at_repetition() {
+    // Both players move a piece and then move it back,
+    // which would loop forever without repetition detection.
+    let a = State::initial();
+    let (b, c, d) = a
+        .children()
+        .into_iter()
+        .flat_map(|b| b.children().into_iter().map(move |c| (b, c)))
+        .flat_map(|(b, c)| c.children().into_iter().map(move |d| (b, c, d)))
+        .find(|&(_, _, d)| d.children().contains(&a))
+        .unwrap();
+    let draw = StateStats::new(Outcome::DRAW, 0);
+    let mut solution = StateMap::empty();
+    solution.add(a, b.with_stats(draw));
+    solution.add(b, c.with_stats(draw));
+    solution.add(c, d.with_stats(draw));
+    solution.add(d, a.with_stats(draw));
+
+    let line = principal_variation(a, &solution);
+
+    assert_eq!(
+        vec![b, c, d, a],
+        line.iter().map(|&(_, state, _)| state).collect::<Vec<_>>()
+    );
+    assert!(line.iter().all(|&(_, _, outcome)| outcome == Outcome::DRAW));
+}
+
+#[test]
+fn solution_index_principal_variation_agrees() {
+    for root in SMALL_ROOTS {
+        let solution = solution(root);
+        let mut bytes = vec![];
+        write_solution_file(
+            &solution,
+            Ruleset::SimplifiedTry,
+            &mut bytes,
+            &mut SilentObserver,
+        )
+        .unwrap();
+        let mut index =
+            SolutionIndex::new(std::io::Cursor::new(bytes), Ruleset::SimplifiedTry).unwrap();
+
+        assert_eq!(
+            principal_variation(root, &solution),
+            index.principal_variation(root).unwrap()
+        );
+    }
+}
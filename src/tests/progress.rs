@@ -0,0 +1,210 @@
+use super::*;
+
+use std::time::Duration;
+
+/// Records every event.
+#[derive(Default)]
+struct Recorder {
+    report_interval: u64,
+    started: Vec<SolverPhase>,
+    progress: Vec<ProgressReport>,
+    finished: Vec<ProgressReport>,
+}
+
+impl ProgressObserver for Recorder {
+    fn report_interval(&self) -> u64 {
+        self.report_interval
+    }
+
+    fn phase_started(&mut self, phase: SolverPhase) {
+        self.started.push(phase);
+    }
+
+    fn progress(&mut self, report: &ProgressReport) {
+        self.progress.push(*report);
+    }
+
+    fn phase_finished(&mut self, report: &ProgressReport) {
+        self.finished.push(*report);
+    }
+}
+
+fn sample_report() -> ProgressReport {
+    ProgressReport {
+        phase: SolverPhase::Retrograde,
+        processed: 100,
+        resumed_from: 0,
+        total: Some(300),
+        queue_depth: Some(7),
+        elapsed: Duration::from_secs(2),
+    }
+}
+
+#[test]
+fn reachable_states_reports_at_interval() {
+    for root in SMALL_ROOTS {
+        let mut recorder = Recorder {
+            report_interval: 3,
+            ..Default::default()
+        };
+        let reachable = reachable_states(root, &mut recorder);
+
+        assert_eq!(vec![SolverPhase::ReachableStates], recorder.started);
+        assert_eq!(reachable.len() / 3, recorder.progress.len() as u64);
+        for (i, report) in recorder.progress.iter().enumerate() {
+            assert_eq!(3 * (i as u64 + 1), report.processed);
+            assert_eq!(None, report.total);
+            assert!(report.queue_depth.is_some());
+        }
+
+        assert_eq!(1, recorder.finished.len());
+        assert_eq!(reachable.len(), recorder.finished[0].processed);
+        assert_eq!(Some(0), recorder.finished[0].queue_depth);
+    }
+}
+
+#[test]
+fn retrograde_total_is_state_count() {
+    let reachable = reachable_states(SMALL_ROOTS[0], &mut SilentObserver);
+    let mut map = initial_stat_map(&reachable, &mut SilentObserver);
+    let mut recorder = Recorder {
+        report_interval: 1,
+        ..Default::default()
+    };
+    compute_stats(&mut map, &mut recorder);
+
+    assert_eq!(vec![SolverPhase::Retrograde], recorder.started);
+    for report in recorder.progress.iter().chain(&recorder.finished) {
+        assert_eq!(Some(reachable.len()), report.total);
+        assert!(report.processed <= reachable.len());
+    }
+}
+
+#[test]
+fn parallel_best_child_map_reports_every_state() {
+    let map = solve(SMALL_ROOTS[0]);
+    let mut recorder = Recorder {
+        report_interval: 1,
+        ..Default::default()
+    };
+    best_child_map_parallel(&map, 3, &mut recorder);
+
+    assert_eq!(vec![SolverPhase::BestChildren], recorder.started);
+    assert_eq!(map.len(), recorder.finished[0].processed);
+}
+
+#[test]
+fn report_estimates_are_correct() {
+    let report = sample_report();
+
+    assert_eq!(50.0, report.throughput());
+    assert_eq!(Some(200), report.remaining());
+    assert_eq!(Some(Duration::from_secs(4)), report.eta());
+
+    let open_ended = ProgressReport {
+        total: None,
+        ..report
+    };
+    assert_eq!(Some(7), open_ended.remaining());
+
+    let unknown = ProgressReport {
+        queue_depth: None,
+        ..open_ended
+    };
+    assert_eq!(None, unknown.remaining());
+    assert_eq!(None, unknown.eta());
+}
+
+#[test]
+fn resumed_report_estimates_only_count_new_work() {
+    let report = ProgressReport {
+        processed: 1_000_100,
+        resumed_from: 1_000_000,
+        total: Some(1_000_300),
+        ..sample_report()
+    };
+
+    assert_eq!(50.0, report.throughput());
+    assert_eq!(Some(200), report.remaining());
+    assert_eq!(Some(Duration::from_secs(4)), report.eta());
+}
+
+#[test]
+fn resumed_tracker_reports_its_baseline() {
+    let mut recorder = Recorder {
+        report_interval: 2,
+        ..Default::default()
+    };
+    let mut tracker = PhaseTracker::resume(&mut recorder, SolverPhase::Retrograde, None, 1_000_000);
+    for _ in 0..5 {
+        tracker.tick(None);
+    }
+    tracker.finish(None);
+
+    let finished = recorder.finished[0];
+    assert_eq!(1_000_005, finished.processed);
+    assert_eq!(1_000_000, finished.resumed_from);
+    assert!(recorder
+        .progress
+        .iter()
+        .all(|report| report.resumed_from == 1_000_000));
+    assert!(finished.throughput() <= 5.0 / finished.elapsed.as_secs_f64());
+}
+
+#[test]
+fn plain_text_observer_output_is_correct() {
+    let mut observer = PlainTextObserver::new(vec![]);
+    observer.phase_started(SolverPhase::Retrograde);
+    observer.progress(&sample_report());
+    observer.phase_finished(&sample_report());
+
+    assert_eq!(
+        "Started retrograde phase.\n\
+         retrograde: processed 100 states (50 states/s), queue depth 7, about 200 remaining, ETA 4s.\n\
+         Completed retrograde phase: processed 100 states. It took 2s.\n",
+        String::from_utf8(observer.into_inner()).unwrap()
+    );
+}
+
+#[test]
+fn json_lines_observer_output_is_correct() {
+    let mut observer = JsonLinesObserver::new(vec![]);
+    observer.phase_started(SolverPhase::Retrograde);
+    observer.progress(&sample_report());
+    observer.phase_finished(&ProgressReport {
+        total: None,
+        queue_depth: None,
+        ..sample_report()
+    });
+
+    assert_eq!(
+        r#"{"event":"phase_started","phase":"retrograde"}
+{"event":"progress","phase":"retrograde","processed":100,"total":300,"queue_depth":7,"remaining":200,"elapsed_secs":2.000,"throughput":50.000,"eta_secs":4.000}
+{"event":"phase_finished","phase":"retrograde","processed":100,"total":null,"queue_depth":null,"remaining":null,"elapsed_secs":2.000,"throughput":50.000,"eta_secs":null}
+"#,
+        String::from_utf8(observer.into_inner()).unwrap()
+    );
+}
+
+#[test]
+fn solution_passes_report_every_state() {
+    let map = solve(SMALL_ROOTS[0]);
+    let best_child_map = best_child_map(&map, &mut SilentObserver);
+
+    let mut recorder = Recorder {
+        report_interval: 1,
+        ..Default::default()
+    };
+    wdl_table_from_best_child_map(&best_child_map, &mut recorder);
+    compact_best_child_map(&best_child_map, &mut recorder);
+
+    assert_eq!(
+        vec![SolverPhase::WdlTable, SolverPhase::CompactSolution],
+        recorder.started
+    );
+    for report in &recorder.finished {
+        assert_eq!(Some(best_child_map.len()), report.total);
+        assert_eq!(best_child_map.len(), report.processed);
+    }
+    assert_eq!(2 * best_child_map.len(), recorder.progress.len() as u64);
+}
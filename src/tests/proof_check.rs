@@ -0,0 +1,139 @@
+use super::*;
+
+use std::collections::BTreeMap;
+
+fn proof_trees() -> Vec<ProofTree> {
+    SMALL_ROOTS
+        .into_iter()
+        .filter_map(|root| proof_tree(&solution(root), root))
+        .collect()
+}
+
+#[test]
+fn exported_proof_trees_are_valid() {
+    let trees = proof_trees();
+    assert!(!trees.is_empty());
+
+    for tree in trees {
+        let report = check_proof_tree(&tree).unwrap();
+
+        assert_eq!(tree.moves.len() as u64, report.reachable_move_count);
+        assert_eq!(0, report.unreachable_move_count);
+    }
+}
+
+#[test]
+fn terminal_roots_are_valid() {
+    let mut terminal_states = vec![];
+    fuzz(100, |state| {
+        if state.is_terminal() {
+            terminal_states.push(state);
+        }
+    });
+
+    for root in terminal_states {
+        let outcome = match root.terminality() {
+            Terminality::Win => Outcome::win_in(0),
+            _ => Outcome::loss_in(0),
+        };
+        let tree = ProofTree {
+            root,
+            outcome,
+            moves: BTreeMap::new(),
+        };
+
+        assert!(check_proof_tree(&tree).is_ok());
+        assert!(check_proof_tree(&ProofTree {
+            outcome: outcome.invert(),
+            ..tree
+        })
+        .is_err());
+    }
+}
+
+#[test]
+fn wrong_outcome_is_rejected() {
+    for tree in proof_trees() {
+        for outcome in [tree.outcome.delay_by_one(), tree.outcome.undelay_by_one()] {
+            let error = check_proof_tree(&ProofTree {
+                outcome,
+                ..tree.clone()
+            })
+            .unwrap_err();
+
+            assert!(matches!(error, ProofTreeError::OutcomeMismatch { .. }));
+        }
+
+        // These claim the wrong winner,
+        // so the checker may fail before it knows the strategy's length.
+        for outcome in [tree.outcome.invert(), Outcome::DRAW] {
+            assert!(check_proof_tree(&ProofTree {
+                outcome,
+                ..tree.clone()
+            })
+            .is_err());
+        }
+    }
+}
+
+#[test]
+fn missing_and_illegal_moves_are_rejected() {
+    for tree in proof_trees() {
+        for &prover in tree.moves.keys() {
+            let mut missing = tree.clone();
+            missing.moves.remove(&prover);
+            assert_eq!(
+                Err(ProofTreeError::MissingMove { state: prover }),
+                check_proof_tree(&missing)
+            );
+
+            let mut illegal = tree.clone();
+            illegal.moves.insert(prover, prover);
+            assert_eq!(
+                Err(ProofTreeError::IllegalMove {
+                    parent: prover,
+                    child: prover
+                }),
+                check_proof_tree(&illegal)
+            );
+        }
+    }
+}
+
+#[test]
+fn unreachable_moves_are_counted() {
+    for mut tree in proof_trees() {
+        let unreachable = State::initial();
+        tree.moves.insert(unreachable, unreachable.children()[0]);
+
+        let report = check_proof_tree(&tree).unwrap();
+
+        assert_eq!(1, report.unreachable_move_count);
+    }
+}
+
+#[test]
+fn cycle_is_rejected() {
+    // The prover moves, the defender replies,
+    // and then the prover and the defender undo their moves.
+    // Since the checker explores the last reply first,
+    // it reaches the repetition before any missing move.
+    let a = State::initial();
+    let (b, r, x) = a
+        .children()
+        .into_iter()
+        .map(|b| (b, *b.children().last().unwrap()))
+        .flat_map(|(b, r)| r.children().into_iter().map(move |x| (b, r, x)))
+        .find(|&(_, _, x)| x.children().contains(&a))
+        .unwrap();
+    let tree = ProofTree {
+        root: a,
+        outcome: Outcome::win_in(3),
+        moves: BTreeMap::from([(a, b), (r, x)]),
+    };
+
+    assert_eq!(
+        Err(ProofTreeError::Cycle { state: a }),
+        check_proof_tree(&tree)
+    );
+}
@@ -0,0 +1,97 @@
+use super::*;
+
+use std::collections::BTreeMap;
+
+#[test]
+fn proof_tree_follows_best_children() {
+    let mut tree_count = 0;
+    for root in SMALL_ROOTS {
+        let solution = solution(root);
+        let Some(tree) = proof_tree(&solution, root) else {
+            assert_eq!(Outcome::DRAW, solution.get(root).parent_outcome());
+            continue;
+        };
+
+        tree_count += 1;
+        assert_eq!(root, tree.root);
+        assert_eq!(solution.get(root).parent_outcome(), tree.outcome);
+        for (&prover, &child) in &tree.moves {
+            assert_eq!(solution.get(prover).state(), child);
+            assert!(solution.get(prover).parent_outcome().0 > 0);
+        }
+    }
+
+    assert!(tree_count > 0);
+}
+
+#[test]
+fn proof_tree_covers_every_defender_reply() {
+    for root in SMALL_ROOTS {
+        let solution = solution(root);
+        let Some(tree) = proof_tree(&solution, root) else {
+            continue;
+        };
+
+        let mut defenders = vec![];
+        if tree.outcome.0 > 0 {
+            defenders.extend(tree.moves.get(&root).copied());
+        } else {
+            defenders.push(root);
+        }
+        defenders.extend(tree.moves.values().copied());
+
+        for defender in defenders {
+            defender.visit_children(|reply| {
+                assert!(reply.terminality() == Terminality::Win || tree.moves.contains_key(&reply));
+            });
+        }
+    }
+}
+
+#[test]
+fn proof_tree_of_draw_is_none() {
+    let draw = State::initial();
+    let mut solution = StateMap::empty();
+    let child = draw.children()[0];
+    solution.add(draw, child.with_stats(StateStats::new(Outcome::DRAW, 0)));
+
+    assert_eq!(None, proof_tree(&solution, draw));
+}
+
+#[test]
+fn proof_tree_round_trips() {
+    for root in SMALL_ROOTS {
+        let Some(tree) = proof_tree(&solution(root), root) else {
+            continue;
+        };
+
+        let mut bytes = vec![];
+        tree.write_to(&mut bytes).unwrap();
+
+        assert_eq!(15 + 6 * tree.moves.len(), bytes.len());
+        assert_eq!(tree, ProofTree::read_from(bytes.as_slice()).unwrap());
+    }
+}
+
+#[test]
+fn proof_tree_rejects_corrupt_files() {
+    let tree = ProofTree {
+        root: State::initial(),
+        outcome: Outcome::win_in(1),
+        moves: BTreeMap::from([(State::initial(), State::initial().children()[0])]),
+    };
+    let mut bytes = vec![];
+    tree.write_to(&mut bytes).unwrap();
+
+    let mut bad_magic = bytes.clone();
+    bad_magic[0] ^= 1;
+    assert!(ProofTree::read_from(bad_magic.as_slice()).is_err());
+
+    let mut bad_index = bytes.clone();
+    *bad_index.last_mut().unwrap() = 255;
+    assert!(ProofTree::read_from(bad_index.as_slice()).is_err());
+
+    let mut truncated = bytes;
+    truncated.pop();
+    assert!(ProofTree::read_from(truncated.as_slice()).is_err());
+}
@@ -0,0 +1,94 @@
+use super::*;
+
+use rand::Rng;
+
+#[test]
+fn rank_count_is_correct() {
+    insta::assert_snapshot!(State::RANK_COUNT.to_string());
+}
+
+#[test]
+fn initial_state_rank_is_correct() {
+    insta::assert_snapshot!(State::initial().rank().to_string());
+}
+
+#[test]
+fn unrank_inverts_rank() {
+    fuzz(100_000, |state| {
+        let rank = state.rank();
+        assert!(rank < State::RANK_COUNT);
+
+        let unranked = State::unrank(rank);
+        if unranked != state {
+            let state = state.pretty();
+            let unranked = unranked.pretty();
+            panic!("Unrank did not invert rank (rank = {rank}).\n\nSTATE:\n\n{state}\n\nUNRANKED:\n\n{unranked}");
+        }
+    });
+}
+
+#[test]
+fn rank_inverts_unrank() {
+    const FUZZ_TIMES: usize = 1_000_000;
+
+    let mut prng = deterministic_prng();
+
+    for _ in 0..FUZZ_TIMES {
+        let rank = prng.gen_range(0..State::RANK_COUNT);
+        let state = State::unrank(rank);
+
+        assert_eq!(state, state.into_builder().build());
+        assert_eq!(rank, state.rank());
+    }
+}
+
+#[test]
+fn extreme_ranks_are_invertible() {
+    for rank in [0, 1, State::RANK_COUNT - 2, State::RANK_COUNT - 1] {
+        assert_eq!(rank, State::unrank(rank).rank());
+    }
+}
+
+#[test]
+fn reachable_states_are_constructible() {
+    fuzz(100_000, |state| {
+        assert!(state.is_constructible(), "{}", state.pretty());
+    });
+}
+
+#[test]
+fn unranked_states_are_constructible() {
+    const FUZZ_TIMES: usize = 100_000;
+
+    let mut prng = deterministic_prng();
+
+    for _ in 0..FUZZ_TIMES {
+        let state = State::unrank(prng.gen_range(0..State::RANK_COUNT));
+        assert!(state.is_constructible(), "{}", state.pretty());
+    }
+}
+
+#[test]
+fn corrupted_states_are_not_constructible() {
+    let initial = State::initial().0;
+
+    // Unused bits are set.
+    assert!(!State(initial | (1 << 40)).is_constructible());
+
+    // The passive lion is in the hand.
+    assert!(!State(initial | 0b1111).is_constructible());
+
+    // Both lions are on the same square.
+    assert!(!State((initial & !0b1111) | ((initial >> 4) & 0b1111)).is_constructible());
+
+    // The passive lion is in the nonexistent fourth column.
+    assert!(!State((initial & !0b1111) | 0b0011).is_constructible());
+
+    // The pieces of a pair are in descending order.
+    let chick0 = (initial >> Offset::CHICK0.0) & 0b11_1111;
+    let chick1 = (initial >> Offset::CHICK1.0) & 0b11_1111;
+    let swapped = (initial & !(0xFFF << Offset::CHICK1.0))
+        | (chick0 << Offset::CHICK1.0)
+        | (chick1 << Offset::CHICK0.0);
+    assert!(!State(swapped).is_constructible());
+}
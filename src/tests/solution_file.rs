@@ -0,0 +1,186 @@
+use super::*;
+
+const HEADER_LEN: usize = SolutionHeader::LEN;
+
+fn solution_bytes(root: State) -> (StateMap<StateAndStats>, Vec<u8>) {
+    let solution = solution(root);
+    let mut bytes = vec![];
+    write_solution_file(
+        &solution,
+        Ruleset::SimplifiedTry,
+        &mut bytes,
+        &mut SilentObserver,
+    )
+    .unwrap();
+    (solution, bytes)
+}
+
+fn read(bytes: &[u8]) -> Result<StateMap<StateAndStats>, SolutionFileError> {
+    read_solution_file(bytes, Ruleset::SimplifiedTry, &mut SilentObserver)
+}
+
+#[test]
+fn solution_file_round_trips() {
+    for root in SMALL_ROOTS {
+        let (solution, bytes) = solution_bytes(root);
+        let read = read(&bytes).unwrap();
+
+        assert_eq!(solution.to_sorted_vec(), read.to_sorted_vec());
+        assert_eq!(
+            HEADER_LEN + solution.to_sorted_vec().len() * SOLUTION_RECORD_LEN,
+            bytes.len()
+        );
+    }
+}
+
+#[test]
+fn solution_header_is_correct() {
+    let (solution, bytes) = solution_bytes(SMALL_ROOTS[0]);
+    let header = SolutionHeader::read_from(&mut bytes.as_slice()).unwrap();
+
+    assert_eq!(&SOLUTION_MAGIC, &bytes[0..8]);
+    assert_eq!(SOLUTION_FORMAT_VERSION, header.version);
+    assert_eq!(Ruleset::SimplifiedTry, header.ruleset);
+    assert_eq!(solution.to_sorted_vec().len() as u64, header.entry_count);
+    assert_eq!(&header.to_bytes()[..], &bytes[..HEADER_LEN]);
+}
+
+#[test]
+fn empty_solution_round_trips() {
+    let mut bytes = vec![];
+    write_solution_file(
+        &StateMap::empty(),
+        Ruleset::SimplifiedTry,
+        &mut bytes,
+        &mut SilentObserver,
+    )
+    .unwrap();
+    assert_eq!(HEADER_LEN, bytes.len());
+
+    let read = read(&bytes).unwrap();
+    assert!(read.to_sorted_vec().is_empty());
+}
+
+#[test]
+fn solution_file_rejects_empty_file() {
+    assert!(matches!(read(&[]), Err(SolutionFileError::MissingMagic)));
+}
+
+#[test]
+fn solution_file_rejects_headerless_file() {
+    let (_, bytes) = solution_bytes(SMALL_ROOTS[0]);
+
+    let error = read(&bytes[HEADER_LEN..]).unwrap_err();
+    assert!(matches!(error, SolutionFileError::MissingMagic));
+}
+
+#[test]
+fn solution_file_rejects_truncated_header() {
+    let (_, bytes) = solution_bytes(SMALL_ROOTS[0]);
+
+    let error = read(&bytes[..HEADER_LEN - 1]).unwrap_err();
+    assert!(matches!(
+        error,
+        SolutionFileError::TruncatedHeader { byte_len } if byte_len == HEADER_LEN as u64 - 1
+    ));
+}
+
+#[test]
+fn solution_file_rejects_unsupported_version() {
+    let (_, mut bytes) = solution_bytes(SMALL_ROOTS[0]);
+    bytes[8..12].copy_from_slice(&(SOLUTION_FORMAT_VERSION + 1).to_le_bytes());
+
+    let error = read(&bytes).unwrap_err();
+    assert!(matches!(
+        error,
+        SolutionFileError::UnsupportedVersion { version } if version == SOLUTION_FORMAT_VERSION + 1
+    ));
+}
+
+#[test]
+fn solution_file_rejects_unknown_ruleset() {
+    let (_, mut bytes) = solution_bytes(SMALL_ROOTS[0]);
+    bytes[12..16].copy_from_slice(&0xdead_u32.to_le_bytes());
+
+    let error = read(&bytes).unwrap_err();
+    assert!(matches!(
+        error,
+        SolutionFileError::UnknownRuleset { tag: 0xdead }
+    ));
+}
+
+#[test]
+fn solution_file_rejects_truncated_file() {
+    let (_, mut bytes) = solution_bytes(SMALL_ROOTS[0]);
+    bytes.truncate(bytes.len() - 3);
+    let byte_len = bytes.len() as u64;
+
+    let error = read(&bytes).unwrap_err();
+    assert!(matches!(error, SolutionFileError::Truncated { byte_len: b } if b == byte_len));
+}
+
+#[test]
+fn solution_file_rejects_odd_record_length() {
+    let (_, mut bytes) = solution_bytes(SMALL_ROOTS[0]);
+    bytes.truncate(bytes.len() - 8);
+    let byte_len = bytes.len() as u64;
+
+    let error = read(&bytes).unwrap_err();
+    assert!(matches!(error, SolutionFileError::OddRecordLength { byte_len: b } if b == byte_len));
+}
+
+#[test]
+fn solution_file_rejects_missing_records() {
+    let (solution, mut bytes) = solution_bytes(SMALL_ROOTS[0]);
+    bytes.truncate(bytes.len() - SOLUTION_RECORD_LEN);
+    let entry_count = solution.to_sorted_vec().len() as u64;
+
+    let error = read(&bytes).unwrap_err();
+    assert!(matches!(
+        error,
+        SolutionFileError::EntryCountMismatch { expected, found }
+            if expected == entry_count && found == entry_count - 1
+    ));
+}
+
+#[test]
+fn solution_file_rejects_corrupted_body() {
+    let (_, mut bytes) = solution_bytes(SMALL_ROOTS[0]);
+    // Flip the lowest bit of the first record's best child's stats.
+    // This still decodes to a valid record, so only the checksum catches it.
+    bytes[HEADER_LEN + 8 + 5] ^= 1;
+
+    let error = read(&bytes).unwrap_err();
+    assert!(matches!(error, SolutionFileError::ChecksumMismatch { .. }));
+}
+
+#[test]
+fn solution_file_rejects_invalid_parent() {
+    let (_, mut bytes) = solution_bytes(SMALL_ROOTS[0]);
+    // Set the most significant byte of the last record's parent.
+    let last_record_index = (bytes.len() - HEADER_LEN) / SOLUTION_RECORD_LEN - 1;
+    bytes[HEADER_LEN + last_record_index * SOLUTION_RECORD_LEN + 7] = 0xff;
+
+    let error = read(&bytes).unwrap_err();
+    assert!(matches!(
+        error,
+        SolutionFileError::InvalidState { record_index, .. }
+            if record_index == last_record_index as u64
+    ));
+}
+
+#[test]
+fn solution_file_rejects_invalid_best_child() {
+    let (_, mut bytes) = solution_bytes(SMALL_ROOTS[0]);
+    // Put the first record's best child's passive lion in hand.
+    bytes[HEADER_LEN + 8] |= 0b1111;
+
+    let error = read(&bytes).unwrap_err();
+    assert!(matches!(
+        error,
+        SolutionFileError::InvalidState {
+            record_index: 0,
+            ..
+        }
+    ));
+}
@@ -0,0 +1,133 @@
+use super::*;
+
+use std::io::Cursor;
+
+fn solution_index(solution: &StateMap<StateAndStats>) -> SolutionIndex<Cursor<Vec<u8>>> {
+    let mut bytes = vec![];
+    write_solution_file(
+        solution,
+        Ruleset::SimplifiedTry,
+        &mut bytes,
+        &mut SilentObserver,
+    )
+    .unwrap();
+    SolutionIndex::new(Cursor::new(bytes), Ruleset::SimplifiedTry).unwrap()
+}
+
+#[test]
+fn solution_index_agrees_with_best_child_map() {
+    for root in SMALL_ROOTS {
+        let stats_map = solve(root);
+        let best_child_map = best_child_map(&stats_map, &mut SilentObserver);
+        let mut index = solution_index(&best_child_map);
+
+        assert_eq!(best_child_map.to_sorted_vec().len() as u64, index.len());
+
+        stats_map.visit_in_key_order(|state, stats| {
+            let best_child = best_child_map.get(state);
+            let expected_best_child = (!best_child.is_null()).then_some(best_child);
+            assert_eq!(expected_best_child, index.best_child(state).unwrap());
+
+            let expected_outcome = stats.best_outcome().unwrap_or(Outcome::DRAW);
+            assert_eq!(Some(expected_outcome), index.outcome(state).unwrap());
+        });
+    }
+}
+
+#[test]
+fn solution_index_finds_every_record() {
+    // This also exercises seeking backwards,
+    // since consecutive queries start their searches from the middle.
+    let best_child_map = solution(SMALL_ROOTS[0]);
+    let mut index = solution_index(&best_child_map);
+
+    for (i, (parent, best_child)) in best_child_map.to_sorted_vec().into_iter().enumerate() {
+        assert_eq!((parent, best_child), index.record(i as u64).unwrap());
+        assert_eq!(Some(best_child), index.best_child(parent).unwrap());
+    }
+}
+
+#[test]
+fn solution_index_agrees_with_large_map() {
+    // The small subgames fit in a single buffer,
+    // so we also test a map that spans many buffers.
+    // The records are arbitrary, since the index doesn't
+    // check whether best children are children of their parents.
+    let mut rng = deterministic_prng();
+    let mut map = StateMap::empty();
+    for _ in 0..100_000 {
+        let parent = State::unrank(rng.gen_range(0..State::RANK_COUNT));
+        let best_child = State::unrank(rng.gen_range(0..State::RANK_COUNT));
+        map.add(
+            parent,
+            best_child.with_stats(StateStats::new(Outcome::DRAW, 0)),
+        );
+    }
+    let mut index = solution_index(&map);
+
+    map.visit_in_key_order(|parent, best_child| {
+        assert_eq!(Some(best_child), index.best_child(parent).unwrap());
+    });
+
+    for _ in 0..100_000 {
+        let state = State::unrank(rng.gen_range(0..State::RANK_COUNT));
+        let best_child = map.get(state);
+        let expected = (!best_child.is_null()).then_some(best_child);
+        assert_eq!(expected, index.best_child(state).unwrap());
+    }
+}
+
+#[test]
+fn solution_index_returns_none_for_absent_states() {
+    let best_child_map = solution(SMALL_ROOTS[0]);
+    let mut index = solution_index(&best_child_map);
+
+    // The initial state is not part of any small subgame.
+    assert_eq!(None, index.best_child(State::initial()).unwrap());
+    assert_eq!(None, index.outcome(State::initial()).unwrap());
+}
+
+#[test]
+fn empty_solution_index_returns_none() {
+    let mut index = solution_index(&StateMap::empty());
+
+    assert!(index.is_empty());
+    assert_eq!(None, index.best_child(State::initial()).unwrap());
+}
+
+#[test]
+fn solution_index_rejects_missing_records() {
+    let best_child_map = solution(SMALL_ROOTS[0]);
+    let mut bytes = vec![];
+    write_solution_file(
+        &best_child_map,
+        Ruleset::SimplifiedTry,
+        &mut bytes,
+        &mut SilentObserver,
+    )
+    .unwrap();
+    bytes.truncate(bytes.len() - SOLUTION_RECORD_LEN);
+
+    let error = SolutionIndex::new(Cursor::new(bytes), Ruleset::SimplifiedTry).unwrap_err();
+    assert!(matches!(
+        error,
+        SolutionFileError::EntryCountMismatch { .. }
+    ));
+}
+
+#[test]
+fn solution_index_rejects_truncated_file() {
+    let best_child_map = solution(SMALL_ROOTS[0]);
+    let mut bytes = vec![];
+    write_solution_file(
+        &best_child_map,
+        Ruleset::SimplifiedTry,
+        &mut bytes,
+        &mut SilentObserver,
+    )
+    .unwrap();
+    bytes.pop();
+
+    let error = SolutionIndex::new(Cursor::new(bytes), Ruleset::SimplifiedTry).unwrap_err();
+    assert!(matches!(error, SolutionFileError::Truncated { .. }));
+}
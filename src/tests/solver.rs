@@ -0,0 +1,220 @@
+use super::*;
+
+use std::fs::{self, File};
+use std::path::PathBuf;
+
+/// Returns an empty directory that is unique to the test.
+fn test_dir(name: &str) -> PathBuf {
+    let dir =
+        std::env::temp_dir().join(format!("dobutsu_shogi_2024_{}_{name}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Records the phase of every event.
+#[derive(Default)]
+struct PhaseRecorder(Vec<SolverPhase>);
+
+impl ProgressObserver for PhaseRecorder {
+    fn report_interval(&self) -> u64 {
+        1
+    }
+
+    fn phase_started(&mut self, phase: SolverPhase) {
+        self.0.push(phase);
+    }
+
+    fn progress(&mut self, report: &ProgressReport) {
+        self.0.push(report.phase);
+    }
+
+    fn phase_finished(&mut self, report: &ProgressReport) {
+        self.0.push(report.phase);
+    }
+}
+
+#[test]
+fn solver_agrees_with_manual_pipeline() {
+    for root in SMALL_ROOTS {
+        let expected = solution(root);
+        let solution = Solver::new().root(root).solve().unwrap();
+
+        assert_eq!(root, solution.root());
+        assert_eq!(
+            expected.to_sorted_vec(),
+            solution.best_child_map().to_sorted_vec()
+        );
+    }
+}
+
+#[test]
+fn solver_agrees_with_manual_pipeline_on_many_threads() {
+    for root in SMALL_ROOTS {
+        let expected = solution(root);
+        let solution = Solver::new().root(root).thread_count(4).solve().unwrap();
+
+        assert_eq!(
+            expected.to_sorted_vec(),
+            solution.best_child_map().to_sorted_vec()
+        );
+    }
+}
+
+#[test]
+fn solution_outcomes_agree_with_stats() {
+    for root in SMALL_ROOTS {
+        let stats_map = solve(root);
+        let solution = Solver::new().root(root).solve().unwrap();
+
+        stats_map.visit_in_key_order(|state, stats| {
+            assert_eq!(
+                Some(stats.best_outcome().unwrap_or(Outcome::DRAW)),
+                solution.outcome(state)
+            );
+        });
+    }
+}
+
+#[test]
+fn solver_reports_every_phase_in_order() {
+    let mut recorder = PhaseRecorder::default();
+    Solver::new()
+        .root(SMALL_ROOTS[0])
+        .progress(&mut recorder)
+        .solve()
+        .unwrap();
+
+    let mut phases = recorder.0;
+    phases.dedup();
+    assert_eq!(
+        vec![
+            SolverPhase::ReachableStates,
+            SolverPhase::InitialStats,
+            SolverPhase::Retrograde,
+            SolverPhase::BestChildren,
+        ],
+        phases
+    );
+}
+
+#[test]
+fn outputs_report_to_the_solver_observer() {
+    let dir = test_dir("outputs_report_to_the_solver_observer");
+    let mut recorder = PhaseRecorder::default();
+    Solver::new()
+        .root(SMALL_ROOTS[0])
+        .progress(&mut recorder)
+        .output(WdlTableSink(dir.join("wdl.dat")))
+        .output(CompactSolutionSink(dir.join("compact_solution.dat")))
+        .output(MoveOutcomeTableSink(dir.join("move_outcomes.dat")))
+        .output(OptimalChildrenSink(dir.join("optimal_children.dat")))
+        .solve()
+        .unwrap();
+
+    let mut phases = recorder.0;
+    phases.dedup();
+    assert_eq!(
+        vec![
+            SolverPhase::WdlTable,
+            SolverPhase::CompactSolution,
+            SolverPhase::MoveOutcomes,
+            SolverPhase::OptimalChildren,
+        ],
+        phases[4..]
+    );
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn solver_writes_outputs() {
+    let dir = test_dir("solver_writes_outputs");
+    let solution_path = dir.join("solution.dat");
+    let mut sink_calls = 0;
+
+    let solution = Solver::new()
+        .root(SMALL_ROOTS[0])
+        .output(SolutionFileSink(solution_path.clone()))
+        .output(|_: &Solution| {
+            sink_calls += 1;
+            Ok(())
+        })
+        .solve()
+        .unwrap();
+
+    assert_eq!(1, sink_calls);
+    let read = read_solution_file(
+        File::open(&solution_path).unwrap(),
+        Ruleset::SimplifiedTry,
+        &mut SilentObserver,
+    )
+    .unwrap();
+    assert_eq!(
+        solution.best_child_map().to_sorted_vec(),
+        read.to_sorted_vec()
+    );
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn solver_resumes_from_checkpoints() {
+    let dir = test_dir("solver_resumes_from_checkpoints");
+    let first = Solver::new()
+        .root(SMALL_ROOTS[1])
+        .checkpoint_dir(&dir)
+        .solve()
+        .unwrap();
+
+    assert!(dir
+        .join(crate::checkpoint::CheckpointKind::ReachableStates.file_name())
+        .exists());
+    assert!(dir
+        .join(crate::checkpoint::CheckpointKind::StateStats.file_name())
+        .exists());
+
+    let mut recorder = PhaseRecorder::default();
+    let second = Solver::new()
+        .root(SMALL_ROOTS[1])
+        .checkpoint_dir(&dir)
+        .progress(&mut recorder)
+        .solve()
+        .unwrap();
+
+    assert!(recorder
+        .0
+        .iter()
+        .all(|&phase| phase == SolverPhase::BestChildren));
+    assert_eq!(
+        first.best_child_map().to_sorted_vec(),
+        second.best_child_map().to_sorted_vec()
+    );
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn solver_rejects_checkpoint_for_other_root() {
+    let dir = test_dir("solver_rejects_checkpoint_for_other_root");
+    Solver::new()
+        .root(SMALL_ROOTS[0])
+        .checkpoint_dir(&dir)
+        .solve()
+        .unwrap();
+
+    let error = Solver::new()
+        .root(SMALL_ROOTS[1])
+        .checkpoint_dir(&dir)
+        .solve()
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        SolverError::Checkpoint {
+            error: crate::checkpoint::CheckpointError::RootMismatch { .. },
+            ..
+        }
+    ));
+
+    fs::remove_dir_all(dir).unwrap();
+}
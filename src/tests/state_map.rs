@@ -25,6 +25,7 @@ fn state_map_is_consistent_with_hash_map() {
         });
 
         assert_eq!(state_map_cardinality, reference.len());
+        assert_eq!(reference.len() as u64, state_map.len());
     }
 }
 
@@ -91,6 +92,53 @@ fn state_map_visitable_in_key_order() {
     }
 }
 
+#[test]
+fn state_map_shards_partition_entries_in_key_order() {
+    const FUZZ_TIMES: usize = 100;
+
+    let mut prng = deterministic_prng();
+
+    for _ in 0..FUZZ_TIMES {
+        let (state_map, _) = random_state_map_pair(&mut prng);
+        let shard_count = prng.gen_range(1..100);
+        let mut visited = vec![];
+        for shard_index in 0..shard_count {
+            state_map.visit_shard_in_key_order(shard_index, shard_count, |state, value| {
+                visited.push((state, value))
+            });
+        }
+
+        assert_eq!(state_map.to_sorted_vec(), visited);
+    }
+}
+
+#[test]
+fn state_map_union_in_place_is_consistent_with_hash_map() {
+    const FUZZ_TIMES: usize = 100;
+
+    let mut prng = deterministic_prng();
+
+    for _ in 0..FUZZ_TIMES {
+        let (mut state_map, mut reference) = random_state_map_pair(&mut prng);
+        let (mut other_state_map, mut other_reference) = random_state_map_pair(&mut prng);
+
+        // Make some keys collide, so that some buckets overlap.
+        for (&key, _) in reference.iter().take(10) {
+            let val = NonZeroU64::new(prng.gen());
+            other_state_map.add(key, val);
+            other_reference.insert(key, val);
+        }
+
+        state_map.union_in_place(other_state_map);
+        reference.extend(other_reference);
+
+        let mut expected = reference.into_iter().collect::<Vec<_>>();
+        expected.sort_unstable_by_key(|(key, _)| *key);
+        assert_eq!(expected.len() as u64, state_map.len());
+        assert_eq!(expected, state_map.to_sorted_vec());
+    }
+}
+
 fn random_state_map_pair(
     prng: &mut XorShiftRng,
 ) -> (
@@ -25,6 +25,7 @@ fn state_set_is_consistent_with_hash_set() {
         });
 
         assert_eq!(state_set_cardinality, reference.len());
+        assert_eq!(reference.len() as u64, state_set.len());
     }
 }
 
@@ -38,8 +39,8 @@ fn state_set_vec_is_consistent_with_hash_set() {
         let (state_set, reference) = random_state_set_pair(&mut prng);
         let state_set_vec = state_set.to_sorted_vec();
 
-        for state in reference.iter().copied() {
-            assert!(state_set_vec.contains(&state));
+        for state in reference.iter() {
+            assert!(state_set_vec.contains(state));
         }
 
         for state in state_set_vec.iter().copied() {
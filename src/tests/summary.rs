@@ -0,0 +1,174 @@
+use super::*;
+
+use std::time::Duration;
+
+#[test]
+fn stats_and_best_child_summaries_agree() {
+    for root in SMALL_ROOTS {
+        let stats_map = solve(root);
+        let best_child_map = best_child_map(&stats_map, &mut SilentObserver);
+
+        assert_eq!(
+            SolutionSummary::from_stats_map(&stats_map, root),
+            SolutionSummary::from_best_child_map(&best_child_map, root)
+        );
+    }
+}
+
+#[test]
+fn summary_counts_are_consistent() {
+    for root in SMALL_ROOTS {
+        let stats_map = solve(root);
+        let summary = SolutionSummary::from_stats_map(&stats_map, root);
+
+        assert_eq!(root, summary.root);
+        assert_eq!(stats_map.len(), summary.reachable_state_count);
+        assert_eq!(
+            summary.reachable_state_count,
+            summary.win_count + summary.loss_count + summary.draw_count
+        );
+        assert_eq!(summary.win_count, summary.win_in.iter().sum::<u64>());
+        assert_eq!(summary.loss_count, summary.loss_in.iter().sum::<u64>());
+
+        let mut terminal_count = 0;
+        stats_map.visit_in_key_order(|state, _| {
+            terminal_count += (state.terminality() != Terminality::Nonterminal) as u64;
+        });
+        assert_eq!(terminal_count, summary.win_in[0] + summary.loss_in[0]);
+    }
+}
+
+#[test]
+fn summary_root_outcome_is_correct() {
+    for root in SMALL_ROOTS {
+        let stats_map = solve(root);
+        let best_child_map = best_child_map(&stats_map, &mut SilentObserver);
+        let summary = SolutionSummary::from_stats_map(&stats_map, root);
+
+        assert_eq!(
+            Some(best_child_map.get(root).parent_outcome()),
+            summary.root_outcome
+        );
+    }
+}
+
+#[test]
+fn summary_json_is_correct() {
+    let mut win_in = [0; OUTCOME_DELAY_COUNT];
+    win_in[1] = 3;
+    let mut loss_in = [0; OUTCOME_DELAY_COUNT];
+    loss_in[0] = 2;
+    loss_in[200] = 1;
+    let summary = SolutionSummary {
+        root: State(0xabc),
+        reachable_state_count: 7,
+        win_count: 3,
+        loss_count: 3,
+        draw_count: 1,
+        win_in,
+        loss_in,
+        root_outcome: Some(Outcome::win_in(1)),
+        phase_timings: vec![
+            (SolverPhase::ReachableStates, Duration::from_millis(1500)),
+            (SolverPhase::Retrograde, Duration::from_millis(250)),
+        ],
+    };
+
+    let zeros = |count: usize| vec!["0"; count].join(",");
+    assert_eq!(
+        format!(
+            r#"{{"root":"0xabc","reachable_state_count":7,"win_count":3,"loss_count":3,"draw_count":1,"root_outcome":{{"result":"win","plies":1}},"win_in":[0,3,{}],"loss_in":[2,{},1],"phase_timings":{{"reachable_states":1.500,"retrograde":0.250}}}}"#,
+            zeros(199),
+            zeros(199)
+        ),
+        summary.to_json()
+    );
+
+    let draw = SolutionSummary {
+        root_outcome: Some(Outcome::DRAW),
+        phase_timings: vec![],
+        ..summary.clone()
+    };
+    assert!(draw
+        .to_json()
+        .contains(r#""root_outcome":{"result":"draw"},"#));
+    assert!(draw.to_json().ends_with(r#""phase_timings":{}}"#));
+
+    let loss = SolutionSummary {
+        root_outcome: Some(Outcome::loss_in(4)),
+        ..summary
+    };
+    assert!(loss
+        .to_json()
+        .contains(r#""root_outcome":{"result":"loss","plies":4},"#));
+}
+
+#[test]
+fn solver_summary_lists_every_phase() {
+    let root = SMALL_ROOTS[0];
+    let solution = Solver::new().root(root).solve().unwrap();
+    let summary = solution.summary();
+
+    assert_eq!(
+        vec![
+            SolverPhase::ReachableStates,
+            SolverPhase::InitialStats,
+            SolverPhase::Retrograde,
+            SolverPhase::BestChildren,
+        ],
+        summary
+            .phase_timings
+            .iter()
+            .map(|&(phase, _)| phase)
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        SolutionSummary::from_stats_map(&solve(root), root),
+        summary.with_phase_timings(vec![])
+    );
+}
+
+#[test]
+fn resumed_solve_accumulates_phase_timings() {
+    let root = SMALL_ROOTS[0];
+    let token = CancellationToken::new();
+    token.cancel();
+
+    let Err(SolverError::Cancelled(snapshot)) =
+        Solver::new().root(root).cancellation_token(token).solve()
+    else {
+        panic!("The solve was not cancelled.");
+    };
+    assert_eq!(
+        vec![SolverPhase::ReachableStates],
+        snapshot
+            .phase_timings()
+            .iter()
+            .map(|&(phase, _)| phase)
+            .collect::<Vec<_>>()
+    );
+
+    let solution = Solver::new().resume_from(*snapshot).solve().unwrap();
+    assert_eq!(4, solution.phase_timings().len());
+    assert_eq!(SolverPhase::ReachableStates, solution.phase_timings()[0].0);
+}
+
+#[test]
+fn summary_sink_writes_json() {
+    let path = std::env::temp_dir().join(format!(
+        "dobutsu_shogi_2024_{}_summary.json",
+        std::process::id()
+    ));
+    let solution = Solver::new()
+        .root(SMALL_ROOTS[1])
+        .output(SummarySink(path.clone()))
+        .solve()
+        .unwrap();
+
+    let json = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    // The timings are written before the sink runs,
+    // so they match the solution's.
+    assert_eq!(format!("{}\n", solution.summary().to_json()), json);
+}
@@ -0,0 +1,140 @@
+use super::*;
+
+fn sample(state: State, is_flipped: bool) -> TrainingSample {
+    TrainingSample {
+        state,
+        is_flipped,
+        outcome: Outcome::DRAW,
+        best_move_index: 0,
+    }
+}
+
+#[test]
+fn initial_state_planes_are_correct() {
+    let planes = sample(State::initial(), false).planes();
+
+    // Lion, chick, hen, elephant, and giraffe for owner 0, then owner 1.
+    let expected = [
+        0b000_000_000_010,
+        0b000_000_010_000,
+        0,
+        0b000_000_000_001,
+        0b000_000_000_100,
+        0b010_000_000_000,
+        0b000_010_000_000,
+        0,
+        0b100_000_000_000,
+        0b001_000_000_000,
+    ];
+    assert_eq!(expected, planes);
+    assert_eq!(
+        [0; HAND_COUNT_COUNT],
+        sample(State::initial(), false).hand_counts()
+    );
+
+    // The initial position is symmetric.
+    assert_eq!(planes, sample(State::initial(), true).planes());
+}
+
+#[test]
+fn flipping_rotates_the_board_and_swaps_owners() {
+    fuzz(1_000, |state| {
+        if state.is_terminal() {
+            return;
+        }
+
+        let planes = sample(state, false).planes();
+        let flipped_planes = sample(state, true).planes();
+        for (i, &plane) in planes.iter().enumerate() {
+            let rotated = (plane.reverse_bits() >> 4) & 0b1111_1111_1111;
+            assert_eq!(rotated, flipped_planes[(i + 5) % PLANE_COUNT]);
+        }
+
+        let hand_counts = sample(state, false).hand_counts();
+        let flipped_hand_counts = sample(state, true).hand_counts();
+        for (i, &count) in hand_counts.iter().enumerate() {
+            assert_eq!(count, flipped_hand_counts[(i + 3) % HAND_COUNT_COUNT]);
+        }
+    });
+}
+
+#[test]
+fn every_piece_is_on_a_plane_or_in_a_hand() {
+    fuzz(1_000, |state| {
+        if state.is_terminal() {
+            return;
+        }
+
+        let sample = sample(state, false);
+        let board_count: u32 = sample.planes().iter().map(|plane| plane.count_ones()).sum();
+        let hand_count: u32 = sample.hand_counts().iter().map(|&count| count as u32).sum();
+        assert_eq!(8, board_count + hand_count);
+
+        // Squares hold at most one piece.
+        let occupied = sample.planes().iter().fold(0, |occupied, plane| {
+            assert_eq!(0, occupied & plane);
+            occupied | plane
+        });
+        assert_eq!(board_count, occupied.count_ones());
+    });
+}
+
+#[test]
+fn sampling_is_reproducible() {
+    let solution = combined_solution();
+    let sample_count = (solution.len() as usize).saturating_sub(1).max(1);
+
+    let dataset = TrainingDataset::sample(&solution, sample_count, 42, &mut SilentObserver);
+    assert_eq!(
+        dataset,
+        TrainingDataset::sample(&solution, sample_count, 42, &mut SilentObserver)
+    );
+    assert_eq!(42, dataset.seed);
+    assert_eq!(sample_count, dataset.samples.len());
+
+    for pair in dataset.samples.windows(2) {
+        assert!(pair[0].state < pair[1].state);
+    }
+}
+
+#[test]
+fn samples_are_labelled_by_the_solution() {
+    let solution = combined_solution();
+    let mut observer = PlainTextObserver::new(vec![]);
+    let dataset = TrainingDataset::sample(&solution, usize::MAX, 7, &mut observer);
+
+    let log = String::from_utf8(observer.into_inner()).unwrap();
+    assert!(log.contains(&format!(
+        "Completed training_data phase: processed {} states.",
+        solution.len()
+    )));
+    assert_eq!(solution.len() as usize, dataset.samples.len());
+    for sample in dataset.samples {
+        let best_child = solution.get(sample.state);
+        assert_eq!(best_child.parent_outcome(), sample.outcome);
+        assert_eq!(
+            best_child.state(),
+            sample.state.children()[sample.best_move_index as usize]
+        );
+    }
+}
+
+#[test]
+fn dataset_round_trips_through_file() {
+    let dataset = TrainingDataset::sample(&combined_solution(), 1_000, 3, &mut SilentObserver);
+
+    let mut buffer = vec![];
+    dataset.write_to(&mut buffer).unwrap();
+    assert_eq!(
+        16 + dataset.samples.len() * TRAINING_RECORD_LEN,
+        buffer.len()
+    );
+    assert_eq!(dataset, TrainingDataset::read_from(&buffer[..]).unwrap());
+
+    let error = TrainingDataset::read_from(&buffer[..buffer.len() - 1]).unwrap_err();
+    assert_eq!(std::io::ErrorKind::UnexpectedEof, error.kind());
+
+    buffer[0] ^= 1;
+    let error = TrainingDataset::read_from(&buffer[..]).unwrap_err();
+    assert_eq!(std::io::ErrorKind::InvalidData, error.kind());
+}
@@ -0,0 +1,249 @@
+use super::*;
+
+fn verify(solution: &StateMap<StateAndStats>, root: State) -> VerificationReport {
+    verify_solution(solution, root, &mut SilentObserver)
+}
+
+/// Returns `best_child` with stats that imply a different parent outcome.
+fn with_wrong_stats(best_child: StateAndStats) -> StateAndStats {
+    let wrong_outcome = if best_child.parent_outcome() == Outcome::win_in(1) {
+        Outcome::win_in(0)
+    } else {
+        Outcome::loss_in(0)
+    };
+    best_child
+        .state()
+        .with_stats(StateStats::new(wrong_outcome, 0))
+}
+
+#[test]
+fn correct_solutions_are_valid() {
+    for root in SMALL_ROOTS {
+        let solution = solution(root);
+        let mut observer = PlainTextObserver::new(vec![]);
+        let report = verify_solution(&solution, root, &mut observer);
+
+        assert!(report.is_valid(), "{}", report.violations[0]);
+        assert!(report.violations.is_empty());
+        assert_eq!(solution.len(), report.checked_state_count);
+
+        let log = String::from_utf8(observer.into_inner()).unwrap();
+        assert!(log.contains(&format!(
+            "Completed verification phase: processed {} states.",
+            solution.len()
+        )));
+    }
+}
+
+#[test]
+fn verifier_detects_missing_root() {
+    for root in SMALL_ROOTS {
+        let mut solution = solution(root);
+        solution.add(root, StateAndStats::null());
+
+        let report = verify(&solution, root);
+        assert_eq!(
+            vec![SolutionViolation::MissingState {
+                state: root,
+                parent: None,
+            }],
+            report.violations
+        );
+    }
+}
+
+#[test]
+fn verifier_detects_missing_state() {
+    let mut checked_count = 0;
+
+    for root in SMALL_ROOTS {
+        let mut solution = solution(root);
+        // Every entry is reachable from the root,
+        // so every entry other than the root's is some entry's child.
+        let Some((missing, _)) = solution
+            .to_sorted_vec()
+            .into_iter()
+            .find(|&(state, _)| state != root)
+        else {
+            continue;
+        };
+        solution.add(missing, StateAndStats::null());
+
+        let report = verify(&solution, root);
+        assert!(report.violations.iter().any(|violation| matches!(
+            violation,
+            SolutionViolation::MissingState { state, parent: Some(_) } if *state == missing
+        )));
+        checked_count += 1;
+    }
+
+    assert!(checked_count > 0);
+}
+
+#[test]
+fn missing_best_child_is_only_reported_as_missing() {
+    let mut checked_count = 0;
+
+    for root in SMALL_ROOTS {
+        let solution = solution(root);
+        for (parent, best_child) in solution.to_sorted_vec() {
+            for child in parent.children() {
+                if solution.get(child).is_null() {
+                    continue;
+                }
+
+                // Point the parent at the child without changing its outcome,
+                // so that removing the child's entry is the only corruption.
+                let mut corrupted = solution.clone();
+                corrupted.add(parent, child.with_stats(best_child.stats()));
+                corrupted.add(child, StateAndStats::null());
+
+                let report = verify(&corrupted, root);
+                assert_eq!(
+                    vec![SolutionViolation::MissingState {
+                        state: child,
+                        parent: Some(parent),
+                    }],
+                    report.violations
+                );
+                checked_count += 1;
+            }
+        }
+    }
+
+    assert!(checked_count > 0);
+}
+
+#[test]
+fn verifier_detects_terminal_entry() {
+    for root in SMALL_ROOTS {
+        let mut solution = solution(root);
+        let terminal = root
+            .children()
+            .into_iter()
+            .find(|child| child.terminality() != Terminality::Nonterminal)
+            .unwrap();
+        solution.add(terminal, solution.get(root));
+
+        let report = verify(&solution, root);
+        assert_eq!(
+            vec![SolutionViolation::TerminalStateHasEntry { state: terminal }],
+            report.violations
+        );
+    }
+}
+
+#[test]
+fn verifier_detects_non_child() {
+    for (i, root) in SMALL_ROOTS.into_iter().enumerate() {
+        let mut solution = solution(root);
+        let other_root = SMALL_ROOTS[(i + 1) % SMALL_ROOTS.len()];
+        let foreign_best_child = self::solution(other_root).get(other_root);
+        assert!(!root.children().contains(&foreign_best_child.state()));
+        solution.add(root, foreign_best_child);
+
+        let report = verify(&solution, root);
+        assert!(report.violations.contains(&SolutionViolation::NotAChild {
+            parent: root,
+            best_child: foreign_best_child.state(),
+        }));
+    }
+}
+
+#[test]
+fn verifier_detects_outcome_mismatch() {
+    for root in SMALL_ROOTS {
+        let mut solution = solution(root);
+        let best_child = solution.get(root);
+        let wrong = with_wrong_stats(best_child);
+        solution.add(root, wrong);
+
+        let report = verify(&solution, root);
+        assert!(report.violations.iter().any(|violation| matches!(
+            violation,
+            SolutionViolation::OutcomeMismatch { parent, stored_outcome, child_outcome, .. }
+                if *parent == root
+                    && *stored_outcome == wrong.parent_outcome()
+                    && child_outcome.invert().delay_by_one() == best_child.parent_outcome()
+        )));
+    }
+}
+
+#[test]
+fn verifier_detects_better_child() {
+    let mut checked_count = 0;
+
+    for root in SMALL_ROOTS {
+        let solution = solution(root);
+        for (parent, best_child) in solution.to_sorted_vec() {
+            let best_outcome = best_child.parent_outcome();
+            for child in parent.children() {
+                let child_stats = match child.terminality() {
+                    Terminality::Loss => StateStats::IMMEDIATE_LOSS,
+                    Terminality::Win => StateStats::IMMEDIATE_WIN,
+                    Terminality::Nonterminal => {
+                        StateStats::new(solution.get(child).parent_outcome(), 0)
+                    }
+                };
+                let worse_child = child.with_stats(child_stats);
+                if worse_child.parent_outcome() >= best_outcome {
+                    continue;
+                }
+
+                let mut corrupted = solution.clone();
+                corrupted.add(parent, worse_child);
+
+                let report = verify(&corrupted, root);
+                assert!(report
+                    .violations
+                    .contains(&SolutionViolation::BetterChildExists {
+                        parent,
+                        better_child: best_child.state(),
+                        stored_outcome: worse_child.parent_outcome(),
+                        better_outcome: best_outcome,
+                    }));
+                checked_count += 1;
+            }
+        }
+    }
+
+    assert!(checked_count > 0);
+}
+
+#[test]
+fn verifier_caps_stored_violations() {
+    let mut solution = StateMap::empty();
+    let mut terminal_count = 0;
+    fuzz(1_000, |state| {
+        if state.terminality() != Terminality::Nonterminal
+            && !solution
+                .add(state, StateAndStats::new(state, StateStats::IMMEDIATE_LOSS))
+                .did_addend_already_exist
+        {
+            terminal_count += 1;
+        }
+    });
+    assert!(terminal_count > MAX_STORED_VIOLATIONS as u64);
+
+    let report = verify(&solution, State::initial());
+    // The initial state has no entry, which is also a violation.
+    assert_eq!(terminal_count + 1, report.violation_count);
+    assert_eq!(MAX_STORED_VIOLATIONS, report.violations.len());
+}
+
+#[test]
+fn violation_display_shows_states() {
+    let root = SMALL_ROOTS[0];
+    let violation = SolutionViolation::MissingState {
+        state: root,
+        parent: None,
+    };
+
+    assert_eq!(
+        format!(
+            "Nonterminal root has no entry.\n\nSTATE:\n\n{}",
+            root.pretty()
+        ),
+        violation.to_string()
+    );
+}
@@ -0,0 +1,62 @@
+use super::*;
+
+#[test]
+fn wdl_table_agrees_with_stats() {
+    for root in SMALL_ROOTS {
+        let map = solve(root);
+        let table = wdl_table(&map, &mut SilentObserver);
+
+        map.visit_in_key_order(|state, stats| {
+            assert_eq!(Some(stats.wdl()), table.get(state));
+        });
+    }
+}
+
+#[test]
+fn wdl_table_from_best_child_map_agrees_with_stats() {
+    for root in SMALL_ROOTS {
+        let map = solve(root);
+        let table = wdl_table_from_best_child_map(
+            &best_child_map(&map, &mut SilentObserver),
+            &mut SilentObserver,
+        );
+
+        map.visit_in_key_order(|state, stats| {
+            assert_eq!(Some(stats.wdl()), table.get(state));
+        });
+    }
+}
+
+#[test]
+fn wdl_table_omits_absent_states() {
+    let map = solve(SMALL_ROOTS[0]);
+    let table = wdl_table(&map, &mut SilentObserver);
+
+    assert_eq!(None, table.get(State::initial()));
+}
+
+#[test]
+fn wdl_table_add_overwrites_neighbors_correctly() {
+    let mut table = WdlTable::empty();
+    let states = [0, 1, 2, 3, 4].map(State::unrank);
+
+    for (state, wdl) in states
+        .into_iter()
+        .zip([Wdl::Win, Wdl::Loss, Wdl::Draw, Wdl::Win])
+    {
+        table.add(state, wdl);
+    }
+    table.add(states[1], Wdl::Draw);
+
+    assert_eq!(Some(Wdl::Win), table.get(states[0]));
+    assert_eq!(Some(Wdl::Draw), table.get(states[1]));
+    assert_eq!(Some(Wdl::Draw), table.get(states[2]));
+    assert_eq!(Some(Wdl::Win), table.get(states[3]));
+    assert_eq!(None, table.get(states[4]));
+}
+
+#[test]
+fn wdl_table_rejects_wrong_length() {
+    let bytes = vec![0; 100];
+    assert!(WdlTable::read_from(bytes.as_slice()).is_err());
+}
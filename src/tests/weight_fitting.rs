@@ -0,0 +1,116 @@
+use super::*;
+
+#[test]
+fn weights_round_trip_through_arrays() {
+    let weights = EvaluationWeights::DEFAULT;
+    assert_eq!(weights, EvaluationWeights::from_array(weights.to_array()));
+}
+
+#[test]
+fn empty_fit_has_zero_weights() {
+    let fit = EvaluationFitter::new().fit();
+    assert_eq!(0, fit.sample_count);
+    assert_eq!(
+        EvaluationWeights::from_array([0; FEATURE_COUNT]),
+        fit.weights()
+    );
+}
+
+#[test]
+fn fit_recovers_exact_linear_results() {
+    // Every sample's result is exactly its chick count,
+    // so the fit should weigh chicks by 1 and everything else by 0.
+    let mut fitter = EvaluationFitter::new();
+    fuzz(1_000, |state| {
+        if state.is_terminal() {
+            return;
+        }
+
+        let chick = EvaluationFeatures::of(state).chick;
+        let wdl = match chick {
+            1 => Wdl::Win,
+            0 => Wdl::Draw,
+            -1 => Wdl::Loss,
+            _ => return,
+        };
+        fitter.add_sample(state, wdl);
+    });
+
+    let fit = fitter.fit();
+    assert!(fit.sample_count > 0);
+    assert!(fit.mean_squared_error < 1e-6);
+
+    let mut expected = [0; FEATURE_COUNT];
+    expected[0] = FITTED_WEIGHT_SCALE as i32;
+    assert_eq!(EvaluationWeights::from_array(expected), fit.weights());
+}
+
+#[test]
+fn fitted_weights_agree_with_their_labels() {
+    // Labelling samples with the default evaluation's sign
+    // should produce weights that mostly agree with that sign.
+    let mut fitter = EvaluationFitter::new();
+    let mut states = vec![];
+    fuzz(1_000, |state| {
+        if state.is_terminal() {
+            return;
+        }
+
+        let wdl = match evaluate(state) {
+            1.. => Wdl::Win,
+            0 => Wdl::Draw,
+            _ => Wdl::Loss,
+        };
+        fitter.add_sample(state, wdl);
+        states.push(state);
+    });
+
+    let weights = fitter.fit().weights();
+    let agreement_count = states
+        .iter()
+        .filter(|&&state| evaluate(state).signum() == weights.evaluate(state).signum())
+        .count();
+    assert!(agreement_count * 10 > states.len() * 8);
+}
+
+#[test]
+fn fit_samples_every_nonterminal_state_of_small_roots() {
+    for root in SMALL_ROOTS {
+        let stats_map = solve(root);
+        let mut nonterminal_count = 0;
+        stats_map.visit_in_key_order(|state, _| {
+            if !state.is_terminal() {
+                nonterminal_count += 1;
+            }
+        });
+
+        let mut observer = PlainTextObserver::new(vec![]);
+        let fit = fit_evaluation_weights(&stats_map, 1, &mut observer);
+        assert_eq!(nonterminal_count, fit.sample_count);
+        assert!(fit.mean_squared_error.is_finite());
+
+        let log = String::from_utf8(observer.into_inner()).unwrap();
+        assert!(log.contains(&format!(
+            "Completed weight_fitting phase: processed {} states.",
+            stats_map.len()
+        )));
+    }
+}
+
+#[test]
+fn best_child_map_fit_agrees_with_stats_map_fit() {
+    for root in SMALL_ROOTS {
+        let stats_map = solve(root);
+        let best_child_map = best_child_map(&stats_map, &mut SilentObserver);
+
+        for sample_interval in [1, 3] {
+            let expected = fit_evaluation_weights(&stats_map, sample_interval, &mut SilentObserver);
+            let fit = fit_evaluation_weights_from_best_child_map(
+                &best_child_map,
+                sample_interval,
+                &mut SilentObserver,
+            );
+            assert_eq!(expected, fit);
+        }
+    }
+}
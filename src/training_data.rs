@@ -0,0 +1,305 @@
+use super::*;
+
+use crate::prng::SplitMix64;
+
+use std::io::{self, Read, Write};
+
+/// The first 8 bytes of every training data file.
+pub const TRAINING_DATA_MAGIC: [u8; 8] = *b"DBTTRAIN";
+
+/// The number of bytes in each training data record.
+pub const TRAINING_RECORD_LEN: usize = 35;
+
+/// The number of board planes per sample:
+/// one per species (lion, chick, hen, elephant, giraffe) per owner.
+pub const PLANE_COUNT: usize = 10;
+
+/// The number of hand counts per sample:
+/// one per droppable species (chick, elephant, giraffe) per owner.
+pub const HAND_COUNT_COUNT: usize = 6;
+
+/// A labelled position for training a model.
+///
+/// The planes and hand counts describe the board from the perspective
+/// of _owner 0_, whose home row is row 0.
+/// If the sample is not flipped, owner 0 is the player to move
+/// (i.e., the active player of `state`).
+/// If it is flipped, the board is rotated 180 degrees
+/// and owner 1 is the player to move.
+/// The labels are always from the perspective of the player to move.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TrainingSample {
+    pub state: State,
+    pub is_flipped: bool,
+
+    /// The outcome of `state` for the player to move.
+    pub outcome: Outcome,
+
+    /// The index of the best move,
+    /// where moves are ordered by `State::visit_children`.
+    pub best_move_index: u8,
+}
+
+/// A reproducible sample of solved positions.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TrainingDataset {
+    /// The seed the samples were drawn with.
+    pub seed: u64,
+
+    /// The samples, sorted by state.
+    pub samples: Vec<TrainingSample>,
+}
+
+impl TrainingSample {
+    /// Returns the owner (0 or 1) of the player to move.
+    pub const fn side_to_move(self) -> u8 {
+        self.is_flipped as u8
+    }
+
+    pub const fn wdl(self) -> Wdl {
+        self.outcome.wdl()
+    }
+
+    /// Returns the number of plies until the game ends,
+    /// or zero for draws.
+    pub const fn distance(self) -> u8 {
+        if self.outcome.0 > 0 {
+            (Outcome::win_in(0).0 - self.outcome.0) as u8
+        } else if self.outcome.0 < 0 {
+            (self.outcome.0 - Outcome::loss_in(0).0) as u8
+        } else {
+            0
+        }
+    }
+
+    /// Returns one bitboard per species and owner,
+    /// in the order lion, chick, hen, elephant, giraffe
+    /// (first for owner 0, then for owner 1).
+    /// Bit `row * 3 + column` is set if the piece is on that square.
+    pub fn planes(self) -> [u16; PLANE_COUNT] {
+        let state = self.oriented_builder();
+        let board = state.board();
+        let mut planes = [0; PLANE_COUNT];
+
+        for row in 0..4 {
+            for column in 0..3 {
+                let coords = Coords((row << 2) | column);
+                let square = (board.0 >> coords.board_offset()) & 0b1111;
+                let piece = Piece((square & 0b111) as u8);
+                let owner = (square >> 3) as usize;
+
+                let species = match piece {
+                    Piece(0b000) => continue,
+                    Piece::AMBIGUOUS_LION => 0,
+                    Piece::CHICK0 | Piece::CHICK1 => {
+                        if Actor(piece).is_promoted(state) {
+                            2
+                        } else {
+                            1
+                        }
+                    }
+                    Piece::ELEPHANT0 | Piece::ELEPHANT1 => 3,
+                    _ => 4,
+                };
+
+                planes[owner * 5 + species] |= 1 << (row * 3 + column);
+            }
+        }
+
+        planes
+    }
+
+    /// Returns the number of pieces in each hand,
+    /// in the order chick, elephant, giraffe
+    /// (first for owner 0, then for owner 1).
+    pub fn hand_counts(self) -> [u8; HAND_COUNT_COUNT] {
+        let state = self.oriented_builder();
+        let mut counts = [0; HAND_COUNT_COUNT];
+
+        for (piece, species) in [
+            (Nonlion::CHICK0, 0),
+            (Nonlion::CHICK1, 0),
+            (Nonlion::ELEPHANT0, 1),
+            (Nonlion::ELEPHANT1, 1),
+            (Nonlion::GIRAFFE0, 2),
+            (Nonlion::GIRAFFE1, 2),
+        ] {
+            if piece.is_in_hand(state) {
+                let owner = if piece.is_active(state) { 0 } else { 1 };
+                counts[owner * 3 + species] += 1;
+            }
+        }
+
+        counts
+    }
+
+    /// Returns the state with owner 0 as its active player.
+    fn oriented_builder(self) -> StateBuilder {
+        let state = self.state.into_builder();
+        if self.is_flipped {
+            state.invert_active_player()
+        } else {
+            state
+        }
+    }
+
+    fn to_record(self) -> [u8; TRAINING_RECORD_LEN] {
+        let mut record = [0; TRAINING_RECORD_LEN];
+        record[0..5].copy_from_slice(&self.state.0.to_le_bytes()[0..5]);
+        record[5] = self.side_to_move();
+        for (i, plane) in self.planes().into_iter().enumerate() {
+            record[6 + 2 * i..8 + 2 * i].copy_from_slice(&plane.to_le_bytes());
+        }
+        record[26..32].copy_from_slice(&self.hand_counts());
+        record[32] = match self.wdl() {
+            Wdl::Win => 1,
+            Wdl::Draw => 0,
+            Wdl::Loss => -1i8 as u8,
+        };
+        record[33] = self.distance();
+        record[34] = self.best_move_index;
+        record
+    }
+}
+
+impl TrainingDataset {
+    /// Draws `sample_count` positions uniformly at random
+    /// (without replacement) from the entries of `solution`,
+    /// and flips each one with probability 1/2.
+    /// The same `solution` and `seed` always give the same dataset.
+    ///
+    /// If `solution` has fewer entries than `sample_count`,
+    /// every entry is sampled.
+    pub fn sample(
+        solution: &StateMap<StateAndStats>,
+        sample_count: usize,
+        seed: u64,
+        observer: &mut (impl ProgressObserver + ?Sized),
+    ) -> Self {
+        let mut rng = SplitMix64::new(seed);
+        let mut tracker =
+            PhaseTracker::resume(observer, SolverPhase::TrainingData, Some(solution.len()), 0);
+
+        // Reservoir sampling (Algorithm R).
+        let mut reservoir: Vec<(State, bool)> =
+            Vec::with_capacity(sample_count.min(solution.len() as usize));
+        let mut seen_count: u64 = 0;
+        solution.visit_in_key_order(|state, _| {
+            let is_flipped = rng.next() & 1 != 0;
+            if reservoir.len() < sample_count {
+                reservoir.push((state, is_flipped));
+            } else {
+                let index = rng.below(seen_count + 1);
+                if index < sample_count as u64 {
+                    reservoir[index as usize] = (state, is_flipped);
+                }
+            }
+            seen_count += 1;
+
+            tracker.tick(None);
+        });
+
+        tracker.finish(None);
+
+        let mut samples: Vec<TrainingSample> = reservoir
+            .into_iter()
+            .map(|(state, is_flipped)| {
+                let best_child = solution.get(state);
+                let best_move_index = state
+                    .children()
+                    .iter()
+                    .position(|&child| child == best_child.state())
+                    .expect("The best child should be a child.");
+
+                TrainingSample {
+                    state,
+                    is_flipped,
+                    outcome: best_child.parent_outcome(),
+                    best_move_index: best_move_index as u8,
+                }
+            })
+            .collect();
+        samples.sort_unstable_by_key(|sample| sample.state);
+
+        Self { seed, samples }
+    }
+
+    /// The file starts with a 16-byte header:
+    /// the 8-byte `TRAINING_DATA_MAGIC`
+    /// and the 8-byte seed.
+    /// It is followed by one `TRAINING_RECORD_LEN`-byte record per sample
+    /// (see `docs/spec.md` for the record layout).
+    /// Everything is stored in little-endian order.
+    pub fn write_to(&self, mut writer: impl Write) -> io::Result<()> {
+        let mut header = [0; 16];
+        header[0..8].copy_from_slice(&TRAINING_DATA_MAGIC);
+        header[8..16].copy_from_slice(&self.seed.to_le_bytes());
+        writer.write_all(&header)?;
+
+        for sample in &self.samples {
+            writer.write_all(&sample.to_record())?;
+        }
+
+        Ok(())
+    }
+
+    /// Only the position, side to move, outcome, and best move index
+    /// are read; the other fields are derived from them.
+    pub fn read_from(mut reader: impl Read) -> io::Result<Self> {
+        let mut header = [0; 16];
+        if solution_file::read_up_to(&mut reader, &mut header)? < header.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "The training data ends before its header does.",
+            ));
+        }
+
+        if header[0..8] != TRAINING_DATA_MAGIC {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "The file is not a training dataset.",
+            ));
+        }
+
+        let seed = u64::from_le_bytes(header[8..16].try_into().unwrap());
+
+        let mut samples = vec![];
+        let mut record = [0; TRAINING_RECORD_LEN];
+        loop {
+            let record_len = solution_file::read_up_to(&mut reader, &mut record)?;
+
+            if record_len == 0 {
+                return Ok(Self { seed, samples });
+            }
+
+            if record_len < record.len() {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "The training data ends with an incomplete record.",
+                ));
+            }
+
+            let mut state_bytes = [0; 8];
+            state_bytes[0..5].copy_from_slice(&record[0..5]);
+            let distance = record[33];
+            let outcome = match record[32] as i8 {
+                1 => Outcome::win_in(distance),
+                0 => Outcome::DRAW,
+                -1 => Outcome::loss_in(distance),
+                _ => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "The training data has an invalid WDL label.",
+                    ))
+                }
+            };
+
+            samples.push(TrainingSample {
+                state: State(u64::from_le_bytes(state_bytes)),
+                is_flipped: record[5] != 0,
+                outcome,
+                best_move_index: record[34],
+            });
+        }
+    }
+}
@@ -0,0 +1,237 @@
+use super::*;
+
+use crate::pretty::*;
+
+use std::fmt::{self, Display, Formatter};
+
+/// `verify_solution` stores at most this many violations,
+/// though it counts all of them.
+pub const MAX_STORED_VIOLATIONS: usize = 100;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerificationReport {
+    /// The number of entries in the solution.
+    pub checked_state_count: u64,
+
+    pub violation_count: u64,
+
+    /// The first `MAX_STORED_VIOLATIONS` violations,
+    /// in the order of their states.
+    pub violations: Vec<SolutionViolation>,
+}
+
+/// A way in which a best child map disagrees with the game tree.
+/// Outcomes are from the perspective of the parent's active player.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SolutionViolation {
+    TerminalStateHasEntry {
+        state: State,
+    },
+
+    /// The stored best child is not a child of the parent.
+    NotAChild {
+        parent: State,
+        best_child: State,
+    },
+
+    /// The parent's outcome (implied by the stats stored with its best child)
+    /// is not the best child's own outcome, inverted and delayed by one.
+    OutcomeMismatch {
+        parent: State,
+        best_child: State,
+        stored_outcome: Outcome,
+        child_outcome: Outcome,
+    },
+
+    /// Moving to `better_child` gives the parent a better outcome
+    /// than moving to the stored best child.
+    BetterChildExists {
+        parent: State,
+        better_child: State,
+        stored_outcome: Outcome,
+        better_outcome: Outcome,
+    },
+
+    /// A nonterminal state reachable from the root has no entry.
+    /// `parent` is `None` if the state is the root.
+    MissingState {
+        state: State,
+        parent: Option<State>,
+    },
+}
+
+impl VerificationReport {
+    pub fn is_valid(&self) -> bool {
+        self.violation_count == 0
+    }
+}
+
+impl Display for SolutionViolation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match *self {
+            SolutionViolation::TerminalStateHasEntry { state } => write!(
+                f,
+                "Terminal state has an entry.\n\nSTATE:\n\n{}",
+                state.pretty()
+            ),
+
+            SolutionViolation::NotAChild { parent, best_child } => write!(
+                f,
+                "Best child is not a child of its parent.\n\nPARENT:\n\n{}\n\nBEST CHILD:\n\n{}",
+                parent.pretty(),
+                best_child.pretty()
+            ),
+
+            SolutionViolation::OutcomeMismatch {
+                parent,
+                best_child,
+                stored_outcome,
+                child_outcome,
+            } => write!(
+                f,
+                "Parent outcome {} does not match best child outcome {}.\n\nPARENT:\n\n{}\n\nBEST CHILD:\n\n{}",
+                stored_outcome.pretty(),
+                child_outcome.pretty(),
+                parent.pretty(),
+                best_child.pretty()
+            ),
+
+            SolutionViolation::BetterChildExists {
+                parent,
+                better_child,
+                stored_outcome,
+                better_outcome,
+            } => write!(
+                f,
+                "Parent outcome {} is worse than {}, which another child gives.\n\nPARENT:\n\n{}\n\nBETTER CHILD:\n\n{}",
+                stored_outcome.pretty(),
+                better_outcome.pretty(),
+                parent.pretty(),
+                better_child.pretty()
+            ),
+
+            SolutionViolation::MissingState {
+                state,
+                parent: None,
+            } => write!(
+                f,
+                "Nonterminal root has no entry.\n\nSTATE:\n\n{}",
+                state.pretty()
+            ),
+
+            SolutionViolation::MissingState {
+                state,
+                parent: Some(parent),
+            } => write!(
+                f,
+                "Nonterminal state has no entry.\n\nSTATE:\n\n{}\n\nPARENT:\n\n{}",
+                state.pretty(),
+                parent.pretty()
+            ),
+        }
+    }
+}
+
+/// Checks that `solution` is a correct best child map
+/// of every state reachable from `root`.
+///
+/// For every entry, this checks that the parent is nonterminal,
+/// that the best child is one of its children,
+/// that the parent's outcome is the best child's outcome
+/// inverted and delayed by one,
+/// and that no other child gives a better outcome.
+/// It also checks that the root and every nonterminal child
+/// of every entry has an entry,
+/// which (by induction) means that every nonterminal state
+/// reachable from the root has one.
+///
+/// Entries for unreachable states are checked like any other,
+/// but are not reported just for existing.
+pub fn verify_solution(
+    solution: &StateMap<StateAndStats>,
+    root: State,
+    observer: &mut (impl ProgressObserver + ?Sized),
+) -> VerificationReport {
+    let mut report = VerificationReport {
+        checked_state_count: 0,
+        violation_count: 0,
+        violations: vec![],
+    };
+    let mut record = |violation| {
+        report.violation_count += 1;
+        if report.violations.len() < MAX_STORED_VIOLATIONS {
+            report.violations.push(violation);
+        }
+    };
+
+    if root.terminality() == Terminality::Nonterminal && solution.get(root).is_null() {
+        record(SolutionViolation::MissingState {
+            state: root,
+            parent: None,
+        });
+    }
+
+    let mut tracker =
+        PhaseTracker::resume(observer, SolverPhase::Verification, Some(solution.len()), 0);
+    solution.visit_in_key_order(|parent, best_child| {
+        verify_entry(solution, parent, best_child, &mut record);
+        tracker.tick(None);
+    });
+
+    report.checked_state_count = tracker.processed();
+    tracker.finish(None);
+    report
+}
+
+fn verify_entry(
+    solution: &StateMap<StateAndStats>,
+    parent: State,
+    best_child: StateAndStats,
+    record: &mut impl FnMut(SolutionViolation),
+) {
+    if parent.terminality() != Terminality::Nonterminal {
+        record(SolutionViolation::TerminalStateHasEntry { state: parent });
+        return;
+    }
+
+    let stored_outcome = best_child.parent_outcome();
+    let best_child = best_child.state();
+
+    let mut is_best_child_a_child = false;
+    parent.visit_children(|child| {
+        if child == best_child {
+            is_best_child_a_child = true;
+        }
+
+        let Some(child_outcome) = solution.outcome(child) else {
+            record(SolutionViolation::MissingState {
+                state: child,
+                parent: Some(parent),
+            });
+            return;
+        };
+        let outcome = child_outcome.invert().delay_by_one();
+
+        if child == best_child {
+            if outcome != stored_outcome {
+                record(SolutionViolation::OutcomeMismatch {
+                    parent,
+                    best_child,
+                    stored_outcome,
+                    child_outcome,
+                });
+            }
+        } else if outcome > stored_outcome {
+            record(SolutionViolation::BetterChildExists {
+                parent,
+                better_child: child,
+                stored_outcome,
+                better_outcome: outcome,
+            });
+        }
+    });
+
+    if !is_best_child_a_child {
+        record(SolutionViolation::NotAChild { parent, best_child });
+    }
+}
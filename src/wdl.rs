@@ -0,0 +1,164 @@
+use super::*;
+
+use std::io::{self, Read, Write};
+
+/// A game-theoretic value, without the distance.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Wdl {
+    Loss = 0b01,
+    Draw = 0b10,
+    Win = 0b11,
+}
+
+/// A table that stores one `Wdl` per constructible state,
+/// indexed by `State::rank`.
+///
+/// Each entry is 2 bits wide:
+/// entry `i` is stored in bits `2 * (i % 4)` and `2 * (i % 4) + 1`
+/// of byte `i / 4`.
+/// The entry `0b00` means the state was not in the table's source
+/// (e.g., because it is unreachable).
+#[derive(Clone, Debug)]
+pub struct WdlTable {
+    raw: Box<[u8]>,
+}
+
+impl Outcome {
+    pub const fn wdl(self) -> Wdl {
+        if self.0 > 0 {
+            Wdl::Win
+        } else if self.0 < 0 {
+            Wdl::Loss
+        } else {
+            Wdl::Draw
+        }
+    }
+}
+
+impl StateStats {
+    /// Unsolved states are draws.
+    pub const fn wdl(self) -> Wdl {
+        match self.best_outcome() {
+            Some(outcome) => outcome.wdl(),
+            None => Wdl::Draw,
+        }
+    }
+}
+
+impl Wdl {
+    const fn from_bits(bits: u8) -> Option<Self> {
+        match bits {
+            0b01 => Some(Wdl::Loss),
+            0b10 => Some(Wdl::Draw),
+            0b11 => Some(Wdl::Win),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub const fn invert(self) -> Self {
+        match self {
+            Wdl::Loss => Wdl::Win,
+            Wdl::Draw => Wdl::Draw,
+            Wdl::Win => Wdl::Loss,
+        }
+    }
+}
+
+impl WdlTable {
+    /// The size (in bytes) of every table.
+    pub const BYTE_LEN: usize = State::RANK_COUNT.div_ceil(4) as usize;
+
+    pub fn empty() -> Self {
+        Self {
+            raw: vec![0; Self::BYTE_LEN].into_boxed_slice(),
+        }
+    }
+
+    /// Returns `None` if the state was not added to the table.
+    pub fn get(&self, state: State) -> Option<Wdl> {
+        let rank = state.rank();
+        let byte = self.raw[(rank / 4) as usize];
+        Wdl::from_bits((byte >> (2 * (rank % 4))) & 0b11)
+    }
+
+    pub fn add(&mut self, state: State, wdl: Wdl) {
+        let rank = state.rank();
+        let byte = &mut self.raw[(rank / 4) as usize];
+        let shift = 2 * (rank % 4);
+        *byte = (*byte & !(0b11 << shift)) | ((wdl as u8) << shift);
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.raw
+    }
+
+    pub fn write_to(&self, mut writer: impl Write) -> io::Result<()> {
+        writer.write_all(&self.raw)
+    }
+
+    pub fn read_from(mut reader: impl Read) -> io::Result<Self> {
+        let mut raw = Vec::with_capacity(Self::BYTE_LEN);
+        reader.read_to_end(&mut raw)?;
+
+        if raw.len() != Self::BYTE_LEN {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "A WDL table must be exactly {} bytes long, but this one is {} bytes long.",
+                    Self::BYTE_LEN,
+                    raw.len()
+                ),
+            ));
+        }
+
+        Ok(Self {
+            raw: raw.into_boxed_slice(),
+        })
+    }
+}
+
+pub fn wdl_table(
+    map: &StateMap<StateStats>,
+    observer: &mut (impl ProgressObserver + ?Sized),
+) -> WdlTable {
+    let mut out = WdlTable::empty();
+    let mut tracker = PhaseTracker::resume(observer, SolverPhase::WdlTable, Some(map.len()), 0);
+
+    map.visit_in_key_order(|state, stats| {
+        out.add(state, stats.wdl());
+
+        tracker.tick(None);
+    });
+
+    tracker.finish(None);
+
+    out
+}
+
+/// Since terminal states are absent from a best child map,
+/// we recover them by visiting the children of every parent.
+pub fn wdl_table_from_best_child_map(
+    map: &StateMap<StateAndStats>,
+    observer: &mut (impl ProgressObserver + ?Sized),
+) -> WdlTable {
+    let mut out = WdlTable::empty();
+    let mut tracker = PhaseTracker::resume(observer, SolverPhase::WdlTable, Some(map.len()), 0);
+
+    map.visit_in_key_order(|parent, best_child| {
+        out.add(parent, best_child.stats().wdl().invert());
+
+        parent.visit_children(|child| match child.terminality() {
+            Terminality::Loss => out.add(child, Wdl::Loss),
+            Terminality::Win => out.add(child, Wdl::Win),
+            Terminality::Nonterminal => {}
+        });
+
+        tracker.tick(None);
+    });
+
+    tracker.finish(None);
+
+    out
+}
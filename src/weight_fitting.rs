@@ -0,0 +1,253 @@
+use super::*;
+
+use std::fmt::{self, Display, Formatter};
+
+/// `EvaluationFit::weights` scales the fitted coefficients by this,
+/// so an evaluation of `FITTED_WEIGHT_SCALE` predicts a certain win.
+pub const FITTED_WEIGHT_SCALE: f64 = 1000.0;
+
+/// A small ridge penalty that keeps the normal equations solvable
+/// when a feature never varies in the samples.
+const RIDGE: f64 = 1e-9;
+
+/// Fits evaluation weights by least squares,
+/// so that the evaluation predicts each sample's result
+/// (1 for a win, 0 for a draw, and -1 for a loss,
+/// from the perspective of the active player).
+///
+/// There is no intercept,
+/// since inverting the active player negates both the features
+/// and the result.
+///
+/// Only the normal equations are stored,
+/// so memory use does not depend on the sample count.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EvaluationFitter {
+    /// The sum of `features * features^T` over the samples.
+    gram: [[f64; FEATURE_COUNT]; FEATURE_COUNT],
+
+    /// The sum of `features * result` over the samples.
+    moment: [f64; FEATURE_COUNT],
+
+    /// The sum of `result^2` over the samples.
+    result_square_sum: f64,
+
+    sample_count: u64,
+}
+
+/// The result of `EvaluationFitter::fit`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EvaluationFit {
+    /// The fitted weight of each feature,
+    /// in the order of `EvaluationFeatures::to_array`.
+    pub coefficients: [f64; FEATURE_COUNT],
+
+    pub sample_count: u64,
+
+    /// The mean squared difference between
+    /// the predicted and actual results.
+    pub mean_squared_error: f64,
+}
+
+impl EvaluationFitter {
+    pub fn new() -> Self {
+        Self {
+            gram: [[0.0; FEATURE_COUNT]; FEATURE_COUNT],
+            moment: [0.0; FEATURE_COUNT],
+            result_square_sum: 0.0,
+            sample_count: 0,
+        }
+    }
+
+    /// `state` must be nonterminal.
+    pub fn add_sample(&mut self, state: State, wdl: Wdl) {
+        let result = match wdl {
+            Wdl::Win => 1.0,
+            Wdl::Draw => 0.0,
+            Wdl::Loss => -1.0,
+        };
+        let features = EvaluationFeatures::of(state).to_array().map(f64::from);
+
+        for (i, &feature_i) in features.iter().enumerate() {
+            for (j, &feature_j) in features.iter().enumerate() {
+                self.gram[i][j] += feature_i * feature_j;
+            }
+            self.moment[i] += feature_i * result;
+        }
+        self.result_square_sum += result * result;
+        self.sample_count += 1;
+    }
+
+    pub fn sample_count(&self) -> u64 {
+        self.sample_count
+    }
+
+    /// Solves the normal equations.
+    pub fn fit(&self) -> EvaluationFit {
+        let mut gram = self.gram;
+        for (i, row) in gram.iter_mut().enumerate() {
+            row[i] += RIDGE * (1 + self.sample_count) as f64;
+        }
+        let coefficients = solve_linear_system(gram, self.moment);
+
+        // The sum of squared errors is
+        // `sum(result^2) - 2 * c^T * moment + c^T * gram * c`.
+        let mut squared_error_sum = self.result_square_sum;
+        for i in 0..FEATURE_COUNT {
+            squared_error_sum -= 2.0 * coefficients[i] * self.moment[i];
+            for j in 0..FEATURE_COUNT {
+                squared_error_sum += coefficients[i] * self.gram[i][j] * coefficients[j];
+            }
+        }
+
+        EvaluationFit {
+            coefficients,
+            sample_count: self.sample_count,
+            mean_squared_error: if self.sample_count == 0 {
+                0.0
+            } else {
+                squared_error_sum.max(0.0) / self.sample_count as f64
+            },
+        }
+    }
+}
+
+impl Default for EvaluationFitter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EvaluationFit {
+    /// Returns the coefficients,
+    /// scaled by `FITTED_WEIGHT_SCALE` and rounded.
+    pub fn weights(&self) -> EvaluationWeights {
+        EvaluationWeights::from_array(
+            self.coefficients
+                .map(|coefficient| (coefficient * FITTED_WEIGHT_SCALE).round() as i32),
+        )
+    }
+}
+
+impl Display for EvaluationFit {
+    /// Prints the weights as a Rust constant,
+    /// so they can be pasted into `EvaluationWeights::DEFAULT`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let weights = self.weights();
+        write!(
+            f,
+            "Fitted {} samples (mean squared error {:.4}).\n\nEvaluationWeights {{\n    chick: {},\n    hen: {},\n    elephant: {},\n    giraffe: {},\n    hand_piece: {},\n    lion_advancement: {},\n    lion_safety: {},\n    chick_advancement: {},\n}}",
+            self.sample_count,
+            self.mean_squared_error,
+            weights.chick,
+            weights.hen,
+            weights.elephant,
+            weights.giraffe,
+            weights.hand_piece,
+            weights.lion_advancement,
+            weights.lion_safety,
+            weights.chick_advancement
+        )
+    }
+}
+
+/// Fits evaluation weights to every `sample_interval`-th
+/// nonterminal state of `stats_map` (in key order).
+pub fn fit_evaluation_weights(
+    stats_map: &StateMap<StateStats>,
+    sample_interval: u64,
+    observer: &mut (impl ProgressObserver + ?Sized),
+) -> EvaluationFit {
+    assert!(sample_interval > 0, "Sample interval must be positive.");
+
+    let mut fitter = EvaluationFitter::new();
+    let mut index = 0;
+    let mut tracker = PhaseTracker::resume(
+        observer,
+        SolverPhase::WeightFitting,
+        Some(stats_map.len()),
+        0,
+    );
+
+    stats_map.visit_in_key_order(|state, stats| {
+        if !state.is_terminal() {
+            if index % sample_interval == 0 {
+                let outcome = stats.best_outcome().unwrap_or(Outcome::DRAW);
+                fitter.add_sample(state, outcome.wdl());
+            }
+            index += 1;
+        }
+
+        tracker.tick(None);
+    });
+
+    tracker.finish(None);
+
+    fitter.fit()
+}
+
+/// This is equivalent to `fit_evaluation_weights`,
+/// but reads the outcomes from a best child map.
+/// A best child map has no terminal states,
+/// so every entry is a candidate sample.
+pub fn fit_evaluation_weights_from_best_child_map(
+    best_child_map: &StateMap<StateAndStats>,
+    sample_interval: u64,
+    observer: &mut (impl ProgressObserver + ?Sized),
+) -> EvaluationFit {
+    assert!(sample_interval > 0, "Sample interval must be positive.");
+
+    let mut fitter = EvaluationFitter::new();
+    let mut tracker = PhaseTracker::resume(
+        observer,
+        SolverPhase::WeightFitting,
+        Some(best_child_map.len()),
+        0,
+    );
+
+    best_child_map.visit_in_key_order(|state, best_child| {
+        if tracker.processed().is_multiple_of(sample_interval) {
+            fitter.add_sample(state, best_child.parent_outcome().wdl());
+        }
+
+        tracker.tick(None);
+    });
+
+    tracker.finish(None);
+
+    fitter.fit()
+}
+
+/// Solves `matrix * x = vector` by Gaussian elimination with partial pivoting.
+/// `matrix` must be nonsingular.
+fn solve_linear_system(
+    mut matrix: [[f64; FEATURE_COUNT]; FEATURE_COUNT],
+    mut vector: [f64; FEATURE_COUNT],
+) -> [f64; FEATURE_COUNT] {
+    for column in 0..FEATURE_COUNT {
+        let pivot = (column..FEATURE_COUNT)
+            .max_by(|&a, &b| matrix[a][column].abs().total_cmp(&matrix[b][column].abs()))
+            .unwrap();
+        matrix.swap(column, pivot);
+        vector.swap(column, pivot);
+
+        let pivot_row = matrix[column];
+        for row in column + 1..FEATURE_COUNT {
+            let factor = matrix[row][column] / pivot_row[column];
+            for (entry, pivot_entry) in matrix[row][column..].iter_mut().zip(&pivot_row[column..]) {
+                *entry -= factor * pivot_entry;
+            }
+            vector[row] -= factor * vector[column];
+        }
+    }
+
+    let mut solution = [0.0; FEATURE_COUNT];
+    for row in (0..FEATURE_COUNT).rev() {
+        let mut sum = vector[row];
+        for k in row + 1..FEATURE_COUNT {
+            sum -= matrix[row][k] * solution[k];
+        }
+        solution[row] = sum / matrix[row][row];
+    }
+    solution
+}